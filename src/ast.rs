@@ -1,18 +1,118 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A node paired with the source line it starts on
+///
+/// The line and file are metadata only and do not take part in equality, so a parsed tree
+/// compares equal to the same tree built by hand.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    /// The wrapped node
+    pub node: T,
+    /// 1-based source line, or 0 for nodes that were not produced by the parser
+    pub line: usize,
+    /// File a top-level statement was inlined from by an import, which `line` counts in, or
+    /// `None` for the file being compiled
+    pub file: Option<Arc<str>>,
+}
+
+impl<T> Spanned<T> {
+    /// Wrap a node with its source line
+    pub fn new(node: T, line: usize) -> Self {
+        Self { node, line, file: None }
+    }
+    
+    /// Apply a function to the node, keeping the line and file
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned { node: f(self.node), line: self.line, file: self.file }
+    }
+}
+
+/// Describe a source line for messages, naming the imported file it is in, if any
+pub fn describe_line(line: usize, file: Option<&str>) -> String {
+    match file {
+        Some(file) => format!("line {} of {}", line, file),
+        None => format!("line {}", line),
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+    
+    fn deref(&self) -> &T {
+        &self.node
+    }
+}
+
+impl<T> From<T> for Spanned<T> {
+    fn from(node: T) -> Self {
+        Self::new(node, 0)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for Spanned<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.node == *other
+    }
+}
+
 /// Abstract Syntax Tree nodes for the TFI language
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     /// Print statement: bahubali(expr1, expr2, ...)
     Print(Vec<Expression>),
+    /// Print with a custom separator and ending: bahubali(expr1, expr2, sep="-", end="!")
+    ///
+    /// Holds the values, the separator and the ending; whichever was not given is " " or "\n".
+    PrintJoined(Vec<Expression>, Expression, Expression),
+    /// Print a block of text verbatim: bahubali_block { ... }
+    RawPrint(String),
+    /// JavaScript passed through unchecked: rajini { ... }
+    RawJs(String),
+    /// Print without a trailing newline: salaar(expr1, expr2, ...)
+    PrintInline(Vec<Expression>),
+    /// Clear the console: wipe()
+    Clear,
     /// Const declaration: rrr name = value
     Const(String, Expression),
+    /// Several const declarations in one statement: rrr a = 1, b = 2
+    MultiConst(Vec<(String, Expression)>),
     /// Let declaration: pushpa name = value
     Let(String, Expression),
+    /// Assignment to an existing variable: name = value
+    ///
+    /// Compound assignments such as `name -= value` are desugared to `name = name - value`.
+    Assign(String, Expression),
+    /// Increment or decrement of an existing variable: name++ / name-- (true for decrement)
+    Increment(String, bool),
     /// If statement: magadheera(condition) { ... } karthikeya { ... }
-    If(Expression, Vec<Statement>, Option<Vec<Statement>>),
+    If(Expression, Vec<Spanned<Statement>>, Option<Vec<Spanned<Statement>>>),
     /// While loop: pokiri(condition) { ... }
-    While(Expression, Vec<Statement>),
-    /// For loop: eega(init; condition; update) { ... }
-    For(Box<Statement>, Expression, Expression, Vec<Statement>),
+    While(Expression, Vec<Spanned<Statement>>),
+    /// For loop: eega(init; condition; update) { ... }, where any clause may be left empty
+    For(Option<Box<Statement>>, Option<Expression>, Option<Box<Statement>>, Vec<Spanned<Statement>>),
+    /// Counting loop over a half-open range: eega name in start..end { ... }
+    ForRange(String, Expression, Expression, Vec<Spanned<Statement>>),
+    /// Loop with a label that break statements can target: name: pokiri(...) { ... }
+    Labeled(String, Box<Statement>),
+    /// Exit the innermost loop, or the loop with the given label: aagipo; / aagipo name;
+    Break(Option<String>),
+    /// Return from the enclosing function, with an optional value: vaapas; / vaapas value;
+    Return(Option<Expression>),
+    /// Throw at runtime unless the condition holds, naming it by its source text: nammaka(x == 5)
+    Assert(Expression, String),
+    /// Expression evaluated for its side effects: a function call, or a plain for-loop update
+    Expr(Expression),
+    /// Source comment: // text
+    Comment(String),
+    /// Another file to inline, replaced by its statements before validation: import "file.tfi"
+    Import(String),
 }
 
 /// Expression nodes for the TFI language
@@ -20,12 +120,76 @@ pub enum Statement {
 pub enum Expression {
     /// Numeric literal
     Number(i32),
+    /// Floating-point literal, kept as written (e.g. 1.5, 2e-2)
+    Float(String),
     /// Variable identifier
     Identifier(String),
     /// String literal
     String(String),
     /// Binary operation: left op right
     BinaryOp(Box<Expression>, String, Box<Expression>),
+    /// Property access: object.field
+    Member(Box<Expression>, String),
+    /// Optional property access: object?.field
+    OptionalMember(Box<Expression>, String),
+    /// Parenthesized expression as written by the user: (expr)
+    Grouping(Box<Expression>),
+    /// Method call: object.method(args...)
+    MethodCall(Box<Expression>, String, Vec<Expression>),
+    /// Object literal: { key: value, ... }
+    Object(Vec<(String, Expression)>),
+    /// Character access: object[index]
+    Index(Box<Expression>, Box<Expression>),
+    /// Character access counting back from the end, written as a literal negative index: object[-n]
+    IndexFromEnd(Box<Expression>, u32),
+    /// Half-open slice: object[start..end]
+    Slice(Box<Expression>, Box<Expression>, Box<Expression>),
+    /// Value computed at compile time, with the expression it replaced: value /* original */
+    Folded(Box<Expression>, Box<Expression>),
+    /// Anonymous function with its parameters and body: fn(a, b) { vaapas a + b; }
+    Lambda(Vec<String>, Vec<Spanned<Statement>>),
+    /// Call of a function value: callee(args...)
+    Call(Box<Expression>, Vec<Expression>),
+    /// Conditional expression: condition ? then : else
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+/// Binary operators recognized by TFI
+///
+/// `&&` has no TFI syntax; it is only produced by `--auto-chain` when expanding `0 < x < 10`.
+pub const BINARY_OPERATORS: &[&str] = &["+", "-", "*", "/", "~/", ">", "<", ">=", "<=", "==", "!=", "&&"];
+
+/// Binding strength of a binary operator, following JavaScript: higher binds tighter
+pub fn precedence(op: &str) -> u8 {
+    match op {
+        "*" | "/" | "~/" => 4,
+        "+" | "-" => 3,
+        ">" | "<" | ">=" | "<=" => 2,
+        "==" | "!=" => 1,
+        _ => 0,
+    }
+}
+
+/// Comparison operators, which `--auto-chain` expands when chained
+pub const COMPARISON_OPERATORS: &[&str] = &[">", "<", ">=", "<=", "==", "!="];
+
+/// String methods recognized by TFI, paired with their JavaScript names
+pub const STRING_METHODS: &[(&str, &str)] = &[
+    ("upper", "toUpperCase"),
+    ("lower", "toLowerCase"),
+    ("trim", "trim"),
+    ("slice", "slice"),
+    ("contains", "includes"),
+    ("starts", "startsWith"),
+    ("ends", "endsWith"),
+    ("replace", "replace"),
+    ("repeat", "repeat"),
+    ("split", "split"),
+];
+
+/// Look up the JavaScript name of a TFI string method
+pub fn js_string_method(name: &str) -> Option<&'static str> {
+    STRING_METHODS.iter().find(|(tfi, _)| *tfi == name).map(|(_, js)| *js)
 }
 
 impl Statement {
@@ -33,11 +197,27 @@ impl Statement {
     pub fn statement_type(&self) -> &'static str {
         match self {
             Statement::Print(_) => "Print",
+            Statement::PrintInline(_) => "PrintInline",
+            Statement::PrintJoined(_, _, _) => "PrintJoined",
+            Statement::Import(_) => "Import",
+            Statement::RawPrint(_) => "RawPrint",
+            Statement::RawJs(_) => "RawJs",
+            Statement::Clear => "Clear",
             Statement::Const(_, _) => "Const",
+            Statement::MultiConst(_) => "MultiConst",
             Statement::Let(_, _) => "Let",
+            Statement::Assign(_, _) => "Assign",
+            Statement::Increment(_, _) => "Increment",
             Statement::If(_, _, _) => "If",
             Statement::While(_, _) => "While",
             Statement::For(_, _, _, _) => "For",
+            Statement::ForRange(_, _, _, _) => "ForRange",
+            Statement::Labeled(_, _) => "Labeled",
+            Statement::Break(_) => "Break",
+            Statement::Return(_) => "Return",
+            Statement::Assert(_, _) => "Assert",
+            Statement::Comment(_) => "Comment",
+            Statement::Expr(_) => "Expr",
         }
     }
 }
@@ -47,9 +227,22 @@ impl Expression {
     pub fn expression_type(&self) -> &'static str {
         match self {
             Expression::Number(_) => "Number",
+            Expression::Float(_) => "Float",
             Expression::Identifier(_) => "Identifier",
             Expression::String(_) => "String",
             Expression::BinaryOp(_, _, _) => "BinaryOp",
+            Expression::Member(_, _) => "Member",
+            Expression::OptionalMember(_, _) => "OptionalMember",
+            Expression::Grouping(_) => "Grouping",
+            Expression::MethodCall(_, _, _) => "MethodCall",
+            Expression::Object(_) => "Object",
+            Expression::Index(_, _) => "Index",
+            Expression::IndexFromEnd(_, _) => "IndexFromEnd",
+            Expression::Slice(_, _, _) => "Slice",
+            Expression::Folded(_, _) => "Folded",
+            Expression::Lambda(_, _) => "Lambda",
+            Expression::Call(_, _) => "Call",
+            Expression::Conditional(_, _, _) => "Conditional",
         }
     }
 }
@@ -68,7 +261,7 @@ mod tests {
 
         let if_stmt = Statement::If(
             Expression::Number(1),
-            vec![Statement::Print(vec![Expression::String("hello".to_string())])],
+            vec![Statement::Print(vec![Expression::String("hello".to_string())]).into()],
             None
         );
         assert_eq!(if_stmt.statement_type(), "If");
@@ -112,4 +305,15 @@ mod tests {
         assert_eq!(stmt1, stmt2);
         assert_ne!(stmt1, stmt3);
     }
+
+    #[test]
+    fn test_spanned_equality_ignores_line() {
+        let parsed = Spanned::new(Statement::Clear, 3);
+        let built: Spanned<Statement> = Statement::Clear.into();
+        
+        assert_eq!(built.line, 0);
+        assert_eq!(parsed, built);
+        assert_eq!(parsed, Statement::Clear);
+        assert_eq!(parsed.statement_type(), "Clear");
+    }
 } 
\ No newline at end of file
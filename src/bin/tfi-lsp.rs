@@ -0,0 +1,10 @@
+use std::io;
+
+/// Run the TFI language server over stdin and stdout
+fn main() {
+    let stdin = io::stdin();
+    if let Err(e) = tfi_lang::lsp::serve(stdin.lock(), io::stdout().lock()) {
+        eprintln!("tfi-lsp: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether diagnostics printed by the library are colored; off unless the CLI enables it
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// When to color terminal output, as selected by `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorChoice {
+    /// Color when stderr is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Parse a `--color` value: auto, always or never
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+    
+    /// Decide whether to color, checking `NO_COLOR` and the terminal for `Auto`
+    pub fn should_color(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && std::io::stderr().is_terminal()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// ANSI colors used for diagnostics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    /// Errors
+    Red,
+    /// Warnings
+    Yellow,
+    /// Suggestions
+    Cyan,
+}
+
+impl Color {
+    /// Get the ANSI escape sequence that switches to this color
+    fn code(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Yellow => "\x1b[33m",
+            Color::Cyan => "\x1b[36m",
+        }
+    }
+}
+
+/// Turn colored diagnostics on or off for the whole process
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check whether colored diagnostics are turned on
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Wrap text in a color, or return it unchanged when colors are off
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if enabled && !text.is_empty() {
+        format!("{}{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Color a multi-line error report: headings red, suggestions cyan
+pub fn paint_error(report: &str, enabled: bool) -> String {
+    report.split('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('💡') {
+                paint(line, Color::Cyan, enabled)
+            } else if trimmed.starts_with(['❌', '⚠', '🔧']) || trimmed.starts_with("Error") {
+                paint(line, Color::Red, enabled)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const REPORT: &str = "❌ Parse Error [E0100] at line 1, column 5\n   Syntax error\n   💡 Suggestion: add a semicolon\n";
+
+    #[test]
+    fn test_parse_color_choice() {
+        assert_eq!(ColorChoice::parse("always"), Some(ColorChoice::Always));
+        assert_eq!(ColorChoice::parse("never"), Some(ColorChoice::Never));
+        assert_eq!(ColorChoice::parse("sometimes"), None);
+        assert!(!ColorChoice::Never.should_color());
+        assert!(ColorChoice::Always.should_color());
+    }
+
+    #[test]
+    fn test_paint_error_colors() {
+        let colored = paint_error(REPORT, true);
+        
+        assert!(colored.starts_with("\x1b[31m❌"));
+        assert!(colored.contains("\x1b[36m   💡"));
+        assert!(colored.contains("\n   Syntax error\n"));
+    }
+
+    #[test]
+    fn test_never_has_no_escape_codes() {
+        let plain = paint_error(REPORT, ColorChoice::Never.should_color());
+        
+        assert_eq!(plain, REPORT);
+        assert!(!plain.contains('\x1b'));
+    }
+}
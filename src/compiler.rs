@@ -1,6 +1,16 @@
-use crate::parser::parse_program;
-use crate::validator::validate_program;
-use crate::generator::generate_program;
+use crate::ast::{Statement, Spanned};
+use crate::error_codes;
+use crate::optimizer::{expand_chained_comparisons, fold_constants, fold_constants_keeping_names, MangleNames, MergePrints, Pass};
+use crate::parser::{parse_program, parse_program_silent, parse_program_with_nesting_limit, DEFAULT_MAX_NESTING_DEPTH};
+use crate::validator::{validate_program_in_context, ValidationContext, ValidationError, Warning, DEFAULT_MAX_EXPRESSION_DEPTH};
+use crate::lexer::{AliasMap, Lexer};
+use crate::generator::{generate_program_with_options, GeneratorOptions, IndentStyle, OutputMode, QuoteStyle, RAW_JS_END, RAW_JS_START};
+use crate::imports::{imported_sources, inline_imports};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Enhanced compilation error types with better context
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +25,8 @@ pub enum CompilationError {
     },
     /// Validation error with context
     ValidationError {
+        /// Code of the underlying validator error
+        code: &'static str,
         message: String,
         line: Option<usize>,
         context: Option<String>,
@@ -32,21 +44,33 @@ pub enum CompilationError {
     },
 }
 
+impl CompilationError {
+    /// Get the stable error code, explained by `tfi-lang --explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilationError::ParseError { .. } => error_codes::SYNTAX_ERROR,
+            CompilationError::ValidationError { code, .. } => code,
+            CompilationError::GenerationError { .. } => error_codes::GENERATION_ERROR,
+            CompilationError::General { .. } => error_codes::COMPILATION_ERROR,
+        }
+    }
+}
+
 impl std::fmt::Display for CompilationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CompilationError::ParseError { message, line, column, source_line, suggestion } => {
-                writeln!(f, "❌ Parse Error at line {}, column {}", line, column)?;
+                writeln!(f, "❌ Parse Error [{}] at line {}, column {}", self.code(), line, column)?;
                 writeln!(f, "   {}", message)?;
                 writeln!(f, "   {}", source_line)?;
-                write!(f, "   {}^", " ".repeat(*column - 1))?;
+                write!(f, "   {}^", " ".repeat(column.saturating_sub(1)))?;
                 if let Some(sugg) = suggestion {
                     writeln!(f, "\n   💡 Suggestion: {}", sugg)?;
                 }
                 Ok(())
             }
-            CompilationError::ValidationError { message, line, context, suggestion } => {
-                writeln!(f, "⚠️  Validation Error")?;
+            CompilationError::ValidationError { message, line, context, suggestion, .. } => {
+                writeln!(f, "⚠️  Validation Error [{}]", self.code())?;
                 if let Some(l) = line {
                     writeln!(f, "   at line {}", l)?;
                 }
@@ -60,7 +84,7 @@ impl std::fmt::Display for CompilationError {
                 Ok(())
             }
             CompilationError::GenerationError { message, context } => {
-                writeln!(f, "🔧 Generation Error")?;
+                writeln!(f, "🔧 Generation Error [{}]", self.code())?;
                 writeln!(f, "   {}", message)?;
                 if let Some(ctx) = context {
                     writeln!(f, "   Context: {}", ctx)?;
@@ -68,7 +92,7 @@ impl std::fmt::Display for CompilationError {
                 Ok(())
             }
             CompilationError::General { message, context } => {
-                writeln!(f, "❌ Compilation Error")?;
+                writeln!(f, "❌ Compilation Error [{}]", self.code())?;
                 writeln!(f, "   {}", message)?;
                 if let Some(ctx) = context {
                     writeln!(f, "   Context: {}", ctx)?;
@@ -116,6 +140,11 @@ impl CompilationResult {
     pub fn warning_count(&self) -> usize {
         self.warnings.len()
     }
+    
+    /// Get the JavaScript as written to a file, ending in exactly one newline
+    pub fn file_contents(&self) -> String {
+        format!("{}\n", self.js_code.trim_end_matches(['\n', '\r']))
+    }
 }
 
 /// Compile TFI source code to JavaScript
@@ -126,67 +155,217 @@ pub fn compile(source: &str) -> Result<String, Box<dyn std::error::Error>> {
 
 /// Compile TFI source code to JavaScript with detailed results
 pub fn compile_with_details(source: &str) -> Result<CompilationResult, Box<dyn std::error::Error>> {
-    // Step 1: Parse the source code
-    let ast = parse_program(source).map_err(|e| {
-        CompilationError::General {
-            message: format!("Failed to parse TFI code: {}", e),
-            context: Some("The parser has already printed detailed error information above".to_string()),
+    compile_with_options(source, &CompilationOptions::default())
+}
+
+/// Time spent in each compilation phase
+///
+/// A phase is `None` when it did not run, e.g. `optimize` when no optimization passes are enabled.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings {
+    /// Time spent parsing the source into an AST
+    pub parse: Option<Duration>,
+    /// Time spent validating the AST
+    pub validate: Option<Duration>,
+    /// Time spent in optimization passes
+    pub optimize: Option<Duration>,
+    /// Time spent generating JavaScript
+    pub generate: Option<Duration>,
+}
+
+impl PhaseTimings {
+    /// Get the combined time of all phases that ran
+    pub fn total(&self) -> Duration {
+        [self.parse, self.validate, self.optimize, self.generate]
+            .iter()
+            .flatten()
+            .sum()
+    }
+    
+    /// Get a summary string
+    pub fn summary(&self) -> String {
+        let phases = [
+            ("Parse", self.parse),
+            ("Validate", self.validate),
+            ("Optimize", self.optimize),
+            ("Generate", self.generate),
+        ];
+        
+        let mut summary = String::from("Phase Timings:");
+        for (name, timing) in phases {
+            match timing {
+                Some(duration) => summary.push_str(&format!("\n - {}: {:?}", name, duration)),
+                None => summary.push_str(&format!("\n - {}: skipped", name)),
+            }
+        }
+        summary.push_str(&format!("\n - Total: {:?}", self.total()));
+        summary
+    }
+}
+
+/// Compile an already-constructed AST, skipping the parse step
+///
+/// Runs validation, generation, warnings and the output transforms selected in `options`.
+pub fn compile_ast(
+    statements: &[Spanned<Statement>],
+    options: &CompilationOptions
+) -> Result<CompilationResult, CompilationError> {
+    compile_ast_timed(statements, options, &[], None)
+}
+
+/// Compile an AST, recording how long each phase takes when `timings` is given
+///
+/// The clock is only read when profiling, since `Instant::now` is unavailable on some targets
+/// such as `wasm32-unknown-unknown`.
+fn compile_ast_timed(
+    statements: &[Spanned<Statement>],
+    options: &CompilationOptions,
+    passes: &[Box<dyn Pass>],
+    timings: Option<&mut PhaseTimings>
+) -> Result<CompilationResult, CompilationError> {
+    let profile = timings.is_some();
+    let mut unused = PhaseTimings::default();
+    let timings = timings.unwrap_or(&mut unused);
+    
+    // Step 1: Rewrite the AST before validation, so it checks the expanded chains and folded
+    // values such as a zero divisor
+    let rewritten;
+    let statements = if options.auto_chain || options.fold_constants {
+        let start = profile.then(Instant::now);
+        let mut transformed = statements.to_vec();
+        if options.auto_chain {
+            transformed = expand_chained_comparisons(transformed)?;
         }
-    })?;
+        if options.fold_constants && options.keep_names {
+            transformed = fold_constants_keeping_names(transformed);
+        } else if options.fold_constants {
+            transformed = fold_constants(transformed);
+        }
+        rewritten = transformed;
+        timings.optimize = start.map(|start| start.elapsed());
+        &rewritten[..]
+    } else {
+        statements
+    };
     
     // Step 2: Validate the AST
-    validate_program(&ast).map_err(|e| {
-        CompilationError::ValidationError {
-            message: format!("Validation failed: {}", e),
-            line: None, // Placeholder, will be updated by validator
-            context: None, // Placeholder, will be updated by validator
-            suggestion: None,
+    let start = profile.then(Instant::now);
+    let validation_warnings = validate_ast(statements, options)?;
+    timings.validate = start.map(|start| start.elapsed());
+    
+    // Step 3: Run the built-in passes enabled in options, then any custom passes
+    let optimized;
+    let mangle = options.minify && options.mangle;
+    let program = if options.merge_prints || mangle || !passes.is_empty() {
+        let start = profile.then(Instant::now);
+        let mut transformed = statements.to_vec();
+        if options.merge_prints {
+            transformed = MergePrints.run(transformed)?;
+        }
+        for pass in passes {
+            transformed = pass.run(transformed)?;
         }
-    })?;
+        // Mangling runs last so custom passes still see the original names
+        if mangle {
+            transformed = MangleNames.run(transformed)?;
+        }
+        optimized = transformed;
+        let folding_time = timings.optimize.unwrap_or_default();
+        timings.optimize = start.map(|start| folding_time + start.elapsed());
+        &optimized[..]
+    } else {
+        statements
+    };
     
-    // Step 3: Generate JavaScript code
-    let js_code = generate_program(&ast);
+    // Step 4: Generate JavaScript code
+    let start = profile.then(Instant::now);
+    let js_code = generate_program_with_options(program, &GeneratorOptions {
+        explicit_coercion: options.explicit_coercion,
+        quote_style: options.quote_style,
+        stringify_objects: options.stringify_objects,
+        omit_semicolons: options.omit_semicolons,
+        output_mode: options.output_mode,
+        minify: options.minify,
+        loop_guard: options.loop_guard,
+        mark_raw_js: options.format_output,
+    });
+    timings.generate = start.map(|start| start.elapsed());
     
-    // Step 4: Create compilation result
-    let mut result = CompilationResult::new(js_code, ast.len());
+    // Step 5: Create compilation result
+    let mut result = CompilationResult::new(js_code, statements.len());
     
     // Add warnings for potential issues
-    add_compilation_warnings(&ast, &mut result);
+    for warning in validation_warnings.into_iter().chain(compilation_warnings(statements, options)) {
+        result.add_warning(warning.to_string());
+    }
+    
+    // Step 6: Apply output options
+    if options.strict_mode {
+        let terminator = if options.omit_semicolons { "" } else { ";" };
+        result.js_code = format!("{}{}\n{}", options.quote_style.quote("use strict"), terminator, result.js_code);
+    }
+    
+    if options.wrap_iife {
+        result.js_code = wrap_in_iife(&result.js_code);
+    }
+    
+    if options.format_output {
+        let indent_unit = options.indent_style.unit(options.indent_width);
+        result.js_code = format_js_code(&result.js_code, &indent_unit);
+    }
     
     Ok(result)
 }
 
-/// Add warnings for potential issues in the code
-fn add_compilation_warnings(statements: &[crate::ast::Statement], result: &mut CompilationResult) {
+/// Validation context with the limits and allowances in `options`
+pub(crate) fn validation_context(options: &CompilationOptions) -> ValidationContext {
+    let mut context = ValidationContext::with_max_expression_depth(options.max_expression_depth);
+    if options.allow_empty_print {
+        context = context.allowing_empty_print();
+    }
+    context.allowing_globals(options.allowed_globals.iter().cloned())
+}
+
+/// Validate an AST with the limits in `options`, returning the validator's warnings
+fn validate_ast(statements: &[Spanned<Statement>], options: &CompilationOptions) -> Result<Vec<Warning>, CompilationError> {
+    validate_program_in_context(statements, validation_context(options)).map_err(|e| {
+        CompilationError::ValidationError {
+            code: e.downcast_ref::<ValidationError>().map_or(error_codes::COMPILATION_ERROR, ValidationError::code),
+            message: format!("Validation failed: {}", e),
+            line: None, // Placeholder, will be updated by validator
+            context: None, // Placeholder, will be updated by validator
+            suggestion: None,
+        }
+    })
+}
+
+/// Find statements larger than the limits in `options`
+pub(crate) fn compilation_warnings(statements: &[Spanned<Statement>], options: &CompilationOptions) -> Vec<Warning> {
+    let mut warnings = Vec::new();
     for (i, stmt) in statements.iter().enumerate() {
-        match stmt {
-            crate::ast::Statement::Print(expressions) => {
-                if expressions.len() > 5 {
-                    result.add_warning(format!(
-                        "Statement {}: Print statement has {} arguments, consider breaking it up",
-                        i + 1, expressions.len()
-                    ));
-                }
+        // A label doesn't change what the loop itself should be warned about
+        let node = match &stmt.node {
+            Statement::Labeled(_, body) => body,
+            node => node,
+        };
+        match node {
+            Statement::Print(expressions) | Statement::PrintInline(expressions) | Statement::PrintJoined(expressions, _, _)
+                if expressions.len() > options.max_print_args =>
+            {
+                warnings.push(Warning::TooManyPrintArgs(i + 1, expressions.len()));
             }
-            crate::ast::Statement::While(_, block) => {
-                if block.len() > 10 {
-                    result.add_warning(format!(
-                        "Statement {}: While loop has {} statements, consider refactoring",
-                        i + 1, block.len()
-                    ));
-                }
+            Statement::While(_, block) if block.len() > options.max_loop_body => {
+                warnings.push(Warning::LongLoopBody(i + 1, "While".to_string(), block.len()));
             }
-            crate::ast::Statement::For(_, _, _, block) => {
-                if block.len() > 10 {
-                    result.add_warning(format!(
-                        "Statement {}: For loop has {} statements, consider refactoring",
-                        i + 1, block.len()
-                    ));
-                }
+            Statement::For(_, _, _, block) | Statement::ForRange(_, _, _, block)
+                if block.len() > options.max_loop_body =>
+            {
+                warnings.push(Warning::LongLoopBody(i + 1, "For".to_string(), block.len()));
             }
             _ => {}
         }
     }
+    warnings
 }
 
 /// Compile TFI source code with specific options
@@ -194,12 +373,82 @@ pub fn compile_with_options(
     source: &str,
     options: &CompilationOptions
 ) -> Result<CompilationResult, Box<dyn std::error::Error>> {
-    let mut result = compile_with_details(source)?;
+    Ok(compile_source(source, options, &[], None)?)
+}
+
+/// Compile TFI source code, reusing the cached result when the same source and options were compiled before
+///
+/// The cache is keyed by a hash of the source together with every option, since options change the
+/// output, and the text of every file the source imports. Only successful compilations are cached.
+/// Finding the imports parses the source, so programs with a source path pay for a parse on every call.
+pub fn compile_cached(
+    source: &str,
+    options: &CompilationOptions,
+    cache: &mut HashMap<u64, CompilationResult>
+) -> Result<CompilationResult, Box<dyn std::error::Error>> {
+    let key = cache_key(source, options);
+    if let Some(result) = cache.get(&key) {
+        return Ok(result.clone());
+    }
     
-    // Apply options
-    if options.format_output {
-        result.js_code = format_js_code(&result.js_code);
+    let result = compile_with_options(source, options)?;
+    cache.insert(key, result.clone());
+    Ok(result)
+}
+
+/// Hash source, options and imported files into the key used by `compile_cached`
+fn cache_key(source: &str, options: &CompilationOptions) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    options.hash(&mut hasher);
+    
+    // An imported file can change while the importing source stays the same. A program whose
+    // imports don't resolve fails to compile, so it never reaches the cache.
+    if let Some(path) = &options.source_path {
+        let silent = options.clone().with_silent_parsing();
+        let sources = parse_single_source(source, &silent).and_then(|ast| imported_sources(ast, path, &silent));
+        if let Ok(sources) = sources {
+            sources.hash(&mut hasher);
+        }
     }
+    hasher.finish()
+}
+
+/// Compile TFI source code, running custom AST passes between validation and generation
+///
+/// The passes run in order, after any built-in passes enabled in `options`.
+pub fn compile_with_passes(
+    source: &str,
+    passes: &[Box<dyn Pass>],
+    options: &CompilationOptions
+) -> Result<CompilationResult, Box<dyn std::error::Error>> {
+    Ok(compile_source(source, options, passes, None)?)
+}
+
+/// Compile TFI source code with custom options, measuring the time spent in each phase
+pub fn compile_profiled(
+    source: &str,
+    options: &CompilationOptions
+) -> Result<(CompilationResult, PhaseTimings), Box<dyn std::error::Error>> {
+    let mut timings = PhaseTimings::default();
+    let result = compile_source(source, options, &[], Some(&mut timings))?;
+    Ok((result, timings))
+}
+
+/// Parse and compile source, recording phase timings when `timings` is given
+fn compile_source(
+    source: &str,
+    options: &CompilationOptions,
+    passes: &[Box<dyn Pass>],
+    mut timings: Option<&mut PhaseTimings>
+) -> Result<CompilationResult, CompilationError> {
+    let start = timings.is_some().then(Instant::now);
+    let ast = parse_source(source, options)?;
+    if let (Some(timings), Some(start)) = (timings.as_deref_mut(), start) {
+        timings.parse = Some(start.elapsed());
+    }
+    
+    let mut result = compile_ast_timed(&ast, options, passes, timings)?;
     
     if options.add_comments {
         result.js_code = add_source_comments(&result.js_code, source);
@@ -208,8 +457,75 @@ pub fn compile_with_options(
     Ok(result)
 }
 
+/// Parse and validate TFI source without generating JavaScript, returning the warnings
+pub fn check(source: &str, options: &CompilationOptions) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let ast = parse_source(source, options)?;
+    
+    // Collect the same warnings a full compilation would report
+    let mut result = CompilationResult::new(String::new(), ast.len());
+    for warning in validate_ast(&ast, options)?.into_iter().chain(compilation_warnings(&ast, options)) {
+        result.add_warning(warning.to_string());
+    }
+    
+    Ok(result.warnings)
+}
+
+/// Parse source after applying the keyword aliases, nesting limit and statement budget in `options`
+///
+/// When `options` names the source file, its imports are inlined before the budget is checked.
+pub(crate) fn parse_source(source: &str, options: &CompilationOptions) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+    parse_single_source(source, options)
+        .and_then(|ast| match &options.source_path {
+            Some(path) => inline_imports(ast, path, options),
+            None => Ok(ast),
+        })
+        .and_then(|ast| check_statement_budget(ast, options))
+}
+
+/// Parse one source file after applying the keyword aliases and nesting limit in `options`
+pub(crate) fn parse_single_source(source: &str, options: &CompilationOptions) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+    let aliased;
+    let program_source = if options.aliases.is_empty() {
+        source
+    } else {
+        aliased = options.aliases.apply(source);
+        &aliased
+    };
+    
+    let parsed = if options.silent {
+        parse_program_silent(program_source, options.preserve_comments, options.max_nesting_depth)
+    } else {
+        parse_program_with_nesting_limit(program_source, options.preserve_comments, options.max_nesting_depth)
+    };
+    
+    parsed.map_err(|e| {
+        CompilationError::General {
+            message: format!("Failed to parse TFI code: {}", e),
+            context: (!options.silent).then(|| "The parser has already printed detailed error information above".to_string()),
+        }
+    })
+}
+
+/// Reject programs with more statements, counting nested ones, than `options.max_statements`
+fn check_statement_budget(
+    ast: Vec<Spanned<Statement>>,
+    options: &CompilationOptions
+) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+    if let Some(max) = options.max_statements {
+        let count = stats_for_ast(&ast).all_statements;
+        if count > max {
+            return Err(CompilationError::General {
+                message: format!("Program has {} statements, more than the limit of {}", count, max),
+                context: Some("Split the program up or raise --max-statements".to_string()),
+            });
+        }
+    }
+    
+    Ok(ast)
+}
+
 /// Compilation options
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Hash)]
 pub struct CompilationOptions {
     /// Format the output JavaScript code
     pub format_output: bool,
@@ -219,6 +535,92 @@ pub struct CompilationOptions {
     pub strict_mode: bool,
     /// Minify the output
     pub minify: bool,
+    /// Shorten variable names when minifying
+    pub mangle: bool,
+    /// Wrap the program in an immediately-invoked function expression
+    pub wrap_iife: bool,
+    /// Carry `//` comments from the TFI source into the generated code
+    pub preserve_comments: bool,
+    /// Wrap numbers concatenated with strings in `String(...)`
+    pub explicit_coercion: bool,
+    /// Print object values with `JSON.stringify(...)`
+    pub stringify_objects: bool,
+    /// Emit statements without trailing semicolons
+    pub omit_semicolons: bool,
+    /// Merge adjacent print statements into a single console.log
+    pub merge_prints: bool,
+    /// Evaluate arithmetic on number literals at compile time
+    pub fold_constants: bool,
+    /// When folding, comment folded values with the expression they replaced
+    pub keep_names: bool,
+    /// Rewrite chained comparisons such as `0 < x < 10` into `&&`-joined comparisons
+    pub auto_chain: bool,
+    /// Number of spaces per indentation level when formatting with spaces
+    pub indent_width: usize,
+    /// Indent formatted output with spaces or tabs
+    pub indent_style: IndentStyle,
+    /// Quote character for string literals in the output
+    pub quote_style: QuoteStyle,
+    /// Print to stdout or return the printed text from a function
+    pub output_mode: OutputMode,
+    /// Deepest bracket nesting the parser accepts
+    pub max_nesting_depth: usize,
+    /// Deepest expression tree the validator accepts
+    pub max_expression_depth: usize,
+    /// Print statements with more arguments than this produce a warning
+    pub max_print_args: usize,
+    /// Loops with more body statements than this produce a warning
+    pub max_loop_body: usize,
+    /// Alternative spellings for keywords, rewritten before parsing
+    pub aliases: AliasMap,
+    /// Return parse errors without printing a report to stderr
+    pub silent: bool,
+    /// Reject programs with more statements than this, counting nested ones
+    pub max_statements: Option<usize>,
+    /// Accept `bahubali()` with no arguments, printing a blank line
+    pub allow_empty_print: bool,
+    /// JavaScript globals such as `Math` that the program may read without declaring
+    pub allowed_globals: Vec<String>,
+    /// File the source was read from; imports are resolved relative to it
+    pub source_path: Option<PathBuf>,
+    /// Make loops throw once they run more than this many iterations
+    pub loop_guard: Option<u32>,
+}
+
+impl Default for CompilationOptions {
+    fn default() -> Self {
+        Self {
+            format_output: false,
+            add_comments: false,
+            strict_mode: false,
+            minify: false,
+            mangle: false,
+            wrap_iife: false,
+            preserve_comments: false,
+            explicit_coercion: false,
+            stringify_objects: false,
+            omit_semicolons: false,
+            merge_prints: false,
+            fold_constants: false,
+            keep_names: false,
+            auto_chain: false,
+            indent_width: 4,
+            indent_style: IndentStyle::Spaces,
+            quote_style: QuoteStyle::Double,
+            output_mode: OutputMode::ConsoleLog,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            max_expression_depth: DEFAULT_MAX_EXPRESSION_DEPTH,
+            max_print_args: 5,
+            max_loop_body: 10,
+            aliases: AliasMap::new(),
+            silent: false,
+            max_statements: None,
+            allow_empty_print: false,
+            allowed_globals: Vec::new(),
+            source_path: None,
+            loop_guard: None,
+        }
+    }
 }
 
 impl CompilationOptions {
@@ -227,6 +629,42 @@ impl CompilationOptions {
         Self::default()
     }
     
+    /// Set the most statements a program may have, counting those inside blocks
+    pub fn with_max_statements(mut self, max: usize) -> Self {
+        self.max_statements = Some(max);
+        self
+    }
+    
+    /// Make every loop throw after `max` iterations, so a loop that never ends fails fast
+    pub fn with_loop_guard(mut self, max: u32) -> Self {
+        self.loop_guard = Some(max);
+        self
+    }
+    
+    /// Accept `bahubali()` with no arguments, compiling it to `console.log()`
+    pub fn with_empty_print(mut self) -> Self {
+        self.allow_empty_print = true;
+        self
+    }
+    
+    /// Permit reading the JavaScript global `name`, such as `Math` or `JSON`
+    pub fn with_allowed_global(mut self, name: impl Into<String>) -> Self {
+        self.allowed_globals.push(name.into());
+        self
+    }
+    
+    /// Record the file the source was read from, so its imports can be inlined
+    pub fn with_source_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.source_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+    
+    /// Return parse errors without printing them, for embedders that handle errors themselves
+    pub fn with_silent_parsing(mut self) -> Self {
+        self.silent = true;
+        self
+    }
+    
     /// Enable formatting
     pub fn with_formatting(mut self) -> Self {
         self.format_output = true;
@@ -250,15 +688,158 @@ impl CompilationOptions {
         self.minify = true;
         self
     }
+    
+    /// Extension for output files named by default, so minified and plain builds don't collide
+    pub fn output_extension(&self) -> &'static str {
+        if self.minify { "min.js" } else { "js" }
+    }
+    
+    /// Shorten variable names to `a`, `b`, `c`...; only takes effect together with minification
+    pub fn with_mangled_names(mut self) -> Self {
+        self.mangle = true;
+        self
+    }
+    
+    /// Enable IIFE wrapping
+    pub fn with_iife_wrapping(mut self) -> Self {
+        self.wrap_iife = true;
+        self
+    }
+    
+    /// Enable comment preservation
+    pub fn with_preserved_comments(mut self) -> Self {
+        self.preserve_comments = true;
+        self
+    }
+    
+    /// Enable explicit string coercion
+    pub fn with_explicit_coercion(mut self) -> Self {
+        self.explicit_coercion = true;
+        self
+    }
+    
+    /// Print values inferred to be objects as JSON
+    pub fn with_stringified_objects(mut self) -> Self {
+        self.stringify_objects = true;
+        self
+    }
+    
+    /// Leave statements without semicolons, relying on automatic semicolon insertion
+    pub fn with_omitted_semicolons(mut self) -> Self {
+        self.omit_semicolons = true;
+        self
+    }
+    
+    /// Enable merging of adjacent print statements
+    pub fn with_merged_prints(mut self) -> Self {
+        self.merge_prints = true;
+        self
+    }
+    
+    /// Expand chained comparisons, so `0 < x < 10` compiles to `((0 < x) && (x < 10))`
+    pub fn with_auto_chain(mut self) -> Self {
+        self.auto_chain = true;
+        self
+    }
+    
+    /// Enable constant folding of arithmetic on number literals
+    pub fn with_constant_folding(mut self) -> Self {
+        self.fold_constants = true;
+        self
+    }
+    
+    /// Comment values folded from named constants with the original expression
+    pub fn with_kept_names(mut self) -> Self {
+        self.keep_names = true;
+        self
+    }
+    
+    /// Set the number of spaces per indentation level
+    pub fn with_indent_width(mut self, width: usize) -> Self {
+        self.indent_width = width;
+        self
+    }
+    
+    /// Set the keyword aliases
+    pub fn with_aliases(mut self, aliases: AliasMap) -> Self {
+        self.aliases = aliases;
+        self
+    }
+    
+    /// Set the indentation style
+    pub fn with_indent_style(mut self, style: IndentStyle) -> Self {
+        self.indent_style = style;
+        self
+    }
+    
+    /// Set the quote character for string literals
+    pub fn with_quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = style;
+        self
+    }
+    
+    /// Set whether printed text goes to stdout or is returned from a function
+    pub fn with_output_mode(mut self, mode: OutputMode) -> Self {
+        self.output_mode = mode;
+        self
+    }
+    
+    /// Set the deepest bracket nesting the parser accepts
+    pub fn with_max_nesting_depth(mut self, depth: usize) -> Self {
+        self.max_nesting_depth = depth;
+        self
+    }
+    
+    /// Set the deepest expression tree the validator accepts
+    pub fn with_max_expression_depth(mut self, depth: usize) -> Self {
+        self.max_expression_depth = depth;
+        self
+    }
+    
+    /// Set the print argument count above which a warning is emitted
+    pub fn with_max_print_args(mut self, max: usize) -> Self {
+        self.max_print_args = max;
+        self
+    }
+    
+    /// Set the loop body size above which a warning is emitted
+    pub fn with_max_loop_body(mut self, max: usize) -> Self {
+        self.max_loop_body = max;
+        self
+    }
 }
 
-/// Format JavaScript code with proper indentation
-fn format_js_code(js_code: &str) -> String {
+/// Format JavaScript code with proper indentation and consistent spacing
+///
+/// Raw JavaScript between `RAW_JS_START` and `RAW_JS_END` is copied as written, with only its
+/// first line indented, and the markers are removed.
+fn format_js_code(js_code: &str, indent_unit: &str) -> String {
+    let mut normalized = String::new();
+    for (i, chunk) in js_code.split([RAW_JS_START, RAW_JS_END]).enumerate() {
+        if i % 2 == 0 {
+            normalized.push_str(&normalize_spacing(chunk));
+        } else {
+            normalized.push(RAW_JS_START);
+            normalized.push_str(chunk);
+            normalized.push(RAW_JS_END);
+        }
+    }
+    
     let mut formatted = String::new();
     let mut indent_level: usize = 0;
-    let indent_size = 4;
+    let mut in_raw_js = false;
     
-    for line in js_code.lines() {
+    for line in normalized.lines() {
+        if in_raw_js || line.contains(RAW_JS_START) {
+            if !in_raw_js {
+                formatted.push_str(&indent_unit.repeat(indent_level));
+            }
+            in_raw_js = !line.contains(RAW_JS_END);
+            formatted.push_str(&line.replace([RAW_JS_START, RAW_JS_END], ""));
+            formatted.push('\n');
+            continue;
+        }
+        
         let trimmed = line.trim();
         
         if trimmed.is_empty() {
@@ -272,7 +853,7 @@ fn format_js_code(js_code: &str) -> String {
         }
         
         // Add indentation
-        let indent = " ".repeat(indent_level * indent_size);
+        let indent = indent_unit.repeat(indent_level);
         formatted.push_str(&format!("{}{}\n", indent, trimmed));
         
         // Increase indent for opening braces
@@ -284,6 +865,157 @@ fn format_js_code(js_code: &str) -> String {
     formatted
 }
 
+/// Operators written with one space on each side, longest first so `===` isn't read as `==`
+const SPACED_OPERATORS: &[&str] = &[
+    ">>>=", "===", "!==", "**=", ">>>", "<<=", ">>=", "&&=", "||=", "??=",
+    "==", "!=", "<=", ">=", "&&", "||", "??", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "=>", "**", "<<", ">>",
+    "+", "-", "*", "/", "%", "<", ">", "=", "&", "|", "^",
+];
+
+/// Keywords after which `-`, `+` or `/` starts an operand rather than being a binary operator
+const OPERAND_KEYWORDS: &[&str] = &["return", "typeof", "case", "throw", "new", "delete", "void", "in", "of", "instanceof", "else", "do", "yield", "await"];
+
+/// Put exactly one space around binary operators and after commas, and none just inside brackets
+///
+/// String, template and regex literals and comments are copied unchanged. Unary operators and
+/// `++`/`--` stay attached to their operand, and other spacing is collapsed to single spaces.
+fn normalize_spacing(js_code: &str) -> String {
+    let chars = js_code.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(js_code.len());
+    // Whether the last token ends an operand, so a following `-` or `/` is binary
+    let mut after_operand = false;
+    let mut pending_space = false;
+    // Conditionals waiting for their `:`, which otherwise belongs to an object key or a label
+    let mut open_conditionals = 0;
+    let mut i = 0;
+    
+    let flush_space = |out: &mut String, pending_space: &mut bool| {
+        if std::mem::take(pending_space) && !out.is_empty() && !out.ends_with(['(', '[', ' ', '\n']) {
+            out.push(' ');
+        }
+    };
+    
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+        let starts_with = |text: &str| rest.iter().take(text.len()).copied().eq(text.chars());
+        
+        if c == '\n' {
+            out.truncate(out.trim_end_matches(' ').len());
+            out.push('\n');
+            pending_space = false;
+            i += 1;
+        } else if c.is_whitespace() {
+            pending_space = true;
+            i += 1;
+        } else if starts_with("//") || starts_with("/*") {
+            let end = if starts_with("//") {
+                rest.iter().position(|&c| c == '\n').unwrap_or(rest.len())
+            } else {
+                rest.windows(2).position(|pair| pair == ['*', '/']).map_or(rest.len(), |end| end + 2)
+            };
+            flush_space(&mut out, &mut pending_space);
+            out.extend(&rest[..end]);
+            i += end;
+        } else if matches!(c, '"' | '\'' | '`') || (c == '/' && !after_operand) {
+            // Copy a string, template or regex literal up to its unescaped closing character
+            let mut end = 1;
+            let mut in_class = false;
+            while end < rest.len() {
+                match rest[end] {
+                    '\\' => end += 1,
+                    '[' if c == '/' => in_class = true,
+                    ']' if c == '/' => in_class = false,
+                    ch if ch == c && !in_class => break,
+                    '\n' if c != '`' => break,
+                    _ => {}
+                }
+                end += 1;
+            }
+            if rest.get(end) == Some(&c) {
+                end += 1;
+            }
+            if c == '/' {
+                end += rest[end..].iter().take_while(|ch| ch.is_ascii_alphabetic()).count();
+            }
+            flush_space(&mut out, &mut pending_space);
+            out.extend(&rest[..end]);
+            after_operand = true;
+            i += end;
+        } else if starts_with("++") || starts_with("--") || starts_with("...") {
+            let len = if c == '.' { 3 } else { 2 };
+            flush_space(&mut out, &mut pending_space);
+            out.extend(&rest[..len]);
+            i += len;
+        } else if let Some(op) = SPACED_OPERATORS
+            .iter()
+            .copied()
+            .chain((c == '?' && !starts_with("?.")).then_some("?"))
+            .chain((c == ':' && open_conditionals > 0).then_some(":"))
+            .find(|op| starts_with(op))
+        {
+            match op {
+                "?" => open_conditionals += 1,
+                ":" => open_conditionals -= 1,
+                _ => {}
+            }
+            if after_operand || !matches!(op, "-" | "+") {
+                out.truncate(out.trim_end_matches(' ').len());
+                if !out.is_empty() && !out.ends_with(['(', '[', '\n']) {
+                    out.push(' ');
+                }
+                out.push_str(op);
+                out.push(' ');
+                pending_space = false;
+            } else {
+                // Unary sign, kept against its operand
+                flush_space(&mut out, &mut pending_space);
+                out.push_str(op);
+            }
+            after_operand = false;
+            i += op.len();
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            // Numbers run on through `.` and an exponent's sign, as in `1.5` and `2e-2`
+            let number = c.is_ascii_digit();
+            let hex = starts_with("0x") || starts_with("0X");
+            let mut len = 0;
+            while let Some(&ch) = rest.get(len) {
+                let exponent_sign = number && !hex && matches!(ch, '+' | '-') && matches!(rest[len - 1], 'e' | 'E');
+                if !(ch.is_alphanumeric() || matches!(ch, '_' | '$') || (number && ch == '.') || exponent_sign) {
+                    break;
+                }
+                len += 1;
+            }
+            let word = rest[..len].iter().collect::<String>();
+            flush_space(&mut out, &mut pending_space);
+            out.push_str(&word);
+            after_operand = !OPERAND_KEYWORDS.contains(&word.as_str());
+            i += len;
+        } else {
+            match c {
+                ')' | ']' | ',' | ';' => pending_space = false,
+                _ => flush_space(&mut out, &mut pending_space),
+            }
+            if matches!(c, ')' | ']') {
+                out.truncate(out.trim_end_matches(' ').len());
+            }
+            out.push(c);
+            if matches!(c, ',' | ';') {
+                pending_space = true;
+            }
+            after_operand = matches!(c, ')' | ']');
+            i += 1;
+        }
+    }
+    
+    out
+}
+
+/// Wrap JavaScript code in an IIFE so top-level declarations stay out of the global scope
+fn wrap_in_iife(js_code: &str) -> String {
+    format!("(function() {{\n{}\n}})();", js_code)
+}
+
 /// Add source comments to JavaScript code
 fn add_source_comments(js_code: &str, source: &str) -> String {
     let mut commented = String::new();
@@ -296,7 +1028,7 @@ fn add_source_comments(js_code: &str, source: &str) -> String {
         }
     }
     
-    commented.push_str("\n");
+    commented.push('\n');
     commented.push_str(js_code);
     
     commented
@@ -305,24 +1037,45 @@ fn add_source_comments(js_code: &str, source: &str) -> String {
 /// Get compilation statistics
 pub fn get_compilation_stats(source: &str) -> Result<CompilationStats, Box<dyn std::error::Error>> {
     let ast = parse_program(source)?;
+    Ok(CompilationStats {
+        token_count: Lexer::new(source).all_tokens().len(),
+        ..stats_for_ast(&ast)
+    })
+}
+
+/// Collect statistics for a parsed program
+fn stats_for_ast(statements: &[Spanned<Statement>]) -> CompilationStats {
+    let mut stats = CompilationStats {
+        total_statements: statements.len(),
+        ..Default::default()
+    };
     
-    let mut stats = CompilationStats::default();
-    stats.total_statements = ast.len();
-    
-    for stmt in &ast {
+    for stmt in statements {
         count_statement_recursive(stmt, &mut stats);
     }
     
-    Ok(stats)
+    stats
 }
 
 /// Recursively count statements in the AST
-fn count_statement_recursive(stmt: &crate::ast::Statement, stats: &mut CompilationStats) {
+fn count_statement_recursive(stmt: &Statement, stats: &mut CompilationStats) {
+    // A label is part of its loop, and comments are not code
+    if !matches!(stmt, Statement::Labeled(..) | Statement::Comment(_)) {
+        stats.all_statements += 1;
+    }
+    
     match stmt {
-        crate::ast::Statement::Print(_) => stats.print_statements += 1,
-        crate::ast::Statement::Const(_, _) => stats.const_declarations += 1,
-        crate::ast::Statement::Let(_, _) => stats.let_declarations += 1,
-        crate::ast::Statement::If(_, then_block, else_block) => {
+        Statement::Print(_) | Statement::PrintInline(_) | Statement::PrintJoined(_, _, _) | Statement::RawPrint(_) => {
+            stats.print_statements += 1
+        }
+        Statement::Const(_, _) => stats.const_declarations += 1,
+        Statement::MultiConst(declarators) => stats.const_declarations += declarators.len(),
+        Statement::Let(_, _) => stats.let_declarations += 1,
+        Statement::Assign(_, _) | Statement::Increment(_, _) => stats.assignments += 1,
+        Statement::Clear | Statement::Break(_) | Statement::Comment(_) | Statement::Expr(_) | Statement::Import(_)
+            | Statement::RawJs(_) | Statement::Assert(_, _) | Statement::Return(_) => {}
+        Statement::Labeled(_, body) => count_statement_recursive(body, stats),
+        Statement::If(_, then_block, else_block) => {
             stats.if_statements += 1;
             for stmt in then_block {
                 count_statement_recursive(stmt, stats);
@@ -333,13 +1086,13 @@ fn count_statement_recursive(stmt: &crate::ast::Statement, stats: &mut Compilati
                 }
             }
         }
-        crate::ast::Statement::While(_, block) => {
+        Statement::While(_, block) => {
             stats.while_loops += 1;
             for stmt in block {
                 count_statement_recursive(stmt, stats);
             }
         }
-        crate::ast::Statement::For(_, _, _, block) => {
+        Statement::For(_, _, _, block) | Statement::ForRange(_, _, _, block) => {
             stats.for_loops += 1;
             for stmt in block {
                 count_statement_recursive(stmt, stats);
@@ -349,22 +1102,28 @@ fn count_statement_recursive(stmt: &crate::ast::Statement, stats: &mut Compilati
 }
 
 /// Compilation statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct CompilationStats {
     /// Total number of statements
     pub total_statements: usize,
+    /// Number of statements including those nested inside blocks
+    pub all_statements: usize,
     /// Number of print statements
     pub print_statements: usize,
     /// Number of const declarations
     pub const_declarations: usize,
     /// Number of let declarations
     pub let_declarations: usize,
+    /// Number of assignments to existing variables
+    pub assignments: usize,
     /// Number of if statements
     pub if_statements: usize,
     /// Number of while loops
     pub while_loops: usize,
     /// Number of for loops
     pub for_loops: usize,
+    /// Number of tokens the lexer finds in the source, a size measure independent of statements
+    pub token_count: usize,
 }
 
 impl CompilationStats {
@@ -392,11 +1151,18 @@ impl CompilationStats {
             self.total_control_structures()
         )
     }
+    
+    /// Get the stats as a JSON object, for tooling that consumes compiler metrics
+    pub fn to_json(&self) -> String {
+        // Plain integer fields always serialize, so this cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::Expression;
 
     #[test]
     fn test_basic_compilation() {
@@ -449,6 +1215,19 @@ mod tests {
         assert!(details.js_code.contains("console.log"));
     }
 
+    #[test]
+    fn test_configurable_warning_thresholds() {
+        let source = "bahubali(1, 2, 3);";
+        
+        let default_result = compile_with_options(source, &CompilationOptions::new()).unwrap();
+        assert!(!default_result.has_warnings());
+        
+        let options = CompilationOptions::new().with_max_print_args(2);
+        let result = compile_with_options(source, &options).unwrap();
+        assert_eq!(result.warning_count(), 1);
+        assert!(result.warnings[0].contains("3 arguments"));
+    }
+
     #[test]
     fn test_compilation_stats() {
         let source = r#"
@@ -506,18 +1285,22 @@ mod tests {
         assert!(options.add_comments);
         assert!(options.strict_mode);
         assert!(!options.minify);
+        assert_eq!(options.max_print_args, 5);
+        assert_eq!(options.max_loop_body, 10);
     }
 
     #[test]
     fn test_compilation_stats_methods() {
-        let mut stats = CompilationStats::default();
-        stats.total_statements = 10;
-        stats.print_statements = 3;
-        stats.const_declarations = 2;
-        stats.let_declarations = 1;
-        stats.if_statements = 2;
-        stats.while_loops = 1;
-        stats.for_loops = 1;
+        let stats = CompilationStats {
+            total_statements: 10,
+            print_statements: 3,
+            const_declarations: 2,
+            let_declarations: 1,
+            if_statements: 2,
+            while_loops: 1,
+            for_loops: 1,
+            ..Default::default()
+        };
         
         assert_eq!(stats.total_declarations(), 3);
         assert_eq!(stats.total_control_structures(), 4);
@@ -529,16 +1312,287 @@ mod tests {
         assert!(summary.contains("Control structures: 4"));
     }
 
+    #[test]
+    fn test_max_statements_counts_nested() {
+        // 6 statements: two declarations, a loop, and three statements in its body
+        let source = r#"
+            rrr limit = 3;
+            pushpa total = 0;
+            eega i in 0..limit {
+                total += i;
+                bahubali(i);
+                bahubali(total);
+            }
+        "#;
+        
+        assert_eq!(get_compilation_stats(source).unwrap().all_statements, 6);
+        assert!(compile_with_options(source, &CompilationOptions::new().with_max_statements(10)).is_ok());
+        
+        let error = compile_with_options(source, &CompilationOptions::new().with_max_statements(5)).unwrap_err();
+        assert!(error.to_string().contains("Program has 6 statements, more than the limit of 5"));
+    }
+
+    /// Test pass that turns every top-level literal 0 into 1
+    struct ZeroToOne;
+    
+    impl Pass for ZeroToOne {
+        fn run(&self, statements: Vec<Spanned<Statement>>) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+            let replace = |expr: Expression| match expr {
+                Expression::Number(0) => Expression::Number(1),
+                other => other,
+            };
+            Ok(statements.into_iter().map(|stmt| stmt.map(|stmt| match stmt {
+                Statement::Print(args) => Statement::Print(args.into_iter().map(replace).collect()),
+                Statement::Const(name, value) => Statement::Const(name, replace(value)),
+                other => other,
+            })).collect())
+        }
+    }
+
+    #[test]
+    fn test_custom_pass() {
+        let passes: Vec<Box<dyn Pass>> = vec![Box::new(ZeroToOne)];
+        let result = compile_with_passes("rrr x = 0;\nbahubali(0, x);", &passes, &CompilationOptions::new()).unwrap();
+        
+        assert_eq!(result.js_code, "const x = 1;\nconsole.log(1, x);");
+    }
+
+    #[test]
+    fn test_compile_cached() {
+        let mut cache = HashMap::new();
+        let source = "bahubali(1);";
+        let options = CompilationOptions::new();
+        
+        let first = compile_cached(source, &options, &mut cache).unwrap();
+        assert_eq!(cache.len(), 1);
+        
+        // Mark the cached entry so a hit is observable
+        if let Some(cached) = cache.get_mut(&cache_key(source, &options)) {
+            cached.js_code = "// cached".to_string();
+        }
+        assert_eq!(compile_cached(source, &options, &mut cache).unwrap().js_code, "// cached");
+        assert_eq!(first.js_code, "console.log(1);");
+        
+        // Different options are a different key
+        let strict = compile_cached(source, &options.clone().with_strict_mode(), &mut cache).unwrap();
+        assert!(strict.js_code.contains("use strict"));
+        assert_eq!(cache.len(), 2);
+        
+        assert!(compile_cached("bahubali(y);", &options, &mut cache).is_err());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_compilation_stats_json() {
+        let stats = get_compilation_stats("bahubali(1); rrr x = 2; eega i in 0..x { bahubali(i); }").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        
+        assert_eq!(json["total_statements"], 3);
+        assert_eq!(json["print_statements"], 2);
+        assert_eq!(json["const_declarations"], 1);
+        assert_eq!(json["for_loops"], 1);
+        assert_eq!(json["while_loops"], 0);
+    }
+
+    #[test]
+    fn test_compilation_stats_token_count() {
+        // rrr x = 2 ; eega i in 0 .. x { bahubali ( i ) ; }
+        let stats = get_compilation_stats("rrr x = 2;\neega i in 0..x {\n    bahubali(i);\n}").unwrap();
+        assert_eq!(stats.token_count, 18);
+        
+        // A string is one token and a comment is none: bahubali ( "hello world" ) ;
+        let stats = get_compilation_stats("bahubali(\"hello world\"); // say hi").unwrap();
+        assert_eq!(stats.token_count, 5);
+    }
+
     #[test]
     fn test_format_js_code() {
         let js_code = "if (x > 0) {\nconsole.log(x);\n}";
-        let formatted = format_js_code(js_code);
+        let formatted = format_js_code(js_code, "    ");
         
         assert!(formatted.contains("if (x > 0) {"));
         assert!(formatted.contains("console.log(x);"));
         assert!(formatted.contains("}"));
     }
 
+    #[test]
+    fn test_format_normalizes_operator_spacing() {
+        let source = "rrr x = 2;\nbahubali(x + 1, x * 3, \"a + b\");";
+        let options = CompilationOptions::new().with_formatting();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+
+        assert!(js_code.contains("console.log((x + 1), (x * 3), \"a + b\");"));
+        assert!(!js_code.contains("console.log( "));
+        assert_eq!(js_code.matches('+').count(), js_code.matches(" + ").count());
+    }
+
+    #[test]
+    fn test_format_leaves_raw_js_untouched() {
+        let source = "rrr s = \"b\";\nmagadheera(s == \"b\") {\n    rajini { if (s) /b/.test(s);\n  let  y=1; }\n}";
+        let options = CompilationOptions::new().with_formatting();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.contains("if ((s == \"b\")) {"), "got: {}", js_code);
+        assert!(js_code.contains("\n    if (s) /b/.test(s);\n  let  y=1;\n}"), "got: {}", js_code);
+        assert!(!js_code.contains(RAW_JS_START) && !js_code.contains(RAW_JS_END));
+    }
+
+    #[test]
+    fn test_normalize_spacing() {
+        assert_eq!(normalize_spacing("let  z=1+2 ;"), "let z = 1 + 2;");
+        assert_eq!(normalize_spacing("f( a ,b )"), "f(a, b)");
+        assert_eq!(normalize_spacing("x = -y*2e-3"), "x = -y * 2e-3");
+        assert_eq!(normalize_spacing("i++; a=b?c:{k:1}"), "i++; a = b ? c : {k:1}");
+        assert_eq!(normalize_spacing("s = \"a  +  b\" + 'c=d'"), "s = \"a  +  b\" + 'c=d'");
+        assert_eq!(normalize_spacing("r = /a+b/.test(s) // x=1"), "r = /a+b/.test(s) // x=1");
+    }
+
+    #[test]
+    fn test_iife_wrapping() {
+        let source = "rrr x = 1;";
+        let options = CompilationOptions::new().with_iife_wrapping();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.starts_with("(function()"));
+        assert!(js_code.ends_with("})();"));
+        assert!(js_code.contains("const x = 1;"));
+    }
+
+    #[test]
+    fn test_iife_wrapping_keeps_use_strict_inside() {
+        let source = "rrr x = 1;";
+        let options = CompilationOptions::new().with_strict_mode().with_iife_wrapping();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.starts_with("(function() {\n\"use strict\";"));
+        assert!(js_code.ends_with("})();"));
+    }
+
+    #[test]
+    fn test_preserve_comments() {
+        let source = "// the answer\nrrr x = 42;";
+        
+        let options = CompilationOptions::new().with_preserved_comments();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        assert_eq!(js_code, "// the answer\nconst x = 42;");
+        
+        let js_code = compile_with_options(source, &CompilationOptions::new()).unwrap().js_code;
+        assert_eq!(js_code, "const x = 42;");
+    }
+
+    #[test]
+    fn test_explicit_coercion_option() {
+        let source = r#"bahubali("n=" + 5); bahubali(2 + 3);"#;
+        let options = CompilationOptions::new().with_explicit_coercion();
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.contains("console.log((\"n=\" + String(5)));"));
+        assert!(js_code.contains("console.log((2 + 3));"));
+    }
+
+    #[test]
+    fn test_format_with_two_space_indent() {
+        let source = r#"
+            pokiri(1 > 2) {
+                magadheera(1 > 0) {
+                    bahubali("nested");
+                }
+            }
+        "#;
+        let options = CompilationOptions::new().with_formatting().with_indent_width(2);
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.contains("\n  if ((1 > 0)) {\n"));
+        assert!(js_code.contains("\n    console.log(\"nested\");\n"));
+    }
+
+    #[test]
+    fn test_format_with_tabs() {
+        let source = r#"
+            pokiri(1 > 2) {
+                magadheera(1 > 0) {
+                    bahubali("nested");
+                }
+            }
+        "#;
+        let options = CompilationOptions::new().with_formatting().with_indent_style(IndentStyle::Tabs);
+        let js_code = compile_with_options(source, &options).unwrap().js_code;
+        
+        assert!(js_code.contains("\n\tif ((1 > 0)) {\n"));
+        assert!(js_code.contains("\n\t\tconsole.log(\"nested\");\n"));
+    }
+
+    #[test]
+    fn test_merge_prints_option() {
+        let source = r#"
+            bahubali("a");
+            bahubali("b");
+            rrr x = 1;
+            bahubali(x);
+        "#;
+        let options = CompilationOptions::new().with_merged_prints();
+        let result = compile_with_options(source, &options).unwrap();
+        
+        assert_eq!(result.js_code, "console.log(\"a\", \"b\");\nconst x = 1;\nconsole.log(x);");
+        assert_eq!(result.statement_count, 4);
+    }
+
+    #[test]
+    fn test_single_quote_option() {
+        let source = r#"bahubali("hello");"#;
+        let options = CompilationOptions::new()
+            .with_quote_style(QuoteStyle::Single)
+            .with_strict_mode();
+        let result = compile_with_options(source, &options).unwrap();
+        
+        assert_eq!(result.js_code, "'use strict';\nconsole.log('hello');");
+    }
+
+    #[test]
+    fn test_compile_profiled_records_every_phase() {
+        let source = r#"
+            rrr limit = 3;
+            pushpa total = 0;
+            eega(pushpa i = 0; i < limit; i + 1) {
+                bahubali("step", i);
+                bahubali("total", total);
+            }
+            magadheera(total > 1) {
+                bahubali("big");
+            } karthikeya {
+                bahubali("small");
+            }
+        "#;
+        
+        let options = CompilationOptions::new().with_merged_prints();
+        let (result, timings) = compile_profiled(source, &options).unwrap();
+        
+        assert!(result.js_code.contains("for"));
+        assert!(timings.parse.is_some());
+        assert!(timings.validate.is_some());
+        assert!(timings.optimize.is_some());
+        assert!(timings.generate.is_some());
+        assert!(timings.summary().contains("Total"));
+    }
+
+    #[test]
+    fn test_compile_profiled_skips_disabled_optimizer() {
+        let (_, timings) = compile_profiled("bahubali(1);", &CompilationOptions::new()).unwrap();
+        
+        assert!(timings.optimize.is_none());
+        assert!(timings.summary().contains("Optimize: skipped"));
+    }
+
+    #[test]
+    fn test_check_reports_warnings_and_errors() {
+        let options = CompilationOptions::default();
+        
+        let warnings = check("rrr s = \"hi\"; bahubali(s.shout());", &options).unwrap();
+        assert_eq!(warnings.len(), 1);
+        
+        assert!(check("bahubali(missing);", &options).is_err());
+    }
+
     #[test]
     fn test_add_source_comments() {
         let js_code = "console.log('hello');";
@@ -550,5 +1604,20 @@ mod tests {
         assert!(commented.contains("console.log('hello');"));
     }
 
+    #[test]
+    fn test_file_contents_ends_with_one_newline() {
+        let source = "rrr x = 1;\nbahubali(x);";
+        
+        for options in [
+            CompilationOptions::default(),
+            CompilationOptions::default().with_minification(),
+            CompilationOptions::default().with_formatting(),
+        ] {
+            let contents = compile_with_options(source, &options).unwrap().file_contents();
+            assert!(contents.ends_with('\n'));
+            assert!(!contents.ends_with("\n\n"));
+        }
+    }
+
 
 } 
\ No newline at end of file
@@ -0,0 +1,209 @@
+use crate::compiler::CompilationOptions;
+use crate::generator::{IndentStyle, QuoteStyle};
+use crate::lexer::AliasMap;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Name of the project manifest looked up in the working directory
+pub const MANIFEST_FILE: &str = "tfi.toml";
+
+/// Errors that can occur while loading a project manifest
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The manifest exists but could not be read
+    Io(std::io::Error),
+    /// The manifest is not valid TOML or contains unknown keys
+    Parse(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Failed to read {}: {}", MANIFEST_FILE, e),
+            ConfigError::Parse(msg) => write!(f, "Invalid {}: {}", MANIFEST_FILE, msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Project manifest (`tfi.toml`)
+///
+/// ```toml
+/// entry = "src/app.tfi"
+/// output_dir = "dist"
+///
+/// [options]
+/// format = true
+/// minify = false
+///
+/// [aliases]
+/// print = "bahubali"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// TFI file compiled when no input file is given on the command line
+    pub entry: Option<String>,
+    /// Directory the generated JavaScript is written to
+    pub output_dir: Option<String>,
+    /// Default compilation options
+    #[serde(default)]
+    pub options: ManifestOptions,
+    /// Alternative spellings for keywords, alias = "keyword"
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// Default compilation options from the `[options]` table, each mirroring a CLI flag
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestOptions {
+    /// `--format`
+    pub format: Option<bool>,
+    /// `--comments`
+    pub comments: Option<bool>,
+    /// `--strict`
+    pub strict: Option<bool>,
+    /// `--minify`
+    pub minify: Option<bool>,
+    /// `--wrap-iife`
+    pub wrap_iife: Option<bool>,
+    /// `--preserve-comments`
+    pub preserve_comments: Option<bool>,
+    /// `--explicit-coercion`
+    pub explicit_coercion: Option<bool>,
+    /// `--merge-prints`
+    pub merge_prints: Option<bool>,
+    /// `--indent-width N`
+    pub indent_width: Option<usize>,
+    /// `--indent-tabs`
+    pub indent_tabs: Option<bool>,
+    /// `--single-quotes`
+    pub single_quotes: Option<bool>,
+    /// `--no-semicolons`
+    pub no_semicolons: Option<bool>,
+}
+
+impl Manifest {
+    /// Parse a manifest from TOML text
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let manifest: Self = toml::from_str(text).map_err(|e| ConfigError::Parse(e.message().to_string()))?;
+        manifest.alias_map()?;
+        Ok(manifest)
+    }
+    
+    /// Load a manifest from disk, returning `None` if the file does not exist
+    pub fn load(path: &Path) -> Result<Option<Self>, ConfigError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ConfigError::Io(e)),
+        }
+    }
+    
+    /// Build the keyword alias map from the `[aliases]` table
+    pub fn alias_map(&self) -> Result<AliasMap, ConfigError> {
+        let mut aliases = AliasMap::new();
+        for (alias, keyword) in &self.aliases {
+            aliases.insert(alias, keyword).map_err(ConfigError::Parse)?;
+        }
+        Ok(aliases)
+    }
+    
+    /// Build the compilation options the manifest asks for, starting from the defaults
+    pub fn compilation_options(&self) -> CompilationOptions {
+        let opts = &self.options;
+        let defaults = CompilationOptions::default();
+        
+        CompilationOptions {
+            format_output: opts.format.unwrap_or(defaults.format_output),
+            add_comments: opts.comments.unwrap_or(defaults.add_comments),
+            strict_mode: opts.strict.unwrap_or(defaults.strict_mode),
+            minify: opts.minify.unwrap_or(defaults.minify),
+            wrap_iife: opts.wrap_iife.unwrap_or(defaults.wrap_iife),
+            preserve_comments: opts.preserve_comments.unwrap_or(defaults.preserve_comments),
+            explicit_coercion: opts.explicit_coercion.unwrap_or(defaults.explicit_coercion),
+            merge_prints: opts.merge_prints.unwrap_or(defaults.merge_prints),
+            omit_semicolons: opts.no_semicolons.unwrap_or(defaults.omit_semicolons),
+            indent_width: opts.indent_width.unwrap_or(defaults.indent_width),
+            indent_style: match opts.indent_tabs {
+                Some(true) => IndentStyle::Tabs,
+                Some(false) => IndentStyle::Spaces,
+                None => defaults.indent_style,
+            },
+            quote_style: match opts.single_quotes {
+                Some(true) => QuoteStyle::Single,
+                Some(false) => QuoteStyle::Double,
+                None => defaults.quote_style,
+            },
+            // Aliases are checked when the manifest is parsed
+            aliases: self.alias_map().unwrap_or_default(),
+            ..defaults
+        }
+    }
+    
+    /// Get the output path for an input file inside `output_dir`, if one is configured
+    pub fn output_file(&self, input_file: &str, extension: &str) -> Option<String> {
+        let dir = self.output_dir.as_ref()?;
+        let stem = Path::new(input_file).file_stem().unwrap_or_default().to_string_lossy();
+        Some(Path::new(dir).join(format!("{}.{}", stem, extension)).to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = Manifest::parse(r#"
+            entry = "src/app.tfi"
+            output_dir = "dist"
+            
+            [options]
+            minify = true
+            indent_width = 2
+        "#).unwrap();
+        
+        assert_eq!(manifest.entry.as_deref(), Some("src/app.tfi"));
+        assert_eq!(manifest.output_file("src/app.tfi", "js").as_deref(), Some("dist/app.js"));
+        
+        let options = manifest.compilation_options();
+        assert!(options.minify);
+        assert_eq!(manifest.output_file("src/app.tfi", options.output_extension()).as_deref(), Some("dist/app.min.js"));
+        assert_eq!(options.indent_width, 2);
+        assert!(!options.format_output);
+    }
+
+    #[test]
+    fn test_empty_manifest_uses_defaults() {
+        let manifest = Manifest::parse("").unwrap();
+        
+        assert!(manifest.entry.is_none());
+        assert!(manifest.output_file("main.tfi", "js").is_none());
+        assert!(!manifest.compilation_options().minify);
+    }
+
+    #[test]
+    fn test_unknown_key_error() {
+        let error = Manifest::parse("[options]\nminfy = true").unwrap_err();
+        assert!(error.to_string().contains("minfy"));
+    }
+
+    #[test]
+    fn test_manifest_aliases() {
+        let manifest = Manifest::parse("[aliases]\nprint = \"bahubali\"").unwrap();
+        assert_eq!(manifest.compilation_options().aliases.resolve("print"), Some("bahubali"));
+        
+        let error = Manifest::parse("[aliases]\nprint = \"echo\"").unwrap_err();
+        assert!(error.to_string().contains("not a TFI keyword"));
+    }
+
+    #[test]
+    fn test_missing_manifest() {
+        let path = std::env::temp_dir().join("tfi_lang_missing_manifest").join(MANIFEST_FILE);
+        assert!(Manifest::load(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::ast::{Statement, Spanned};
+use crate::compiler::{
+    compilation_warnings, compile_ast, parse_source, validation_context, CompilationOptions, CompilationResult,
+};
+use crate::error_codes;
+use crate::parser::{parse_program_silent, Rule, DEFAULT_MAX_NESTING_DEPTH};
+use crate::validator::{validate_program_in_context, validate_program_with_warnings, ValidationError, Warning};
+use pest::error::LineColLocation;
+use serde::Serialize;
+
+/// A position in the source, zero-based as in the Language Server Protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Position {
+    /// Zero-based line
+    pub line: usize,
+    /// Zero-based character offset within the line
+    pub character: usize,
+}
+
+/// A half-open span of source text
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Range {
+    /// First position covered
+    pub start: Position,
+    /// Position just past the end
+    pub end: Position,
+}
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Compilation failed
+    Error,
+    /// The code compiles but is likely a mistake
+    Warning,
+}
+
+/// A compiler message located in the source, shaped like an LSP `Diagnostic`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// Source span the message applies to
+    pub range: Range,
+    /// Error or warning
+    pub severity: Severity,
+    /// One-line description of the problem
+    pub message: String,
+    /// Error code explained by `tfi-lang --explain`; warnings have none
+    pub code: Option<&'static str>,
+}
+
+/// Compile source, reporting every problem as a located diagnostic instead of printing it
+///
+/// The result is `None` when compilation failed; the diagnostics then hold the error.
+pub fn compile_with_diagnostics(source: &str) -> (Option<CompilationResult>, Vec<Diagnostic>) {
+    let ast = match parse_program_silent(source, false, DEFAULT_MAX_NESTING_DEPTH) {
+        Ok(ast) => ast,
+        Err(e) => return (None, vec![from_parse_error(&e)]),
+    };
+    
+    let warnings = match validate_program_with_warnings(&ast) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            let diagnostic = match e.downcast_ref::<ValidationError>() {
+                Some(error) => from_validation_error(error, &ast, source),
+                None => whole_source_error(e.to_string(), source),
+            };
+            return (None, vec![diagnostic]);
+        }
+    };
+    
+    let diagnostics = warnings.iter().map(|warning| from_warning(warning, &ast, source)).collect();
+    match compile_ast(&ast, &CompilationOptions::default()) {
+        Ok(result) => (Some(result), diagnostics),
+        Err(e) => (None, vec![whole_source_error(e.to_string(), source)]),
+    }
+}
+
+/// Every problem `tfi-lang lint` found in a program, grouped by severity
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LintReport {
+    /// Errors that stop the program compiling; validation stops at the first one
+    pub errors: Vec<Diagnostic>,
+    /// Likely mistakes in code that still compiles
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Whether any error was found
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+    
+    /// Get the report as text, with `file:line:column` locations counted from 1
+    pub fn summary(&self, file: &str) -> String {
+        let mut summary = format!(
+            "{}: {} error{}, {} warning{}",
+            file,
+            self.errors.len(),
+            if self.errors.len() == 1 { "" } else { "s" },
+            self.warnings.len(),
+            if self.warnings.len() == 1 { "" } else { "s" },
+        );
+        for (heading, diagnostics) in [("Errors", &self.errors), ("Warnings", &self.warnings)] {
+            if diagnostics.is_empty() {
+                continue;
+            }
+            summary.push_str(&format!("\n\n{}:", heading));
+            for diagnostic in diagnostics {
+                let start = diagnostic.range.start;
+                let code = diagnostic.code.map(|code| format!("[{}] ", code)).unwrap_or_default();
+                summary.push_str(&format!(
+                    "\n  {}:{}:{}: {}{}",
+                    file, start.line + 1, start.character + 1, code, diagnostic.message
+                ));
+            }
+        }
+        summary
+    }
+    
+    /// Get the report as a JSON object, for editors and CI tooling
+    pub fn to_json(&self) -> String {
+        // Strings, numbers and enums always serialize, so this cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Parse and validate source with `options`, collecting every warning without generating code
+pub fn lint(source: &str, options: &CompilationOptions) -> LintReport {
+    let options = options.clone().with_silent_parsing();
+    let mut report = LintReport::default();
+    
+    let ast = match parse_source(source, &options) {
+        Ok(ast) => ast,
+        Err(e) => {
+            // Parse again for pest's location; errors found after parsing, such as a bad import, have none
+            let aliased = options.aliases.apply(source);
+            let diagnostic = match parse_program_silent(&aliased, options.preserve_comments, options.max_nesting_depth) {
+                Err(parse_error) => from_parse_error(&parse_error),
+                Ok(_) => Diagnostic { code: Some(e.code()), ..whole_source_error(e.to_string(), source) },
+            };
+            report.errors.push(diagnostic);
+            return report;
+        }
+    };
+    
+    match validate_program_in_context(&ast, validation_context(&options)) {
+        Ok(warnings) => {
+            report.warnings.extend(warnings.iter().map(|warning| from_warning(warning, &ast, source)));
+        }
+        Err(e) => report.errors.push(match e.downcast_ref::<ValidationError>() {
+            Some(error) => from_validation_error(error, &ast, source),
+            None => whole_source_error(e.to_string(), source),
+        }),
+    }
+    
+    let style_warnings = compilation_warnings(&ast, &options);
+    report.warnings.extend(style_warnings.iter().map(|warning| from_warning(warning, &ast, source)));
+    
+    report.warnings.sort_by_key(|warning| warning.range.start);
+    report
+}
+
+/// Convert a parse error, whose location pest reports as 1-based line and column
+pub fn from_parse_error(error: &pest::error::Error<Rule>) -> Diagnostic {
+    let (start, end) = match error.line_col {
+        LineColLocation::Pos(pos) => (pos, (pos.0, pos.1 + 1)),
+        LineColLocation::Span(start, end) => (start, end),
+    };
+    
+    Diagnostic {
+        range: Range { start: zero_based(start), end: zero_based(end) },
+        severity: Severity::Error,
+        message: error.variant.message().into_owned(),
+        code: Some(error_codes::SYNTAX_ERROR),
+    }
+}
+
+/// Convert a validation error, covering its own line or else the line of the statement it was found in
+pub fn from_validation_error(error: &ValidationError, statements: &[Spanned<Statement>], source: &str) -> Diagnostic {
+    let message = summary_line(&error.to_string());
+    let (range, message) = match error.line() {
+        Some(line) => (line_range(line - 1, source), message),
+        None => locate_statement(error.statement(), message, statements, source),
+    };
+    Diagnostic {
+        range,
+        severity: Severity::Error,
+        message,
+        code: Some(error.code()),
+    }
+}
+
+/// Convert a validator warning, covering the line of the statement it is about
+pub fn from_warning(warning: &Warning, statements: &[Spanned<Statement>], source: &str) -> Diagnostic {
+    let message = warning.to_string();
+    // Drop the "Statement N: " prefix, which the range replaces
+    let message = message.split_once(": ").map_or(message.as_str(), |(_, rest)| rest).to_string();
+    
+    let (range, message) = locate_statement(warning.statement(), message, statements, source);
+    Diagnostic {
+        range,
+        severity: Severity::Warning,
+        message,
+        code: None,
+    }
+}
+
+/// An error that can't be tied to a statement, placed at the start of the source
+fn whole_source_error(message: String, source: &str) -> Diagnostic {
+    Diagnostic {
+        range: line_range(0, source),
+        severity: Severity::Error,
+        message: summary_line(&message),
+        code: Some(error_codes::COMPILATION_ERROR),
+    }
+}
+
+/// Get the first non-heading line of a multi-line error report
+fn summary_line(report: &str) -> String {
+    report.lines().nth(1).unwrap_or(report).trim().to_string()
+}
+
+/// Place a message about a 1-based top-level statement number on the source line it starts on
+///
+/// A statement inlined from an import has no line in this source, so the message goes at the
+/// start instead and says which file and line it is about.
+fn locate_statement(statement: usize, message: String, statements: &[Spanned<Statement>], source: &str) -> (Range, String) {
+    match statement.checked_sub(1).and_then(|index| statements.get(index)) {
+        Some(Spanned { line, file: Some(file), .. }) => (line_range(0, source), format!("In {}, line {}: {}", file, line, message)),
+        Some(stmt) => (line_range(stmt.line.saturating_sub(1), source), message),
+        None => (line_range(0, source), message),
+    }
+}
+
+/// Cover a zero-based source line, excluding leading indentation
+fn line_range(line: usize, source: &str) -> Range {
+    let text = source.lines().nth(line).unwrap_or("");
+    let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+    
+    Range {
+        start: Position { line, character: indent },
+        end: Position { line, character: text.chars().count() },
+    }
+}
+
+/// Convert pest's 1-based (line, column) to a zero-based position
+fn zero_based((line, column): (usize, usize)) -> Position {
+    Position { line: line.saturating_sub(1), character: column.saturating_sub(1) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undefined_variable_diagnostic() {
+        let source = "rrr x = 1;\n    bahubali(y);\n";
+        let (result, diagnostics) = compile_with_diagnostics(source);
+        
+        assert!(result.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code, Some(error_codes::UNDEFINED_VARIABLE));
+        assert_eq!(diagnostic.message, "Variable 'y' is not defined");
+        assert_eq!(diagnostic.range, Range {
+            start: Position { line: 1, character: 4 },
+            end: Position { line: 1, character: 16 },
+        });
+    }
+
+    #[test]
+    fn test_parse_error_diagnostic() {
+        let (result, diagnostics) = compile_with_diagnostics("rrr x = 1\nbahubali(x);");
+        
+        assert!(result.is_none());
+        assert_eq!(diagnostics[0].code, Some(error_codes::SYNTAX_ERROR));
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+
+    #[test]
+    fn test_warnings_become_diagnostics() {
+        let (result, diagnostics) = compile_with_diagnostics("bahubali(1);\npushpa x = 1;\nbahubali(x);");
+        
+        assert!(result.is_some());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+        assert!(diagnostics[0].message.starts_with("'x' is never reassigned"));
+    }
+
+    #[test]
+    fn test_lint_locates_size_warnings() {
+        let source = "rrr n = 2;\n\npokiri(n > 5) {\n    bahubali(n);\n    bahubali(n);\n}\nbahubali(n, n, n);";
+        let options = CompilationOptions::new().with_max_loop_body(1).with_max_print_args(2);
+        let report = lint(source, &options);
+        
+        assert_eq!(report.warnings.len(), 2);
+        assert_eq!(report.warnings[0].message, "While loop has 2 statements, consider refactoring");
+        assert_eq!(report.warnings[0].range.start.line, 2);
+        assert_eq!(report.warnings[1].message, "Print statement has 3 arguments, consider breaking it up");
+        assert_eq!(report.warnings[1].range.start.line, 6);
+    }
+}
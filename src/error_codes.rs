@@ -0,0 +1,335 @@
+/// A stable error code with a longer explanation, shown by `tfi-lang --explain CODE`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorCode {
+    /// Code as it appears in error messages, e.g. `E0001`
+    pub code: &'static str,
+    /// One-line description of the error
+    pub summary: &'static str,
+    /// What causes the error and how to fix it, with an example
+    pub explanation: &'static str,
+}
+
+/// Reference to a variable that was never declared
+pub const UNDEFINED_VARIABLE: &str = "E0001";
+/// Variable declared twice in the same scope
+pub const DUPLICATE_VARIABLE: &str = "E0002";
+/// Assignment to an `rrr` constant
+pub const CONST_ASSIGNMENT: &str = "E0003";
+/// `bahubali()` with no arguments
+pub const EMPTY_PRINT: &str = "E0004";
+/// Declaration without a variable name
+pub const EMPTY_IDENTIFIER: &str = "E0005";
+/// Control structure with an empty block
+pub const EMPTY_BLOCK: &str = "E0006";
+/// Malformed expression
+pub const INVALID_EXPRESSION: &str = "E0007";
+/// Expression nested deeper than the configured limit
+pub const NESTING_TOO_DEEP: &str = "E0008";
+/// `aagipo` outside of a loop
+pub const BREAK_OUTSIDE_LOOP: &str = "E0009";
+/// `aagipo label` with no enclosing loop of that label
+pub const UNDEFINED_LABEL: &str = "E0010";
+/// Nested loops sharing a label
+pub const DUPLICATE_LABEL: &str = "E0011";
+/// Division by a divisor that is zero at compile time
+pub const DIVISION_BY_ZERO: &str = "E0012";
+/// `import` that was not inlined
+pub const UNRESOLVED_IMPORT: &str = "E0013";
+/// `==` or `!=` between values of different types
+pub const TYPE_MISMATCH: &str = "E0014";
+/// `vaapas` outside of a function
+pub const RETURN_OUTSIDE_FUNCTION: &str = "E0015";
+/// Source that does not match the TFI grammar
+pub const SYNTAX_ERROR: &str = "E0100";
+/// Failure while generating JavaScript
+pub const GENERATION_ERROR: &str = "E0200";
+/// Any other compilation failure
+pub const COMPILATION_ERROR: &str = "E0300";
+
+/// Every known error code
+pub const ERROR_CODES: &[ErrorCode] = &[
+    ErrorCode {
+        code: UNDEFINED_VARIABLE,
+        summary: "Use of an undefined variable",
+        explanation: "\
+A variable was used before it was declared, or outside the block it was declared in.
+
+    bahubali(total);
+
+Declare the variable first with rrr (constant) or pushpa (reassignable):
+
+    pushpa total = 0;
+    bahubali(total);",
+    },
+    ErrorCode {
+        code: DUPLICATE_VARIABLE,
+        summary: "Variable declared more than once",
+        explanation: "\
+A variable with this name is already declared in the current scope.
+
+    pushpa count = 1;
+    pushpa count = 2;
+
+Assign to the existing variable instead of declaring it again, or pick a new name:
+
+    pushpa count = 1;
+    count = 2;",
+    },
+    ErrorCode {
+        code: CONST_ASSIGNMENT,
+        summary: "Assignment to a constant",
+        explanation: "\
+Variables declared with rrr are constants and cannot be reassigned, incremented or decremented.
+
+    rrr score = 10;
+    score = 20;
+
+Declare the variable with pushpa if it needs to change:
+
+    pushpa score = 10;
+    score = 20;",
+    },
+    ErrorCode {
+        code: EMPTY_PRINT,
+        summary: "Print statement without arguments",
+        explanation: "\
+bahubali and salaar need at least one value to print.
+
+    bahubali();
+
+Pass the value to print:
+
+    bahubali(\"Hello, world!\");",
+    },
+    ErrorCode {
+        code: EMPTY_IDENTIFIER,
+        summary: "Declaration without a variable name",
+        explanation: "\
+An rrr or pushpa declaration is missing the name of the variable.
+
+Give the variable a name made of letters:
+
+    rrr name = \"pushpa\";",
+    },
+    ErrorCode {
+        code: EMPTY_BLOCK,
+        summary: "Empty control structure block",
+        explanation: "\
+A magadheera, karthikeya, pokiri or eega block has no statements. Blocks containing
+only comments count as empty.
+
+    magadheera(x > 5) {
+    }
+
+Add the statements the block should run, or remove the control structure:
+
+    magadheera(x > 5) {
+        bahubali(\"x is large\");
+    }",
+    },
+    ErrorCode {
+        code: INVALID_EXPRESSION,
+        summary: "Malformed expression",
+        explanation: "\
+An expression uses an unknown operator, an empty name, or a malformed number.
+This usually means the AST was built by hand rather than parsed from source.
+
+Use one of the supported operators: + - * / ~/ > < >= <= == !=
+
+    bahubali(7 ~/ 2);",
+    },
+    ErrorCode {
+        code: NESTING_TOO_DEEP,
+        summary: "Expression nested too deeply",
+        explanation: "\
+An expression is nested deeper than the compiler allows, which would risk a stack overflow.
+
+Split the expression into smaller parts using intermediate variables:
+
+    rrr inner = (a + b) * c;
+    rrr result = inner - d;",
+    },
+    ErrorCode {
+        code: BREAK_OUTSIDE_LOOP,
+        summary: "Break outside of a loop",
+        explanation: "\
+aagipo exits a loop, so it can only appear inside a pokiri or eega body.
+
+    magadheera(done) {
+        aagipo;
+    }
+
+Move the aagipo into the loop it should stop:
+
+    pokiri(running) {
+        magadheera(done) {
+            aagipo;
+        }
+    }",
+    },
+    ErrorCode {
+        code: UNDEFINED_LABEL,
+        summary: "Break to an unknown label",
+        explanation: "\
+aagipo names a label that none of the loops around it carries. Labels are only visible
+inside the loop they are attached to.
+
+    pokiri(a < 3) {
+        aagipo outer;
+    }
+
+Label the loop that should be exited:
+
+    outer: pokiri(a < 3) {
+        aagipo outer;
+    }",
+    },
+    ErrorCode {
+        code: DUPLICATE_LABEL,
+        summary: "Loop label used twice",
+        explanation: "\
+A loop nested inside another loop reuses the outer loop's label, so aagipo could not tell
+them apart.
+
+    outer: pokiri(a < 3) {
+        outer: pokiri(b < 3) { aagipo outer; }
+    }
+
+Give each nested loop its own label:
+
+    outer: pokiri(a < 3) {
+        inner: pokiri(b < 3) { aagipo outer; }
+    }",
+    },
+    ErrorCode {
+        code: DIVISION_BY_ZERO,
+        summary: "Division by zero",
+        explanation: "\
+The right-hand side of / or ~/ is the number 0. In JavaScript this produces Infinity or NaN
+rather than an error, which is almost never intended. With --optimize, divisors that fold
+to zero, such as (5 - 5), are caught too.
+
+    bahubali(10 / 0);
+
+Divide by a non-zero value, or check the divisor before dividing:
+
+    magadheera(count != 0) {
+        bahubali(total / count);
+    }",
+    },
+    ErrorCode {
+        code: UNRESOLVED_IMPORT,
+        summary: "Import that could not be resolved",
+        explanation: "\
+Imports are inlined when a file is compiled, relative to the directory of the importing
+file. They must be at the top level of the file, and source compiled without a file
+path, such as from the compiler library, cannot import.
+
+    magadheera(debug) {
+        import \"logging.tfi\";
+    }
+
+Move the import to the top of the file:
+
+    import \"logging.tfi\";",
+    },
+    ErrorCode {
+        code: TYPE_MISMATCH,
+        summary: "Equality between values of different types",
+        explanation: "\
+== and != compile to JavaScript's loose equality, which converts operands of different
+types before comparing them, so 1 == (x > 0) is true whenever x is positive and 5 == \"5\"
+is true. Comparing a number, a string or a boolean with a value of another type is
+almost certainly a mistake.
+
+    rrr count = 5;
+    magadheera(count == \"5\") { bahubali(\"five\"); }
+
+Compare values of the same type:
+
+    magadheera(count == 5) { bahubali(\"five\"); }",
+    },
+    ErrorCode {
+        code: RETURN_OUTSIDE_FUNCTION,
+        summary: "Return outside of a function",
+        explanation: "\
+vaapas returns a value from the fn it is written in, so it can only appear inside a fn body.
+
+    rrr total = 5;
+    vaapas total;
+
+Move the vaapas into the function that should return the value:
+
+    rrr total = fn(a, b) {
+        vaapas a + b;
+    };",
+    },
+    ErrorCode {
+        code: SYNTAX_ERROR,
+        summary: "Syntax error",
+        explanation: "\
+The source does not match the TFI grammar. Common causes are a missing semicolon,
+missing parentheses around a condition, or using = where == was meant.
+
+    magadheera(x = 5) { bahubali(x); }
+
+Check the statement against the syntax of its keyword:
+
+    magadheera(x == 5) { bahubali(x); }",
+    },
+    ErrorCode {
+        code: GENERATION_ERROR,
+        summary: "JavaScript generation failed",
+        explanation: "\
+The program was valid but JavaScript could not be generated for it. This is a compiler
+bug; please report it with the TFI source that triggered it.",
+    },
+    ErrorCode {
+        code: COMPILATION_ERROR,
+        summary: "Compilation failed",
+        explanation: "\
+Compilation stopped for a reason not covered by a more specific code, most often because
+the source could not be parsed. The parser prints the location and a suggestion above
+this error.
+
+Fix the reported line, for example by adding a missing semicolon:
+
+    rrr x = 10;",
+    },
+];
+
+/// Look up an error code, ignoring case
+pub fn lookup(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES.iter().find(|entry| entry.code.eq_ignore_ascii_case(code))
+}
+
+/// Get the full `--explain` text for an error code
+pub fn explain(code: &str) -> Option<String> {
+    lookup(code).map(|entry| format!("{}: {}\n\n{}", entry.code, entry.summary, entry.explanation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_undefined_variable() {
+        let text = explain("E0001").unwrap();
+        
+        assert!(text.contains("undefined"));
+        assert!(text.contains("pushpa total = 0;"));
+    }
+
+    #[test]
+    fn test_lookup_ignores_case() {
+        assert_eq!(lookup("e0003").map(|entry| entry.code), Some(CONST_ASSIGNMENT));
+        assert!(lookup("E9999").is_none());
+    }
+
+    #[test]
+    fn test_codes_are_unique() {
+        for (i, entry) in ERROR_CODES.iter().enumerate() {
+            assert!(ERROR_CODES[i + 1..].iter().all(|other| other.code != entry.code));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// pest errors are returned by value, as in the parser
+#![allow(clippy::result_large_err)]
+
+use crate::ast::{Expression, Spanned, Statement};
+use crate::parser::{parse_formatted, FormattedProgram, Rule};
+
+/// Indentation for each level of block nesting in formatted TFI
+const INDENT: &str = "    ";
+
+/// Reformat TFI source with canonical spacing and indentation, keeping comments and blank lines
+pub fn format_source(source: &str) -> Result<String, pest::error::Error<Rule>> {
+    parse_formatted(source).map(|program| format_program(&program))
+}
+
+/// Print a parsed program back as TFI source
+///
+/// Runs of blank lines between statements collapse to one, and blank lines at the start of a
+/// block are dropped. Compound assignments come back in their desugared `x = x + 1` form.
+pub fn format_program(program: &FormattedProgram) -> String {
+    format_block(&program.statements, 0, program)
+}
+
+/// Format each statement of a block on its own line at the given depth
+fn format_block(block: &[Spanned<Statement>], depth: usize, program: &FormattedProgram) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&Spanned<Statement>> = None;
+    for stmt in block {
+        let follows_on_same_line = previous.is_some_and(|previous| previous.line == stmt.line);
+        
+        // A comment written after a one-line statement stays at the end of that line
+        if let Statement::Comment(text) = &stmt.node
+            && follows_on_same_line
+            && out.ends_with(";\n")
+        {
+            out.pop();
+            out.push_str(&format!("  {}\n", comment(text)));
+            continue;
+        }
+        
+        if previous.is_some() && !follows_on_same_line && program.has_blank_line_before(stmt.line) {
+            out.push('\n');
+        }
+        out.push_str(&INDENT.repeat(depth));
+        out.push_str(&format_statement(&stmt.node, depth, program));
+        out.push('\n');
+        previous = Some(stmt);
+    }
+    out
+}
+
+/// Format a `{ ... }` block whose closing brace lines up with the statement at `depth`
+fn braced(block: &[Spanned<Statement>], depth: usize, program: &FormattedProgram) -> String {
+    format!("{{\n{}{}}}", format_block(block, depth + 1, program), INDENT.repeat(depth))
+}
+
+/// Format a `// text` comment
+fn comment(text: &str) -> String {
+    if text.is_empty() { "//".to_string() } else { format!("// {}", text) }
+}
+
+/// Format a statement, indenting any nested lines for the statement's depth
+fn format_statement(stmt: &Statement, depth: usize, program: &FormattedProgram) -> String {
+    let expr = |e: &Expression| format_expression(e, depth, program);
+    let list = |exprs: &[Expression]| exprs.iter().map(expr).collect::<Vec<_>>().join(", ");
+    match stmt {
+        Statement::Print(exprs) => format!("bahubali({});", list(exprs)),
+        Statement::PrintJoined(exprs, sep, end) => {
+            let mut args = exprs.iter().map(expr).collect::<Vec<_>>();
+            // The parser fills in whichever of sep= and end= was left out
+            if *sep != Expression::String(" ".to_string()) {
+                args.push(format!("sep={}", expr(sep)));
+            }
+            if *end != Expression::String("\n".to_string()) {
+                args.push(format!("end={}", expr(end)));
+            }
+            if args.len() == exprs.len() {
+                args.push(format!("sep={}", expr(sep)));
+            }
+            format!("bahubali({});", args.join(", "))
+        }
+        Statement::PrintInline(exprs) => format!("salaar({});", list(exprs)),
+        // The text is printed verbatim, so only the closing brace follows the depth
+        Statement::RawPrint(text) => format!("bahubali_block {{\n{}\n{}}}", text, INDENT.repeat(depth)),
+        Statement::RawJs(code) => format!("rajini {{{}}}", code),
+        Statement::Clear => "wipe();".to_string(),
+        Statement::Const(name, value) => format!("rrr {} = {};", name, expr(value)),
+        Statement::MultiConst(declarators) => {
+            let declarators = declarators.iter()
+                .map(|(name, value)| format!("{} = {}", name, expr(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("rrr {};", declarators)
+        }
+        Statement::Let(name, value) => format!("pushpa {} = {};", name, expr(value)),
+        Statement::Assign(..) | Statement::Increment(..) | Statement::Expr(_) => {
+            format!("{};", format_clause(stmt, depth, program))
+        }
+        Statement::If(cond, then_block, else_block) => {
+            let else_code = else_block.as_ref()
+                .map(|block| format!(" karthikeya {}", braced(block, depth, program)))
+                .unwrap_or_default();
+            format!("magadheera({}) {}{}", expr(cond), braced(then_block, depth, program), else_code)
+        }
+        Statement::While(cond, body) => format!("pokiri({}) {}", expr(cond), braced(body, depth, program)),
+        Statement::For(init, cond, update, body) => {
+            let init_code = init.as_ref().map(|init| format_clause(init, depth, program)).unwrap_or_default();
+            let cond_code = cond.as_ref().map(|cond| format!(" {}", expr(cond))).unwrap_or_default();
+            let update_code = update.as_ref()
+                .map(|update| format!(" {}", format_clause(update, depth, program)))
+                .unwrap_or_default();
+            format!("eega({};{};{}) {}", init_code, cond_code, update_code, braced(body, depth, program))
+        }
+        Statement::ForRange(name, start, end, body) => {
+            format!("eega {} in {}..{} {}", name, expr(start), expr(end), braced(body, depth, program))
+        }
+        Statement::Labeled(label, body) => format!("{}: {}", label, format_statement(body, depth, program)),
+        Statement::Break(Some(label)) => format!("aagipo {};", label),
+        Statement::Break(None) => "aagipo;".to_string(),
+        Statement::Return(Some(value)) => format!("vaapas {};", expr(value)),
+        Statement::Return(None) => "vaapas;".to_string(),
+        // The condition is kept as written, which is also what the failure message quotes
+        Statement::Assert(_, source) => format!("nammaka({});", source),
+        Statement::Comment(text) => comment(text),
+        Statement::Import(path) => format!("import {};", quote(path)),
+    }
+}
+
+/// Format a statement without its `;`, as used in an `eega(...)` header
+fn format_clause(stmt: &Statement, depth: usize, program: &FormattedProgram) -> String {
+    match stmt {
+        Statement::Assign(name, value) => format!("{} = {}", name, format_expression(value, depth, program)),
+        Statement::Increment(name, is_decrement) => format!("{}{}", name, if *is_decrement { "--" } else { "++" }),
+        Statement::Expr(value) => format_expression(value, depth, program),
+        other => format_statement(other, depth, program).trim_end_matches(';').to_string(),
+    }
+}
+
+/// Format an expression; parentheses come only from the `Grouping` nodes the source wrote
+fn format_expression(expr: &Expression, depth: usize, program: &FormattedProgram) -> String {
+    let format = |e: &Expression| format_expression(e, depth, program);
+    let list = |exprs: &[Expression]| exprs.iter().map(format).collect::<Vec<_>>().join(", ");
+    match expr {
+        Expression::Number(n) => n.to_string(),
+        Expression::Float(text) => text.clone(),
+        Expression::Identifier(name) => name.clone(),
+        Expression::String(text) => quote(text),
+        Expression::BinaryOp(left, op, right) => format!("{} {} {}", format(left), op, format(right)),
+        Expression::Member(object, field) => format!("{}.{}", format(object), field),
+        Expression::OptionalMember(object, field) => format!("{}?.{}", format(object), field),
+        Expression::Grouping(inner) => format!("({})", format(inner)),
+        Expression::MethodCall(object, method, args) => format!("{}.{}({})", format(object), method, list(args)),
+        Expression::Object(entries) if entries.is_empty() => "{}".to_string(),
+        Expression::Object(entries) => {
+            let entries = entries.iter()
+                .map(|(key, value)| format!("{}: {}", key, format(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{{ {} }}", entries)
+        }
+        Expression::Index(object, index) => format!("{}[{}]", format(object), format(index)),
+        Expression::IndexFromEnd(object, n) => format!("{}[-{}]", format(object), n),
+        Expression::Slice(object, start, end) => format!("{}[{}..{}]", format(object), format(start), format(end)),
+        // Only the optimizer folds, so print what the source said
+        Expression::Folded(_, original) => format(original),
+        Expression::Lambda(params, body) => format!("fn({}) {}", params.join(", "), braced(body, depth, program)),
+        Expression::Call(callee, args) => format!("{}({})", format(callee), list(args)),
+        Expression::Conditional(cond, then, otherwise) => {
+            format!("{} ? {} : {}", format(cond), format(then), format(otherwise))
+        }
+    }
+}
+
+/// Quote text as a TFI string literal, escaping what the grammar doesn't accept as written
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            ' '..='~' => quoted.push(c),
+            other => quoted.push_str(&format!("\\u{{{:x}}}", other as u32)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn test_format_keeps_one_blank_line_between_sections() {
+        let source = "rrr x = 1;\npushpa y = 2;\n\n\n\n// Output\nbahubali(x,y);\n";
+        let formatted = format_source(source).unwrap();
+        
+        assert_eq!(formatted, "rrr x = 1;\npushpa y = 2;\n\n// Output\nbahubali(x, y);\n");
+    }
+
+    #[test]
+    fn test_format_indents_blocks_and_keeps_comments() {
+        let source = "pushpa i=0;\npokiri(i<3){\n\n  bahubali(i); // each one\n\n      i=i+1;}\nmagadheera(i==3){bahubali(\"done\\n\");}karthikeya{wipe();}\n";
+        let formatted = format_source(source).unwrap();
+        
+        assert_eq!(formatted, "pushpa i = 0;\npokiri(i < 3) {\n    bahubali(i);  // each one\n\n    i = i + 1;\n}\n\
+            magadheera(i == 3) {\n    bahubali(\"done\\n\");\n} karthikeya {\n    wipe();\n}\n");
+    }
+
+    #[test]
+    fn test_format_indents_nested_raw_block_end() {
+        let source = "pokiri(1 < 2) {\nbahubali_block {\n  as written\n}\naagipo;\n}\n";
+        let formatted = format_source(source).unwrap();
+        
+        assert_eq!(formatted, "pokiri(1 < 2) {\n    bahubali_block {\n  as written\n    }\n    aagipo;\n}\n");
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_keeps_comment_after_if_block() {
+        let source = "pushpa c = 1;\nmagadheera(c == 1) { bahubali(\"x\"); } // note\nbahubali(\"y\");\n";
+        let formatted = format_source(source).unwrap();
+        
+        assert_eq!(formatted, "pushpa c = 1;\nmagadheera(c == 1) {\n    bahubali(\"x\");\n}\n// note\nbahubali(\"y\");\n");
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        let source = "rrr greet = fn(name) {\n    vaapas \"hi \" + name;\n};\n\
+            eega(pushpa i = 0; i < 2; i++) {\n    bahubali(greet(\"x\")[0..2], (i + 1) * 2, i > 0 ? \"a\" : \"b\");\n}\n\
+            outer: eega j in 0..3 {\n    aagipo outer;\n}\n\
+            bahubali(1, 2, sep=\"-\");\nnammaka(1 == 1);\n";
+        let formatted = format_source(source).unwrap();
+        
+        assert_eq!(formatted, source);
+        assert!(parse_program(&formatted).is_ok());
+    }
+}
@@ -1,55 +1,698 @@
-use crate::ast::{Statement, Expression};
+use crate::ast::{Statement, Spanned, Expression, js_string_method, precedence};
+use crate::types::{TypeEnv, ValueType};
+use std::collections::HashSet;
+
+/// Quote character used for string literals in generated code
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+pub enum QuoteStyle {
+    /// Emit "double-quoted" strings
+    #[default]
+    Double,
+    /// Emit 'single-quoted' strings
+    Single,
+}
+
+impl QuoteStyle {
+    /// Quote text as a JavaScript string literal, escaping backslashes, the quote character and line breaks
+    pub fn quote(&self, text: &str) -> String {
+        let quote = match self {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        };
+        
+        let mut quoted = String::with_capacity(text.len() + 2);
+        quoted.push(quote);
+        for c in text.chars() {
+            match c {
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                // Other control characters and the JS line terminators can't appear raw in a literal
+                c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                    quoted.push_str(&format!("\\u{:04x}", c as u32));
+                }
+                c => {
+                    if c == quote || c == '\\' {
+                        quoted.push('\\');
+                    }
+                    quoted.push(c);
+                }
+            }
+        }
+        quoted.push(quote);
+        quoted
+    }
+}
+
+/// Where the output of print statements goes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+pub enum OutputMode {
+    /// Print to stdout with `console.log` and `process.stdout.write`
+    #[default]
+    ConsoleLog,
+    /// Wrap the program in `function run()`, which collects printed text and returns it
+    ReturnString,
+}
+
+/// Name of the buffer printed text is collected in under `OutputMode::ReturnString`
+const OUTPUT_BUFFER: &str = "__tfi_output";
+
+/// Counter a guarded loop increments on each iteration
+const LOOP_COUNTER: &str = "__tfi_iterations";
+
+/// Marks the start of raw JavaScript under `GeneratorOptions::mark_raw_js`
+pub const RAW_JS_START: char = '\u{E000}';
+
+/// Marks the end of raw JavaScript under `GeneratorOptions::mark_raw_js`
+pub const RAW_JS_END: char = '\u{E001}';
+
+/// Options controlling JavaScript generation
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    /// Wrap numbers concatenated with strings in `String(...)`
+    pub explicit_coercion: bool,
+    /// Quote character for string literals
+    pub quote_style: QuoteStyle,
+    /// Print values inferred to be objects with `JSON.stringify(...)`
+    pub stringify_objects: bool,
+    /// Leave statements unterminated, relying on automatic semicolon insertion
+    pub omit_semicolons: bool,
+    /// Print to stdout or return the printed text from a function
+    pub output_mode: OutputMode,
+    /// Leave out the spaces around binary operators and parentheses that precedence makes redundant
+    pub minify: bool,
+    /// Throw from any loop that runs more than this many iterations, instead of hanging
+    pub loop_guard: Option<u32>,
+    /// Surround `rajini` code with `RAW_JS_START` and `RAW_JS_END`, so later passes can skip it
+    pub mark_raw_js: bool,
+}
+
+/// State shared by every node while emitting a program
+#[derive(Clone, Copy, Default)]
+struct EmitContext<'a> {
+    /// Type environment, present when an option needs inferred types
+    types: Option<&'a TypeEnv>,
+    quote_style: QuoteStyle,
+    explicit_coercion: bool,
+    stringify_objects: bool,
+    omit_semicolons: bool,
+    output_mode: OutputMode,
+    minify: bool,
+    loop_guard: Option<u32>,
+    mark_raw_js: bool,
+}
 
 /// Generate JavaScript code from a TFI statement
 pub fn generate_statement(stmt: &Statement) -> String {
+    emit_statement(stmt, EmitContext::default())
+}
+
+/// Generate JavaScript code from a TFI expression
+pub fn generate_expression(expr: &Expression) -> String {
+    emit_expression(expr, EmitContext::default())
+}
+
+/// Generate JavaScript code from a TFI expression, making string/number coercions explicit
+///
+/// A `+` that mixes a string with a number wraps the number in `String(...)`.
+pub fn generate_expression_typed(expr: &Expression, types: &TypeEnv) -> String {
+    emit_expression(expr, EmitContext { types: Some(types), explicit_coercion: true, ..EmitContext::default() })
+}
+
+/// Generate statement code, terminated with the punctuation its kind requires
+fn emit_statement(stmt: &Statement, ctx: EmitContext) -> String {
+    let code = emit_statement_body(stmt, ctx);
+    if ctx.omit_semicolons {
+        guard_asi(code)
+    } else {
+        terminate(code, needs_semicolon(stmt))
+    }
+}
+
+/// Prefix a `;` to an unterminated statement that would otherwise continue the line before it
+///
+/// Without semicolons, a line starting with `(` or `[` is read as a call or an index on the
+/// previous statement's value.
+fn guard_asi(stmt_code: String) -> String {
+    if stmt_code.trim_start_matches(RAW_JS_START).starts_with(['(', '[']) {
+        format!(";{}", stmt_code)
+    } else {
+        stmt_code
+    }
+}
+
+/// Whether a statement must end with a semicolon
+///
+/// Block statements end with their closing brace, comments run to the end of the line and raw
+/// JavaScript is emitted exactly as written.
+fn needs_semicolon(stmt: &Statement) -> bool {
+    !matches!(stmt, Statement::If(..) | Statement::While(..) | Statement::For(..) | Statement::ForRange(..) | Statement::Labeled(..) | Statement::Comment(_) | Statement::Import(_) | Statement::RawJs(_))
+}
+
+/// Append the statement terminator, if the statement needs one
+fn terminate(stmt_code: String, needs_semicolon: bool) -> String {
+    if needs_semicolon {
+        stmt_code + ";"
+    } else {
+        stmt_code
+    }
+}
+
+/// Generate statement code without the trailing semicolon
+fn emit_statement_body(stmt: &Statement, ctx: EmitContext) -> String {
+    let expr = |e: &Expression| emit_expression(e, ctx);
+    let block = |b: &[Spanned<Statement>]| b.iter().map(|s| emit_statement(s, ctx)).collect::<Vec<_>>().join("\n");
     match stmt {
         Statement::Print(expressions) => {
-            let args = expressions.iter().map(generate_expression).collect::<Vec<_>>().join(", ");
-            format!("console.log({});", args)
+            let args = expressions.iter()
+                .map(|e| if is_object(e, ctx) { format!("JSON.stringify({})", expr(e)) } else { expr(e) })
+                .collect::<Vec<_>>()
+                .join(", ");
+            match ctx.output_mode {
+                // console.log separates its arguments with spaces and ends the line
+                OutputMode::ReturnString => format!(
+                    "{} += [{}].join({}) + {}",
+                    OUTPUT_BUFFER, args, ctx.quote_style.quote(" "), ctx.quote_style.quote("\n")
+                ),
+                OutputMode::ConsoleLog => format!("console.log({})", args),
+            }
+        },
+        Statement::PrintInline(expressions) => {
+            let args = expressions.iter()
+                .map(|e| format!("{}({})", if is_object(e, ctx) { "JSON.stringify" } else { "String" }, expr(e)))
+                .collect::<Vec<_>>()
+                .join(" + ");
+            write_output(&args, ctx)
+        },
+        Statement::PrintJoined(expressions, sep, end) => {
+            // join() stringifies the values itself, so only objects need converting
+            let args = expressions.iter()
+                .map(|e| if is_object(e, ctx) { format!("JSON.stringify({})", expr(e)) } else { expr(e) })
+                .collect::<Vec<_>>()
+                .join(", ");
+            write_output(&format!("[{}].join({}) + {}", args, expr(sep), expr(end)), ctx)
+        },
+        Statement::RawPrint(text) => match ctx.output_mode {
+            OutputMode::ReturnString => write_output(&format!("{} + {}", template_literal(text), ctx.quote_style.quote("\n")), ctx),
+            OutputMode::ConsoleLog => format!("console.log({})", template_literal(text)),
+        },
+        Statement::RawJs(code) if ctx.mark_raw_js => format!("{}{}{}", RAW_JS_START, code.trim(), RAW_JS_END),
+        Statement::RawJs(code) => code.trim().to_string(),
+        Statement::Clear => match ctx.output_mode {
+            OutputMode::ReturnString => format!("{} = {}", OUTPUT_BUFFER, ctx.quote_style.quote("")),
+            OutputMode::ConsoleLog => "console.clear()".to_string(),
+        },
+        Statement::Const(id, e) => format!("const {} = {}", id, expr(e)),
+        Statement::MultiConst(declarators) => {
+            let decls = declarators.iter().map(|(id, e)| format!("{} = {}", id, expr(e))).collect::<Vec<_>>().join(", ");
+            format!("const {}", decls)
+        },
+        Statement::Let(id, e) => format!("let {} = {}", id, expr(e)),
+        Statement::Assign(id, e) => format!("{} = {}", id, expr(e)),
+        Statement::Increment(id, is_decrement) => {
+            format!("{}{}", id, if *is_decrement { "--" } else { "++" })
         },
-        Statement::Const(id, expr) => format!("const {} = {};", id, generate_expression(expr)),
-        Statement::Let(id, expr) => format!("let {} = {};", id, generate_expression(expr)),
         Statement::If(cond, then_block, else_block) => {
-            let then_code = then_block.iter().map(generate_statement).collect::<Vec<_>>().join("\n");
-            let else_code = else_block.as_ref().map(|block| {
-                format!(" else {{\n{}\n}}", block.iter().map(generate_statement).collect::<Vec<_>>().join("\n"))
+            let then_code = block(then_block);
+            let else_code = else_block.as_ref().map(|b| {
+                format!(" else {{\n{}\n}}", block(b))
             }).unwrap_or_default();
-            format!("if ({}) {{\n{}\n}}{}", generate_expression(cond), then_code, else_code)
+            format!("if ({}) {{\n{}\n}}{}", expr(cond), then_code, else_code)
+        },
+        Statement::While(cond, body) => {
+            guard_loop(format!("while ({}) {{\n{}\n}}", expr(cond), loop_body(body, ctx)), ctx)
         },
-        Statement::While(cond, block) => {
-            let block_code = block.iter().map(generate_statement).collect::<Vec<_>>().join("\n");
-            format!("while ({}) {{\n{}\n}}", generate_expression(cond), block_code)
+        Statement::For(init, cond, update, body) => {
+            // The for header supplies its own separators, so the init is emitted unterminated
+            let init_code = init.as_ref().map(|init| emit_statement_body(init, ctx)).unwrap_or_default();
+            // Empty clauses leave no space, so `eega(;;)` becomes `for (;;)`
+            let cond_code = cond.as_ref().map(|cond| format!(" {}", expr(cond))).unwrap_or_default();
+            let update_code = update.as_ref().map(|update| format!(" {}", emit_statement_body(update, ctx))).unwrap_or_default();
+            guard_loop(format!("for ({};{};{}) {{\n{}\n}}", init_code, cond_code, update_code, loop_body(body, ctx)), ctx)
         },
-        Statement::For(init, cond, update, block) => {
-            let init_code = generate_statement(init);
-            let cond_code = generate_expression(cond);
-            let update_code = generate_expression(update);
-            let block_code = block.iter().map(generate_statement).collect::<Vec<_>>().join("\n");
-            format!("for ({}; {}; {}) {{\n{}\n}}", init_code.trim_end_matches(';'), cond_code, update_code, block_code)
+        Statement::ForRange(name, start, end, body) => {
+            guard_loop(format!(
+                "for (let {name} = {}; {name} < {}; {name}++) {{\n{}\n}}",
+                expr(start), expr(end), loop_body(body, ctx)
+            ), ctx)
         },
+        Statement::Labeled(label, body) => format!("{}: {}", label, emit_statement_body(body, ctx)),
+        Statement::Break(Some(label)) => format!("break {}", label),
+        Statement::Break(None) => "break".to_string(),
+        Statement::Return(Some(value)) => format!("return {}", expr(value)),
+        Statement::Return(None) => "return".to_string(),
+        Statement::Assert(cond, source) => {
+            // Binary operations are already parenthesized, unless minifying
+            let negated = match cond {
+                _ if ctx.minify => format!("!{}", emit_operand(cond, u8::MAX, ctx)),
+                Expression::BinaryOp(_, _, _) | Expression::Conditional(_, _, _) | Expression::Grouping(_) => format!("!{}", expr(cond)),
+                _ => format!("!({})", expr(cond)),
+            };
+            let message = ctx.quote_style.quote(&format!("assertion failed: {}", source));
+            format!("if ({}) throw new Error({})", negated, message)
+        },
+        Statement::Comment(text) => format!("// {}", text),
+        // Imports are inlined before generation, so one left over is only noted
+        Statement::Import(path) => format!("// import {}", QuoteStyle::Double.quote(path)),
+        Statement::Expr(e) => expr(e),
     }
 }
 
-/// Generate JavaScript code from a TFI expression
-pub fn generate_expression(expr: &Expression) -> String {
+/// Quote text as a JavaScript template literal
+///
+/// Line breaks are escaped so the literal stays on one line, out of reach of the line-based
+/// formatter and source comments.
+fn template_literal(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('`');
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '`' | '\\' => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            // Only "${" starts a substitution, so a lone "$" is left as is
+            '$' if chars.peek() == Some(&'{') => literal.push_str("\\$"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('`');
+    literal
+}
+
+/// Emit an operand, parenthesizing it when minifying if it binds looser than `min_strength`
+///
+/// Without minification, binary operations carry their own parentheses already.
+fn emit_operand(expr: &Expression, min_strength: u8, ctx: EmitContext) -> String {
+    let code = emit_expression(expr, ctx);
+    if ctx.minify && binding_strength(expr) < min_strength {
+        format!("({})", code)
+    } else {
+        code
+    }
+}
+
+/// How tightly an expression holds together as an operand, on the scale of `precedence`
+fn binding_strength(expr: &Expression) -> u8 {
+    match ungrouped(expr) {
+        // Integer division is emitted as a helper call
+        Expression::BinaryOp(_, op, _) if op != "~/" => precedence(op),
+        // An arrow function's body would extend over anything after it
+        Expression::Lambda(_, _) => 0,
+        _ => u8::MAX,
+    }
+}
+
+/// The expression inside any number of parentheses
+fn ungrouped(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::Grouping(inner) => ungrouped(inner),
+        _ => expr,
+    }
+}
+
+/// Whether an expression is a name or a chain of fields on one, so repeating it has no effects
+fn is_plain_path(expr: &Expression) -> bool {
+    match expr {
+        Expression::Identifier(_) => true,
+        Expression::Member(object, _) => is_plain_path(object),
+        _ => false,
+    }
+}
+
+/// Whether a print argument should be wrapped in `JSON.stringify(...)`
+fn is_object(expr: &Expression, ctx: EmitContext) -> bool {
+    ctx.stringify_objects && ctx.types.is_some_and(|types| types.infer(expr) == ValueType::Object)
+}
+
+/// Generate expression code, making coercions explicit when enabled
+fn emit_expression(expr: &Expression, ctx: EmitContext) -> String {
     match expr {
         Expression::Number(n) => n.to_string(),
+        Expression::Float(f) => f.clone(),
         Expression::Identifier(id) => id.clone(),
-        Expression::String(s) => format!("\"{}\"", s),
+        Expression::String(s) => ctx.quote_style.quote(s),
+        Expression::BinaryOp(left, op, right) if op == "~/" => {
+            // JS has no integer division operator, so the prelude helper floors the float result
+            format!("{}({}, {})", Helper::FloorDiv.name(), emit_expression(left, ctx), emit_expression(right, ctx))
+        },
         Expression::BinaryOp(left, op, right) => {
-            format!("({} {} {})", generate_expression(left), op, generate_expression(right))
+            // Operators group left to right, so a right operand of the same strength keeps its parentheses
+            let mut left_code = emit_operand(left, precedence(op), ctx);
+            let mut right_code = emit_operand(right, precedence(op) + 1, ctx);
+            if let Some(types) = ctx.types && ctx.explicit_coercion && op == "+" {
+                match (types.infer(left), types.infer(right)) {
+                    (ValueType::String, ValueType::Number) => right_code = format!("String({})", right_code),
+                    (ValueType::Number, ValueType::String) => left_code = format!("String({})", left_code),
+                    _ => {}
+                }
+            }
+            if !ctx.minify {
+                format!("({} {} {})", left_code, op, right_code)
+            } else if (op == "+" || op == "-") && right_code.starts_with(op.as_str()) {
+                // `a - -1` written as `a--1` would read as a decrement
+                format!("{} {} {}", left_code, op, right_code)
+            } else {
+                format!("{}{}{}", left_code, op, right_code)
+            }
+        },
+        Expression::Member(object, field) => format!("{}.{}", emit_operand(object, u8::MAX, ctx), field),
+        Expression::OptionalMember(object, field) => format!("{}?.{}", emit_operand(object, u8::MAX, ctx), field),
+        // The parent decides whether a minified operand needs parentheses, but `{` or `(...) =>`
+        // keep theirs so they aren't read as a block or swallow what follows
+        Expression::Grouping(inner) if ctx.minify && !matches!(ungrouped(inner), Expression::Object(_) | Expression::Lambda(_, _)) => {
+            emit_expression(inner, ctx)
+        },
+        // Binary operations and conditionals already carry their own parentheses
+        Expression::Grouping(inner) => match **inner {
+            Expression::BinaryOp(_, _, _) | Expression::Conditional(_, _, _) => emit_expression(inner, ctx),
+            _ => format!("({})", emit_expression(inner, ctx)),
+        },
+        Expression::MethodCall(object, method, args) => {
+            // Unknown methods are passed through unchanged; the validator warns about them
+            let js_method = js_string_method(method).unwrap_or(method);
+            let args_code = args.iter().map(|arg| emit_expression(arg, ctx)).collect::<Vec<_>>().join(", ");
+            format!("{}.{}({})", emit_operand(object, u8::MAX, ctx), js_method, args_code)
+        },
+        Expression::Index(object, index) => format!("{}[{}]", emit_operand(object, u8::MAX, ctx), emit_expression(index, ctx)),
+        // JS has no negative indices, so name[-1] counts back from name.length
+        Expression::IndexFromEnd(object, n) => {
+            let object_code = emit_operand(object, u8::MAX, ctx);
+            if is_plain_path(object) {
+                let minus = if ctx.minify { "-" } else { " - " };
+                format!("{0}[{0}.length{1}{2}]", object_code, minus, n)
+            } else {
+                // Repeating any other object would evaluate it twice
+                format!("{}.at(-{})", object_code, n)
+            }
+        }
+        Expression::Lambda(params, body) => {
+            let body_code = body.iter().map(|s| emit_statement(s, ctx)).collect::<Vec<_>>().join("\n");
+            format!("({}) => {{\n{}\n}}", params.join(", "), body_code)
+        },
+        Expression::Call(callee, args) => {
+            // An arrow function must be parenthesized to be called where it is written
+            let callee_code = match **callee {
+                Expression::Lambda(_, _) => format!("({})", emit_expression(callee, ctx)),
+                _ => emit_operand(callee, u8::MAX, ctx),
+            };
+            let args_code = args.iter().map(|arg| emit_expression(arg, ctx)).collect::<Vec<_>>().join(", ");
+            format!("{}({})", callee_code, args_code)
+        },
+        // Parenthesized like binary operations, since it binds looser than all of them
+        Expression::Conditional(cond, then_branch, else_branch) => {
+            let (cond, then_branch, else_branch) = (emit_expression(cond, ctx), emit_expression(then_branch, ctx), emit_expression(else_branch, ctx));
+            if ctx.minify {
+                format!("({}?{}:{})", cond, then_branch, else_branch)
+            } else {
+                format!("({} ? {} : {})", cond, then_branch, else_branch)
+            }
+        },
+        Expression::Slice(object, start, end) => format!(
+            "{}.slice({}, {})",
+            emit_operand(object, u8::MAX, ctx),
+            emit_expression(start, ctx),
+            emit_expression(end, ctx)
+        ),
+        Expression::Folded(value, original) => {
+            // Outer parentheses add nothing in a comment, and "*/" would end it early
+            let original_code = emit_expression(original, ctx);
+            let original_code = match **original {
+                Expression::BinaryOp(_, _, _) if !ctx.minify => original_code.strip_prefix('(').and_then(|code| code.strip_suffix(')')).unwrap_or(&original_code),
+                _ => &original_code,
+            };
+            format!("{} /* {} */", emit_expression(value, ctx), original_code.replace("*/", "* /"))
+        },
+        Expression::Object(entries) if entries.is_empty() => "{}".to_string(),
+        Expression::Object(entries) => {
+            let entries_code = entries.iter()
+                .map(|(key, value)| format!("{}: {}", key, emit_expression(value, ctx)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{{ {} }}", entries_code)
         },
     }
 }
 
+/// Generate a loop body, counting the iteration first when loops are guarded
+fn loop_body(body: &[Spanned<Statement>], ctx: EmitContext) -> String {
+    let code = body.iter().map(|s| emit_statement(s, ctx)).collect::<Vec<_>>().join("\n");
+    let Some(max) = ctx.loop_guard else {
+        return code;
+    };
+    let message = ctx.quote_style.quote(&format!("loop ran more than {} iterations", max));
+    let check = terminate(format!("if (++{} > {}) throw new Error({})", LOOP_COUNTER, max, message), !ctx.omit_semicolons);
+    if code.is_empty() { check } else { format!("{}\n{}", check, code) }
+}
+
+/// Wrap a guarded loop in a block declaring its iteration counter
+///
+/// A nested loop's block shadows the counter, so each loop counts only its own iterations,
+/// starting from zero each time it is entered.
+fn guard_loop(loop_code: String, ctx: EmitContext) -> String {
+    if ctx.loop_guard.is_none() {
+        return loop_code;
+    }
+    let declaration = terminate(format!("let {} = 0", LOOP_COUNTER), !ctx.omit_semicolons);
+    format!("{{\n{}\n{}\n}}", declaration, loop_code)
+}
+
+/// Write already-stringified text without a trailing newline
+fn write_output(text: &str, ctx: EmitContext) -> String {
+    match ctx.output_mode {
+        OutputMode::ReturnString => format!("{} += {}", OUTPUT_BUFFER, text),
+        OutputMode::ConsoleLog => format!("process.stdout.write({})", text),
+    }
+}
+
 /// Generate complete JavaScript program from a vector of statements
-pub fn generate_program(statements: &[Statement]) -> String {
-    statements.iter().map(generate_statement).collect::<Vec<_>>().join("\n")
+pub fn generate_program(statements: &[Spanned<Statement>]) -> String {
+    generate_program_with_options(statements, &GeneratorOptions::default())
+}
+
+/// Generate complete JavaScript program with string/number coercions made explicit
+pub fn generate_program_typed(statements: &[Spanned<Statement>]) -> String {
+    generate_program_with_options(statements, &GeneratorOptions {
+        explicit_coercion: true,
+        ..GeneratorOptions::default()
+    })
+}
+
+/// Generate complete JavaScript program using the given generator options
+pub fn generate_program_with_options(statements: &[Spanned<Statement>], options: &GeneratorOptions) -> String {
+    let types = (options.explicit_coercion || options.stringify_objects).then(|| TypeEnv::from_program(statements));
+    let ctx = EmitContext {
+        types: types.as_ref(),
+        quote_style: options.quote_style,
+        explicit_coercion: options.explicit_coercion,
+        stringify_objects: options.stringify_objects,
+        omit_semicolons: options.omit_semicolons,
+        output_mode: options.output_mode,
+        minify: options.minify,
+        loop_guard: options.loop_guard,
+        mark_raw_js: options.mark_raw_js,
+    };
+    
+    let mut helpers = collect_required_helpers(statements).into_iter().collect::<Vec<_>>();
+    helpers.sort();
+    let code = helpers.iter()
+        .map(|helper| helper.definition().to_string())
+        .chain(statements.iter().map(|stmt| emit_statement(stmt, ctx)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    
+    match options.output_mode {
+        OutputMode::ReturnString => {
+            let terminator = if options.omit_semicolons { "" } else { ";" };
+            format!(
+                "function run() {{\nlet {buffer} = {}{terminator}\n{code}\nreturn {buffer}{terminator}\n}}",
+                options.quote_style.quote(""),
+                buffer = OUTPUT_BUFFER,
+            )
+        }
+        OutputMode::ConsoleLog => code,
+    }
+}
+
+/// Runtime function that generated code calls, defined once in the program's prelude
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Helper {
+    /// Floored division, for `~/`
+    FloorDiv,
+}
+
+impl Helper {
+    /// Get the name generated code calls the helper by
+    ///
+    /// TFI identifiers can't contain underscores, so the name never clashes with a variable.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Helper::FloorDiv => "__tfi_floor_div",
+        }
+    }
+    
+    /// Get the JavaScript definition of the helper, on a single line
+    pub fn definition(&self) -> &'static str {
+        match self {
+            Helper::FloorDiv => "function __tfi_floor_div(a, b) { return Math.floor(a / b); }",
+        }
+    }
+}
+
+/// Find the helpers a program calls, so the prelude defines only those
+pub fn collect_required_helpers(statements: &[Spanned<Statement>]) -> HashSet<Helper> {
+    let mut helpers = HashSet::new();
+    for stmt in statements {
+        statement_helpers(stmt, &mut helpers);
+    }
+    helpers
+}
+
+/// Record the helpers called by a statement and its blocks
+fn statement_helpers(stmt: &Statement, helpers: &mut HashSet<Helper>) {
+    let block = |statements: &[Spanned<Statement>], helpers: &mut HashSet<Helper>| {
+        for stmt in statements {
+            statement_helpers(stmt, helpers);
+        }
+    };
+    match stmt {
+        Statement::Print(expressions) | Statement::PrintInline(expressions) => {
+            for expr in expressions {
+                expression_helpers(expr, helpers);
+            }
+        }
+        Statement::PrintJoined(expressions, sep, end) => {
+            for expr in expressions.iter().chain([sep, end]) {
+                expression_helpers(expr, helpers);
+            }
+        }
+        Statement::MultiConst(declarators) => {
+            for (_, expr) in declarators {
+                expression_helpers(expr, helpers);
+            }
+        }
+        Statement::Const(_, expr) | Statement::Let(_, expr) | Statement::Assign(_, expr) | Statement::Expr(expr)
+            | Statement::Assert(expr, _) => {
+            expression_helpers(expr, helpers);
+        }
+        Statement::If(cond, then_block, else_block) => {
+            expression_helpers(cond, helpers);
+            block(then_block, helpers);
+            if let Some(else_block) = else_block {
+                block(else_block, helpers);
+            }
+        }
+        Statement::While(cond, body) => {
+            expression_helpers(cond, helpers);
+            block(body, helpers);
+        }
+        Statement::For(init, cond, update, body) => {
+            for clause in init.iter().chain(update) {
+                statement_helpers(clause, helpers);
+            }
+            if let Some(cond) = cond {
+                expression_helpers(cond, helpers);
+            }
+            block(body, helpers);
+        }
+        Statement::ForRange(_, start, end, body) => {
+            expression_helpers(start, helpers);
+            expression_helpers(end, helpers);
+            block(body, helpers);
+        }
+        Statement::Labeled(_, body) => statement_helpers(body, helpers),
+        Statement::Return(value) => {
+            if let Some(value) = value {
+                expression_helpers(value, helpers);
+            }
+        }
+        Statement::RawPrint(_) | Statement::RawJs(_) | Statement::Clear | Statement::Increment(_, _) | Statement::Break(_)
+            | Statement::Comment(_) | Statement::Import(_) => {}
+    }
+}
+
+/// Record the helpers called by an expression and its operands
+fn expression_helpers(expr: &Expression, helpers: &mut HashSet<Helper>) {
+    match expr {
+        Expression::BinaryOp(left, op, right) => {
+            if op == "~/" {
+                helpers.insert(Helper::FloorDiv);
+            }
+            expression_helpers(left, helpers);
+            expression_helpers(right, helpers);
+        }
+        Expression::Member(object, _) | Expression::OptionalMember(object, _) | Expression::Grouping(object) => {
+            expression_helpers(object, helpers);
+        }
+        Expression::MethodCall(object, _, args) => {
+            expression_helpers(object, helpers);
+            for arg in args {
+                expression_helpers(arg, helpers);
+            }
+        }
+        Expression::Object(entries) => {
+            for (_, value) in entries {
+                expression_helpers(value, helpers);
+            }
+        }
+        Expression::Index(object, index) => {
+            expression_helpers(object, helpers);
+            expression_helpers(index, helpers);
+        }
+        Expression::IndexFromEnd(object, _) => expression_helpers(object, helpers),
+        Expression::Slice(object, start, end) | Expression::Conditional(object, start, end) => {
+            expression_helpers(object, helpers);
+            expression_helpers(start, helpers);
+            expression_helpers(end, helpers);
+        }
+        // The original only appears in a comment
+        Expression::Folded(value, _) => expression_helpers(value, helpers),
+        Expression::Lambda(_, body) => {
+            for stmt in body {
+                statement_helpers(stmt, helpers);
+            }
+        }
+        Expression::Call(callee, args) => {
+            expression_helpers(callee, helpers);
+            for arg in args {
+                expression_helpers(arg, helpers);
+            }
+        }
+        Expression::Number(_) | Expression::Float(_) | Expression::Identifier(_) | Expression::String(_) => {}
+    }
+}
+
+/// Indentation style for formatted output
+#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
+pub enum IndentStyle {
+    /// Indent with spaces, using the configured width
+    #[default]
+    Spaces,
+    /// Indent with one tab per level
+    Tabs,
+}
+
+impl IndentStyle {
+    /// Get the text for one level of indentation
+    pub fn unit(&self, width: usize) -> String {
+        match self {
+            IndentStyle::Spaces => " ".repeat(width),
+            IndentStyle::Tabs => "\t".to_string(),
+        }
+    }
 }
 
 /// Generate formatted JavaScript code with proper indentation
 pub fn generate_formatted_statement(stmt: &Statement, indent_level: usize) -> String {
-    let indent = "    ".repeat(indent_level);
+    generate_formatted_statement_with_indent(stmt, indent_level, &IndentStyle::Spaces.unit(4))
+}
+
+/// Generate formatted JavaScript code, using `indent_unit` for each indentation level
+pub fn generate_formatted_statement_with_indent(stmt: &Statement, indent_level: usize, indent_unit: &str) -> String {
+    let indent = indent_unit.repeat(indent_level);
     let code = generate_statement(stmt);
     
     // Add indentation to each line
@@ -60,7 +703,7 @@ pub fn generate_formatted_statement(stmt: &Statement, indent_level: usize) -> St
 }
 
 /// Generate formatted JavaScript program
-pub fn generate_formatted_program(statements: &[Statement]) -> String {
+pub fn generate_formatted_program(statements: &[Spanned<Statement>]) -> String {
     statements.iter()
         .map(|stmt| generate_formatted_statement(stmt, 0))
         .collect::<Vec<_>>()
@@ -90,6 +733,37 @@ mod tests {
         assert_eq!(generate_expression(&expr), "\"hello\"");
     }
 
+    #[test]
+    fn test_generate_single_quoted_strings() {
+        let options = GeneratorOptions {
+            quote_style: QuoteStyle::Single,
+            ..GeneratorOptions::default()
+        };
+        let program = vec![
+            Statement::Print(vec![Expression::String("hello".to_string())]).into(),
+            Statement::Print(vec![Expression::String("it's".to_string())]).into(),
+        ];
+        
+        assert_eq!(
+            generate_program_with_options(&program, &options),
+            "console.log('hello');\nconsole.log('it\\'s');"
+        );
+    }
+
+    #[test]
+    fn test_quote_style_escaping() {
+        assert_eq!(QuoteStyle::Double.quote("it's"), "\"it's\"");
+        assert_eq!(QuoteStyle::Double.quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(QuoteStyle::Single.quote("a\\b"), "'a\\\\b'");
+    }
+
+    #[test]
+    fn test_quote_escapes_line_breaks_and_controls() {
+        assert_eq!(QuoteStyle::Double.quote("a\nb\tc"), "\"a\\nb\\tc\"");
+        assert_eq!(QuoteStyle::Double.quote("\u{0}\u{2028}"), "\"\\u0000\\u2028\"");
+        assert_eq!(QuoteStyle::Double.quote("café"), "\"café\"");
+    }
+
     #[test]
     fn test_generate_binary_expression() {
         let expr = Expression::BinaryOp(
@@ -100,6 +774,95 @@ mod tests {
         assert_eq!(generate_expression(&expr), "(5 + 3)");
     }
 
+    #[test]
+    fn test_generate_integer_division_expression() {
+        let expr = Expression::BinaryOp(
+            Box::new(Expression::Number(5)),
+            "~/".to_string(),
+            Box::new(Expression::Number(2))
+        );
+        assert_eq!(generate_expression(&expr), "__tfi_floor_div(5, 2)");
+    }
+
+    #[test]
+    fn test_prelude_defines_only_required_helpers() {
+        let halve = |n| Statement::Print(vec![Expression::BinaryOp(
+            Box::new(Expression::Number(n)),
+            "~/".to_string(),
+            Box::new(Expression::Number(2))
+        )]).into();
+        let program: Vec<Spanned<Statement>> = vec![halve(5), halve(7)];
+        
+        assert_eq!(collect_required_helpers(&program), HashSet::from([Helper::FloorDiv]));
+        assert_eq!(
+            generate_program(&program),
+            "function __tfi_floor_div(a, b) { return Math.floor(a / b); }\n\
+             console.log(__tfi_floor_div(5, 2));\n\
+             console.log(__tfi_floor_div(7, 2));"
+        );
+        
+        let plain: Vec<Spanned<Statement>> = vec![Statement::Print(vec![Expression::Number(1)]).into()];
+        assert!(collect_required_helpers(&plain).is_empty());
+        assert_eq!(generate_program(&plain), "console.log(1);");
+    }
+
+    #[test]
+    fn test_generate_typed_concatenation() {
+        let types = TypeEnv::new();
+        let concat = Expression::BinaryOp(
+            Box::new(Expression::String("n=".to_string())),
+            "+".to_string(),
+            Box::new(Expression::Number(5))
+        );
+        assert_eq!(generate_expression_typed(&concat, &types), "(\"n=\" + String(5))");
+        
+        let sum = Expression::BinaryOp(
+            Box::new(Expression::Number(2)),
+            "+".to_string(),
+            Box::new(Expression::Number(3))
+        );
+        assert_eq!(generate_expression_typed(&sum, &types), "(2 + 3)");
+    }
+
+    #[test]
+    fn test_generate_program_typed_uses_declarations() {
+        let statements = vec![
+            Statement::Const("count".to_string(), Expression::Number(3)).into(),
+            Statement::Print(vec![Expression::BinaryOp(
+                Box::new(Expression::String("count: ".to_string())),
+                "+".to_string(),
+                Box::new(Expression::Identifier("count".to_string()))
+            )]).into(),
+        ];
+        
+        let expected = "const count = 3;\nconsole.log((\"count: \" + String(count)));";
+        assert_eq!(generate_program_typed(&statements), expected);
+    }
+
+    #[test]
+    fn test_raw_print_template_literal() {
+        let stmt = Statement::RawPrint("a `b` \\ ${c} $d\nline two".to_string());
+        assert_eq!(generate_statement(&stmt), "console.log(`a \\`b\\` \\\\ \\${c} $d\\nline two`);");
+    }
+
+    #[test]
+    fn test_stringify_objects() {
+        let statements = vec![
+            Statement::Const("config".to_string(), Expression::Object(vec![("port".to_string(), Expression::Number(8080))])).into(),
+            Statement::Print(vec![
+                Expression::Identifier("config".to_string()),
+                Expression::Member(Box::new(Expression::Identifier("config".to_string())), "port".to_string()),
+            ]).into(),
+            Statement::PrintInline(vec![Expression::Object(vec![])]).into(),
+        ];
+        
+        let options = GeneratorOptions { stringify_objects: true, ..GeneratorOptions::default() };
+        let expected = "const config = { port: 8080 };\nconsole.log(JSON.stringify(config), config.port);\nprocess.stdout.write(JSON.stringify({}));";
+        assert_eq!(generate_program_with_options(&statements, &options), expected);
+        
+        assert!(!generate_program(&statements).contains("JSON.stringify"));
+    }
+
     #[test]
     fn test_generate_complex_binary_expression() {
         let expr = Expression::BinaryOp(
@@ -114,6 +877,60 @@ mod tests {
         assert_eq!(generate_expression(&expr), "((1 + 2) * 3)");
     }
 
+    #[test]
+    fn test_generate_member_expression() {
+        let expr = Expression::Member(
+            Box::new(Expression::Identifier("config".to_string())),
+            "name".to_string()
+        );
+        assert_eq!(generate_expression(&expr), "config.name");
+    }
+
+    #[test]
+    fn test_generate_object_expression() {
+        let expr = Expression::Object(vec![
+            ("name".to_string(), Expression::String("x".to_string())),
+            ("count".to_string(), Expression::Number(3)),
+        ]);
+        assert_eq!(generate_expression(&expr), "{ name: \"x\", count: 3 }");
+        assert_eq!(generate_expression(&Expression::Object(vec![])), "{}");
+    }
+
+    #[test]
+    fn test_generate_grouping_expression() {
+        let grouped_sum = Expression::Grouping(Box::new(Expression::BinaryOp(
+            Box::new(Expression::Number(1)),
+            "+".to_string(),
+            Box::new(Expression::Number(2))
+        )));
+        let expr = Expression::BinaryOp(
+            Box::new(grouped_sum),
+            "*".to_string(),
+            Box::new(Expression::Number(3))
+        );
+        assert_eq!(generate_expression(&expr), "((1 + 2) * 3)");
+        
+        let grouped_ident = Expression::Grouping(Box::new(Expression::Identifier("x".to_string())));
+        assert_eq!(generate_expression(&grouped_ident), "(x)");
+    }
+
+    #[test]
+    fn test_generate_method_call_expression() {
+        let upper = Expression::MethodCall(
+            Box::new(Expression::Identifier("name".to_string())),
+            "upper".to_string(),
+            vec![]
+        );
+        assert_eq!(generate_expression(&upper), "name.toUpperCase()");
+        
+        let slice = Expression::MethodCall(
+            Box::new(Expression::Identifier("text".to_string())),
+            "slice".to_string(),
+            vec![Expression::Number(1), Expression::Number(3)]
+        );
+        assert_eq!(generate_expression(&slice), "text.slice(1, 3)");
+    }
+
     #[test]
     fn test_generate_print_statement() {
         let stmt = Statement::Print(vec![
@@ -123,18 +940,72 @@ mod tests {
         assert_eq!(generate_statement(&stmt), "console.log(\"Hello\", 42);");
     }
 
+    #[test]
+    fn test_generate_print_inline_statement() {
+        let stmt = Statement::PrintInline(vec![
+            Expression::String("a".to_string()),
+            Expression::Number(1)
+        ]);
+        assert_eq!(generate_statement(&stmt), "process.stdout.write(String(\"a\") + String(1));");
+    }
+
+    #[test]
+    fn test_generate_clear_statement() {
+        assert_eq!(generate_statement(&Statement::Clear), "console.clear();");
+    }
+
     #[test]
     fn test_generate_const_statement() {
         let stmt = Statement::Const("x".to_string(), Expression::Number(10));
         assert_eq!(generate_statement(&stmt), "const x = 10;");
     }
 
+    #[test]
+    fn test_generate_multi_const_statement() {
+        let stmt = Statement::MultiConst(vec![
+            ("a".to_string(), Expression::Number(1)),
+            ("b".to_string(), Expression::Number(2)),
+        ]);
+        assert_eq!(generate_statement(&stmt), "const a = 1, b = 2;");
+    }
+
     #[test]
     fn test_generate_let_statement() {
         let stmt = Statement::Let("y".to_string(), Expression::String("hello".to_string()));
         assert_eq!(generate_statement(&stmt), "let y = \"hello\";");
     }
 
+    #[test]
+    fn test_generate_assign_statement() {
+        let stmt = Statement::Assign("y".to_string(), Expression::Number(5));
+        assert_eq!(generate_statement(&stmt), "y = 5;");
+    }
+
+    #[test]
+    fn test_generate_comment_statement() {
+        let stmt = Statement::Comment("note".to_string());
+        assert_eq!(generate_statement(&stmt), "// note");
+    }
+
+    #[test]
+    fn test_statement_termination() {
+        let declaration = generate_statement(&Statement::Let("x".to_string(), Expression::Number(1)));
+        assert_eq!(declaration, "let x = 1;");
+        assert!(!declaration.ends_with(";;"));
+        
+        let if_stmt = generate_statement(&Statement::If(
+            Expression::Identifier("x".to_string()),
+            vec![Statement::Clear.into()],
+            Some(vec![Statement::Increment("x".to_string(), false).into()])
+        ));
+        assert!(if_stmt.ends_with('}'));
+        assert!(if_stmt.contains("console.clear();\n}"));
+        assert!(if_stmt.contains("x++;\n}"));
+        
+        let comment = generate_statement(&Statement::Comment("note".to_string()));
+        assert_eq!(comment, "// note");
+    }
+
     #[test]
     fn test_generate_if_statement() {
         let stmt = Statement::If(
@@ -144,7 +1015,7 @@ mod tests {
                 Box::new(Expression::Number(0))
             ),
             vec![
-                Statement::Print(vec![Expression::String("positive".to_string())])
+                Statement::Print(vec![Expression::String("positive".to_string())]).into()
             ],
             None
         );
@@ -164,10 +1035,10 @@ console.log("positive");
                 Box::new(Expression::Number(0))
             ),
             vec![
-                Statement::Print(vec![Expression::String("positive".to_string())])
+                Statement::Print(vec![Expression::String("positive".to_string())]).into()
             ],
             Some(vec![
-                Statement::Print(vec![Expression::String("negative".to_string())])
+                Statement::Print(vec![Expression::String("negative".to_string())]).into()
             ])
         );
         
@@ -188,12 +1059,12 @@ console.log("negative");
                 Box::new(Expression::Number(10))
             ),
             vec![
-                Statement::Print(vec![Expression::Identifier("i".to_string())]),
+                Statement::Print(vec![Expression::Identifier("i".to_string())]).into(),
                 Statement::Let("i".to_string(), Expression::BinaryOp(
                     Box::new(Expression::Identifier("i".to_string())),
                     "+".to_string(),
                     Box::new(Expression::Number(1))
-                ))
+                )).into()
             ]
         );
         
@@ -204,22 +1075,40 @@ let i = (i + 1);
         assert_eq!(generate_statement(&stmt), expected);
     }
 
+    #[test]
+    fn test_omit_semicolons_guards_asi_hazards() {
+        let options = GeneratorOptions { omit_semicolons: true, ..GeneratorOptions::default() };
+        let sum = Expression::BinaryOp(
+            Box::new(Expression::Identifier("x".to_string())),
+            "+".to_string(),
+            Box::new(Expression::Number(1))
+        );
+        let program: Vec<Spanned<Statement>> = vec![
+            Statement::Const("x".to_string(), Expression::Number(1)).into(),
+            Statement::Expr(sum).into(),
+            Statement::Print(vec![Expression::Identifier("x".to_string())]).into(),
+        ];
+        
+        let js_code = generate_program_with_options(&program, &options);
+        assert_eq!(js_code, "const x = 1\n;(x + 1)\nconsole.log(x)");
+    }
+
     #[test]
     fn test_generate_for_statement() {
         let stmt = Statement::For(
-            Box::new(Statement::Let("i".to_string(), Expression::Number(0))),
-            Expression::BinaryOp(
+            Some(Box::new(Statement::Let("i".to_string(), Expression::Number(0)))),
+            Some(Expression::BinaryOp(
                 Box::new(Expression::Identifier("i".to_string())),
                 "<".to_string(),
                 Box::new(Expression::Number(5))
-            ),
-            Expression::BinaryOp(
+            )),
+            Some(Box::new(Statement::Expr(Expression::BinaryOp(
                 Box::new(Expression::Identifier("i".to_string())),
                 "+".to_string(),
                 Box::new(Expression::Number(1))
-            ),
+            )))),
             vec![
-                Statement::Print(vec![Expression::Identifier("i".to_string())])
+                Statement::Print(vec![Expression::Identifier("i".to_string())]).into()
             ]
         );
         
@@ -229,16 +1118,162 @@ console.log(i);
         assert_eq!(generate_statement(&stmt), expected);
     }
 
+    #[test]
+    fn test_generate_descending_for_statement() {
+        let stmt = Statement::For(
+            Some(Box::new(Statement::Let("i".to_string(), Expression::Number(3)))),
+            Some(Expression::BinaryOp(
+                Box::new(Expression::Identifier("i".to_string())),
+                ">".to_string(),
+                Box::new(Expression::Number(0))
+            )),
+            Some(Box::new(Statement::Increment("i".to_string(), true))),
+            vec![
+                Statement::Print(vec![Expression::Identifier("i".to_string())]).into()
+            ]
+        );
+        
+        assert_eq!(generate_statement(&stmt), "for (let i = 3; (i > 0); i--) {\nconsole.log(i);\n}");
+    }
+
+    #[test]
+    fn test_generate_for_statement_with_assignment_init() {
+        let stmt = Statement::For(
+            Some(Box::new(Statement::Assign("i".to_string(), Expression::Number(0)))),
+            Some(Expression::BinaryOp(
+                Box::new(Expression::Identifier("i".to_string())),
+                "<".to_string(),
+                Box::new(Expression::Number(3))
+            )),
+            Some(Box::new(Statement::Increment("i".to_string(), false))),
+            vec![
+                Statement::Print(vec![Expression::Identifier("i".to_string())]).into()
+            ]
+        );
+        
+        assert_eq!(generate_statement(&stmt), "for (i = 0; (i < 3); i++) {\nconsole.log(i);\n}");
+    }
+
+    #[test]
+    fn test_generate_for_statement_with_empty_clauses() {
+        let body = vec![Statement::Break(None).into()];
+        let forever = Statement::For(None, None, None, body.clone());
+        assert_eq!(generate_statement(&forever), "for (;;) {\nbreak;\n}");
+        
+        let counted = Statement::For(
+            None,
+            Some(Expression::Identifier("running".to_string())),
+            Some(Box::new(Statement::Increment("i".to_string(), false))),
+            body
+        );
+        assert_eq!(generate_statement(&counted), "for (; running; i++) {\nbreak;\n}");
+    }
+
+    #[test]
+    fn test_generate_return_string_program() {
+        let program = vec![
+            Statement::Print(vec![Expression::String("a".to_string()), Expression::Number(1)]).into(),
+            Statement::PrintInline(vec![Expression::Number(2)]).into(),
+            Statement::Clear.into(),
+        ];
+        let options = GeneratorOptions { output_mode: OutputMode::ReturnString, ..GeneratorOptions::default() };
+        
+        let expected = "function run() {\nlet __tfi_output = \"\";\n__tfi_output += [\"a\", 1].join(\" \") + \"\\n\";\n__tfi_output += String(2);\n__tfi_output = \"\";\nreturn __tfi_output;\n}";
+        assert_eq!(generate_program_with_options(&program, &options), expected);
+    }
+
+    #[test]
+    fn test_generate_minified_parentheses() {
+        let num = |n| Box::new(Expression::Number(n));
+        let op = |left, op: &str, right| Box::new(Expression::BinaryOp(left, op.to_string(), right));
+        let grouped = |inner| Box::new(Expression::Grouping(inner));
+        let options = GeneratorOptions { minify: true, ..GeneratorOptions::default() };
+        let minified = |expr: Box<Expression>| {
+            generate_program_with_options(&[Statement::Expr(*expr).into()], &options)
+        };
+        
+        assert_eq!(minified(op(grouped(op(num(1), "+", num(2))), "*", num(3))), "(1+2)*3;");
+        assert_eq!(minified(grouped(grouped(num(42)))), "42;");
+        // Parentheses the tree needs are kept, including a right operand of the same strength
+        assert_eq!(minified(op(num(10), "-", grouped(op(num(4), "-", num(3))))), "10-(4-3);");
+        assert_eq!(minified(op(grouped(op(num(10), "-", num(4))), "-", num(3))), "10-4-3;");
+        assert_eq!(minified(op(num(1), "-", num(-1))), "1 - -1;");
+    }
+
+    #[test]
+    fn test_generate_range_loop() {
+        let stmt = Statement::ForRange(
+            "i".to_string(),
+            Expression::Number(0),
+            Expression::Number(5),
+            vec![
+                Statement::Print(vec![Expression::Identifier("i".to_string())]).into()
+            ]
+        );
+        
+        assert_eq!(generate_statement(&stmt), "for (let i = 0; i < 5; i++) {\nconsole.log(i);\n}");
+    }
+
+    #[test]
+    fn test_generate_negative_index() {
+        let last = |object| Expression::IndexFromEnd(Box::new(object), 1);
+        let name = Expression::Identifier("name".to_string());
+        
+        assert_eq!(generate_expression(&last(name.clone())), "name[name.length - 1]");
+        // A computed negative index is left for JS to read as a property
+        let computed = Expression::Index(Box::new(name.clone()), Box::new(Expression::Number(-1)));
+        assert_eq!(generate_expression(&computed), "name[-1]");
+        // Calls aren't repeated, so at() counts back instead
+        let upper = Expression::MethodCall(Box::new(name.clone()), "upper".to_string(), vec![]);
+        assert_eq!(generate_expression(&last(upper)), "name.toUpperCase().at(-1)");
+        let field = Expression::Member(Box::new(name), "s".to_string());
+        assert_eq!(generate_expression(&last(field)), "name.s[name.s.length - 1]");
+        // A call under the member access must still run once
+        let call = Expression::Call(Box::new(Expression::Identifier("g".to_string())), vec![]);
+        let field_of_call = Expression::Member(Box::new(call), "s".to_string());
+        assert_eq!(generate_expression(&last(field_of_call)), "g().s.at(-1)");
+    }
+
+    #[test]
+    fn test_generate_loop_guard() {
+        let stmt = Statement::While(
+            Expression::Identifier("running".to_string()),
+            vec![Statement::Print(vec![Expression::Number(1)]).into()]
+        );
+        let options = GeneratorOptions { loop_guard: Some(100), ..GeneratorOptions::default() };
+        
+        assert_eq!(
+            generate_program_with_options(&[stmt.clone().into()], &options),
+            "{\nlet __tfi_iterations = 0;\nwhile (running) {\n\
+             if (++__tfi_iterations > 100) throw new Error(\"loop ran more than 100 iterations\");\n\
+             console.log(1);\n}\n}"
+        );
+        assert_eq!(generate_statement(&stmt), "while (running) {\nconsole.log(1);\n}");
+    }
+
+    #[test]
+    fn test_generate_labeled_loop_and_break() {
+        let stmt = Statement::Labeled("outer".to_string(), Box::new(Statement::While(
+            Expression::Number(1),
+            vec![
+                Statement::Break(Some("outer".to_string())).into(),
+                Statement::Break(None).into(),
+            ]
+        )));
+        
+        assert_eq!(generate_statement(&stmt), "outer: while (1) {\nbreak outer;\nbreak;\n}");
+    }
+
     #[test]
     fn test_generate_program() {
         let statements = vec![
-            Statement::Const("x".to_string(), Expression::Number(10)),
-            Statement::Let("y".to_string(), Expression::Number(5)),
+            Statement::Const("x".to_string(), Expression::Number(10)).into(),
+            Statement::Let("y".to_string(), Expression::Number(5)).into(),
             Statement::Print(vec![Expression::String("sum".to_string()), Expression::BinaryOp(
                 Box::new(Expression::Identifier("x".to_string())),
                 "+".to_string(),
                 Box::new(Expression::Identifier("y".to_string()))
-            )])
+            )]).into()
         ];
         
         let expected = r#"const x = 10;
@@ -252,7 +1287,7 @@ console.log("sum", (x + y));"#;
         let stmt = Statement::If(
             Expression::Number(1),
             vec![
-                Statement::Print(vec![Expression::String("true".to_string())])
+                Statement::Print(vec![Expression::String("true".to_string())]).into()
             ],
             None
         );
@@ -262,4 +1297,15 @@ console.log("sum", (x + y));"#;
     }"#;
         assert_eq!(generate_formatted_statement(&stmt, 1), expected);
     }
+
+    #[test]
+    fn test_generate_formatted_statement_with_indent() {
+        let stmt = Statement::Print(vec![Expression::Number(1)]);
+        
+        let two_spaces = IndentStyle::Spaces.unit(2);
+        assert_eq!(generate_formatted_statement_with_indent(&stmt, 2, &two_spaces), "    console.log(1);");
+        
+        let tab = IndentStyle::Tabs.unit(2);
+        assert_eq!(generate_formatted_statement_with_indent(&stmt, 1, &tab), "\tconsole.log(1);");
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,212 @@
+use crate::ast::{Statement, Spanned};
+use crate::compiler::{parse_single_source, CompilationError, CompilationOptions};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Replace each top-level `import "file.tfi";` with the statements of that file, recursively
+///
+/// Paths are relative to the importing file. A file imported a second time is skipped, since its
+/// declarations are already in the program, and an import cycle is an error. Imports nested in
+/// blocks are left for the validator to report. Each inlined statement records the file it came
+/// from, since its line counts in that file.
+pub fn inline_imports(
+    statements: Vec<Spanned<Statement>>,
+    path: &Path,
+    options: &CompilationOptions
+) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+    ImportResolver::new(path, options).inline(statements, path)
+}
+
+/// Read the text of every file a program imports, directly or not, in the order they are inlined
+pub fn imported_sources(
+    statements: Vec<Spanned<Statement>>,
+    path: &Path,
+    options: &CompilationOptions
+) -> Result<Vec<String>, CompilationError> {
+    let mut resolver = ImportResolver::new(path, options);
+    resolver.inline(statements, path)?;
+    Ok(resolver.sources)
+}
+
+/// State for inlining the imports of one program
+struct ImportResolver<'a> {
+    options: &'a CompilationOptions,
+    /// Files currently being inlined, the compiled file first
+    stack: Vec<PathBuf>,
+    /// Every file inlined so far
+    visited: HashSet<PathBuf>,
+    /// Text of every file inlined so far, in the order they were read
+    sources: Vec<String>,
+}
+
+impl<'a> ImportResolver<'a> {
+    /// Start resolving the imports of the file at `path`
+    fn new(path: &Path, options: &'a CompilationOptions) -> Self {
+        let root = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        Self {
+            options,
+            stack: vec![root.clone()],
+            visited: HashSet::from([root]),
+            sources: vec![],
+        }
+    }
+    
+    /// Inline the imports of a file's statements
+    fn inline(&mut self, statements: Vec<Spanned<Statement>>, importer: &Path) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+        let mut program = Vec::with_capacity(statements.len());
+        
+        for stmt in statements {
+            let Statement::Import(target) = &stmt.node else {
+                program.push(stmt);
+                continue;
+            };
+            
+            let path = importer.parent().unwrap_or(Path::new("")).join(target);
+            let import_error = |message: String| CompilationError::General {
+                message,
+                context: Some(format!("imported from {}, line {}", importer.display(), stmt.line)),
+            };
+            
+            let canonical = fs::canonicalize(&path)
+                .map_err(|e| import_error(format!("Failed to import \"{}\": {}", target, e)))?;
+            if self.stack.contains(&canonical) {
+                let cycle = self.stack.iter()
+                    .skip_while(|file| **file != canonical)
+                    .chain(std::iter::once(&canonical))
+                    .map(|file| file.file_name().unwrap_or_default().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Err(import_error(format!("Import cycle: {}", cycle)));
+            }
+            if !self.visited.insert(canonical.clone()) {
+                continue;
+            }
+            
+            let source = fs::read_to_string(&path)
+                .map_err(|e| import_error(format!("Failed to import \"{}\": {}", target, e)))?;
+            let imported = parse_single_source(&source, self.options)?;
+            self.sources.push(source);
+            
+            self.stack.push(canonical);
+            let imported = self.inline(imported, &path)?;
+            self.stack.pop();
+            
+            // Statements the imported file inlined itself already name their own file
+            let file: Arc<str> = Arc::from(path.display().to_string());
+            program.extend(imported.into_iter().map(|mut stmt| {
+                stmt.file.get_or_insert_with(|| Arc::clone(&file));
+                stmt
+            }));
+        }
+        
+        Ok(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Expression;
+    use crate::compiler::{compile_cached, compile_with_options};
+    use crate::diagnostics::lint;
+    
+    /// Write files into a fresh temporary directory
+    fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tfi_lang_imports_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        for (file, source) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        dir
+    }
+    
+    fn inline_file(path: &Path) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+        let options = CompilationOptions::new().with_silent_parsing();
+        let source = fs::read_to_string(path).unwrap();
+        inline_imports(parse_single_source(&source, &options)?, path, &options)
+    }
+
+    #[test]
+    fn test_imports_are_inlined_once() {
+        let dir = project("diamond", &[
+            ("main.tfi", "import \"lib/a.tfi\";\nimport \"lib/b.tfi\";\nbahubali(a, b);"),
+            ("lib/a.tfi", "import \"base.tfi\";\nrrr a = base;"),
+            ("lib/b.tfi", "import \"base.tfi\";\nrrr b = base;"),
+            ("lib/base.tfi", "rrr base = 1;"),
+        ]);
+        
+        let program = inline_file(&dir.join("main.tfi")).unwrap();
+        let declared: Vec<&str> = program.iter().filter_map(|stmt| match &stmt.node {
+            Statement::Const(name, _) => Some(name.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(declared, vec!["base", "a", "b"]);
+        assert!(matches!(&program[3].node, Statement::Print(args) if args[0] == Expression::Identifier("a".to_string())));
+    }
+
+    #[test]
+    fn test_import_cycle_and_missing_file() {
+        let dir = project("cycle", &[
+            ("main.tfi", "import \"a.tfi\";"),
+            ("a.tfi", "import \"main.tfi\";"),
+            ("missing.tfi", "import \"nowhere.tfi\";"),
+        ]);
+        
+        let error = inline_file(&dir.join("main.tfi")).unwrap_err();
+        assert!(error.to_string().contains("Import cycle: main.tfi -> a.tfi -> main.tfi"), "got: {}", error);
+        
+        let error = inline_file(&dir.join("missing.tfi")).unwrap_err();
+        assert!(error.to_string().contains("Failed to import \"nowhere.tfi\""));
+    }
+
+    #[test]
+    fn test_errors_in_imported_files_name_the_file() {
+        let dir = project("error_lines", &[
+            ("lib.tfi", "rrr total = 1;\n\nrrr total = 2;"),
+            ("other.tfi", "rrr count = 1;"),
+            ("main.tfi", "bahubali(1);\nimport \"other.tfi\";"),
+        ]);
+        let options = CompilationOptions::new().with_silent_parsing().with_source_path(dir.join("main.tfi"));
+        
+        let program = inline_file(&dir.join("main.tfi")).unwrap();
+        assert!(program[0].file.is_none());
+        assert!(program[1].file.as_deref().is_some_and(|file| file.ends_with("other.tfi")));
+        
+        // Both declarations are in the imported file, on lines that mean nothing in main.tfi
+        let source = "bahubali(1);\nimport \"lib.tfi\";";
+        let error = compile_with_options(source, &options).unwrap_err().to_string();
+        assert!(error.contains("already declared at line 1 of "), "got: {}", error);
+        assert!(error.contains("lib.tfi, redeclared at line 3 of "), "got: {}", error);
+        
+        let report = lint(source, &options);
+        assert_eq!(report.errors[0].range.start.line, 0);
+        assert!(report.errors[0].message.starts_with("In "), "got: {}", report.errors[0].message);
+        assert!(report.errors[0].message.contains("lib.tfi, line 3: "), "got: {}", report.errors[0].message);
+        
+        // Redeclaring an imported name points at the line in main.tfi
+        let source = "import \"other.tfi\";\nbahubali(1);\nrrr count = 2;";
+        let error = compile_with_options(source, &options).unwrap_err().to_string();
+        assert!(error.contains("other.tfi, redeclared at line 3\n"), "got: {}", error);
+        assert_eq!(lint(source, &options).errors[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn test_compile_cached_sees_changed_import() {
+        let dir = project("cached", &[("lib.tfi", "rrr greeting = \"hi\";")]);
+        let mut cache = std::collections::HashMap::new();
+        let source = "import \"lib.tfi\";\nbahubali(greeting);";
+        let options = CompilationOptions::new().with_source_path(dir.join("main.tfi"));
+        
+        let first = compile_cached(source, &options, &mut cache).unwrap();
+        assert!(first.js_code.contains("\"hi\""));
+        
+        fs::write(dir.join("lib.tfi"), "rrr greeting = \"bye\";").unwrap();
+        let second = compile_cached(source, &options, &mut cache).unwrap();
+        assert!(second.js_code.contains("\"bye\""), "stale result: {}", second.js_code);
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -1,4 +1,7 @@
 use logos::Logos;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::LazyLock;
 
 /// Token types for the TFI language lexer
 #[derive(Logos, Debug, PartialEq, Clone)]
@@ -7,10 +10,26 @@ pub enum Token {
     #[token("rrr")] Const,
     #[token("pushpa")] Let,
     #[token("bahubali")] Print,
+    #[token("salaar")] PrintInline,
     #[token("magadheera")] If,
     #[token("karthikeya")] Else,
     #[token("pokiri")] While,
     #[token("eega")] For,
+    #[token("wipe")] Clear,
+    #[token("aagipo")] Break,
+    #[token("nammaka")] Assert,
+    #[token("vaapas")] Return,
+    #[token("fn")] Lambda,
+    #[token("in")] In,
+    #[token("import")] Import,
+    
+    /// Raw print block, carrying the text between the braces
+    #[token("bahubali_block", lex_raw_block)]
+    RawPrint(String),
+    
+    /// Raw JavaScript block, carrying the code between the braces
+    #[token("rajini", lex_raw_js)]
+    RawJs(String),
     
     /// Identifiers (variable names)
     #[regex("[a-zA-Z]+", |lex| lex.slice().to_string())] 
@@ -20,17 +39,37 @@ pub enum Token {
     #[regex("[0-9]+", |lex| lex.slice().parse().ok())] 
     Number(i32),
     
+    /// Floating-point literals, with an optional exponent
+    #[regex(r"[0-9]+\.[0-9]+([eE][+-]?[0-9]+)?", |lex| lex.slice().parse().ok())]
+    #[regex(r"[0-9]+[eE][+-]?[0-9]+", |lex| lex.slice().parse().ok())]
+    Float(f64),
+    
+    /// String literals, carrying the text between the quotes with escapes as written
+    #[regex(r#""([^"\\\n]|\\.)*""#, |lex| lex.slice()[1..lex.slice().len() - 1].to_string())]
+    String(String),
+    
     /// Operators and punctuation
     #[token("=")] Assign,
     #[token("(")] LParen,
     #[token(")")] RParen,
     #[token("{")] LBrace,
     #[token("}")] RBrace,
+    #[token("[")] LBracket,
+    #[token("]")] RBracket,
     #[token(";")] Semicolon,
+    #[token(",")] Comma,
+    #[token(":")] Colon,
+    #[token(".")] Dot,
+    #[token("?.")] OptionalDot,
+    #[token("?")] Question,
+    #[token("..")] Range,
     #[token("+")] Plus,
     #[token("-")] Minus,
+    #[token("++")] Increment,
+    #[token("--")] Decrement,
     #[token("*")] Multiply,
     #[token("/")] Divide,
+    #[token("~/")] IntDivide,
     #[token(">")] Greater,
     #[token("<")] Less,
     #[token(">=")] GreaterEqual,
@@ -39,82 +78,337 @@ pub enum Token {
     #[token("!=")] NotEqual,
     
     /// Whitespace (skipped)
-    #[regex(r"[ \t\n\f]+", logos::skip)] 
+    #[regex(r"[ \t\r\n\f]+", logos::skip)] 
     Whitespace,
+    
+    /// Line comments (skipped)
+    #[regex(r"//[^\n]*", logos::skip)]
+    Comment,
 }
 
 impl Token {
     /// Check if the token is a keyword
     pub fn is_keyword(&self) -> bool {
-        matches!(self, 
-            Token::Const | 
-            Token::Let | 
-            Token::Print | 
-            Token::If | 
-            Token::Else | 
-            Token::While | 
-            Token::For
-        )
+        self.keyword_name().is_some()
     }
     
     /// Get the keyword name as a string
     pub fn keyword_name(&self) -> Option<&'static str> {
-        match self {
-            Token::Const => Some("rrr"),
-            Token::Let => Some("pushpa"),
-            Token::Print => Some("bahubali"),
-            Token::If => Some("magadheera"),
-            Token::Else => Some("karthikeya"),
-            Token::While => Some("pokiri"),
-            Token::For => Some("eega"),
-            _ => None,
-        }
+        KEYWORDS.iter().find(|(token, _, _)| token == self).map(|(_, name, _)| *name)
+    }
+    
+    /// Look up the keyword token for a canonical keyword name
+    pub fn from_keyword(name: &str) -> Option<Token> {
+        KEYWORDS.iter().find(|(_, keyword, _)| *keyword == name).map(|(token, _, _)| token.clone())
     }
     
     /// Check if the token is an operator
     pub fn is_operator(&self) -> bool {
-        matches!(self, 
-            Token::Plus | Token::Minus | Token::Multiply | Token::Divide |
-            Token::Greater | Token::Less | Token::GreaterEqual | Token::LessEqual |
-            Token::Equal | Token::NotEqual | Token::Assign
-        )
+        self.operator_symbol().is_some()
     }
     
     /// Get the operator symbol as a string
     pub fn operator_symbol(&self) -> Option<&'static str> {
-        match self {
-            Token::Plus => Some("+"),
-            Token::Minus => Some("-"),
-            Token::Multiply => Some("*"),
-            Token::Divide => Some("/"),
-            Token::Greater => Some(">"),
-            Token::Less => Some("<"),
-            Token::GreaterEqual => Some(">="),
-            Token::LessEqual => Some("<="),
-            Token::Equal => Some("=="),
-            Token::NotEqual => Some("!="),
-            Token::Assign => Some("="),
-            _ => None,
+        OPERATORS.iter().find(|(token, _, _)| token == self).map(|(_, symbol, _)| *symbol)
+    }
+}
+
+/// Every keyword token with the name it is lexed from and a label saying what it does
+///
+/// Like `OPERATORS`, this is the one list the keyword lookups and `keyword_list` read; a test
+/// checks it agrees with the `#[token]` attributes above.
+pub const KEYWORDS: &[(Token, &str, &str)] = &[
+    (Token::Const, "rrr", "constant"),
+    (Token::Let, "pushpa", "variable"),
+    (Token::Print, "bahubali", "print"),
+    (Token::PrintInline, "salaar", "print inline"),
+    (Token::If, "magadheera", "if"),
+    (Token::Else, "karthikeya", "else"),
+    (Token::While, "pokiri", "while"),
+    (Token::For, "eega", "for"),
+    (Token::Clear, "wipe", "clear"),
+    (Token::Break, "aagipo", "break"),
+    (Token::Assert, "nammaka", "assert"),
+    (Token::Return, "vaapas", "return"),
+    (Token::Lambda, "fn", "function"),
+    (Token::In, "in", "in"),
+    (Token::Import, "import", "import"),
+];
+
+/// Every operator token with the symbol it is lexed from and a label saying what it does
+///
+/// `is_operator` and `operator_symbol` both read this table, so a new operator only needs adding
+/// here and to the `#[token]` attributes above; a test checks the two agree.
+pub const OPERATORS: &[(Token, &str, &str)] = &[
+    (Token::Plus, "+", "add"),
+    (Token::Minus, "-", "subtract"),
+    (Token::Increment, "++", "increment"),
+    (Token::Decrement, "--", "decrement"),
+    (Token::Multiply, "*", "multiply"),
+    (Token::Divide, "/", "divide"),
+    (Token::IntDivide, "~/", "integer divide"),
+    (Token::Greater, ">", "greater than"),
+    (Token::Less, "<", "less than"),
+    (Token::GreaterEqual, ">=", "greater than or equal"),
+    (Token::LessEqual, "<=", "less than or equal"),
+    (Token::Equal, "==", "equal"),
+    (Token::NotEqual, "!=", "not equal"),
+    (Token::Assign, "=", "assign"),
+];
+
+/// Each keyword with a label saying what it does, such as `("bahubali", "print")`, for editor tooling
+///
+/// Includes the raw block keywords, which are lexed by their own callbacks rather than as plain keywords.
+pub fn keyword_list() -> &'static [(&'static str, &'static str)] {
+    static LIST: LazyLock<Vec<(&str, &str)>> = LazyLock::new(|| {
+        KEYWORDS.iter()
+            .map(|(_, name, label)| (*name, *label))
+            .chain([(RAW_PRINT_KEYWORD, "raw print block"), (RAW_JS_KEYWORD, "raw JavaScript block")])
+            .collect()
+    });
+    &LIST
+}
+
+/// Each operator symbol with a label saying what it does, such as `("+", "add")`, for editor tooling
+pub fn operator_list() -> &'static [(&'static str, &'static str)] {
+    static LIST: LazyLock<Vec<(&str, &str)>> = LazyLock::new(|| {
+        OPERATORS.iter().map(|(_, symbol, label)| (*symbol, *label)).collect()
+    });
+    &LIST
+}
+
+/// Keyword that starts a raw print block
+pub const RAW_PRINT_KEYWORD: &str = "bahubali_block";
+
+/// Length in bytes of the raw print block at the start of `source`, if one starts there
+///
+/// A block runs from `bahubali_block {` at the end of a line to the next line holding only `}`.
+/// The text between is not tokenized, so it may contain quotes, braces and `//`.
+pub fn raw_block_len(source: &str) -> Option<usize> {
+    let rest = source.strip_prefix(RAW_PRINT_KEYWORD)?;
+    let body = rest.trim_start_matches([' ', '\t']).strip_prefix('{')?;
+    let body_start = source.len() - body.len();
+    
+    let mut lines = body.split_inclusive('\n');
+    let opening = lines.next()?;
+    if !opening.ends_with('\n') || !opening.trim().is_empty() {
+        return None;
+    }
+    
+    let mut offset = opening.len();
+    for line in lines {
+        if line.trim() == "}" {
+            return Some(body_start + offset + line.find('}')? + 1);
         }
+        offset += line.len();
+    }
+    None
+}
+
+/// Consume a raw print block after its keyword, producing the text it prints
+fn lex_raw_block(lex: &mut logos::Lexer<Token>) -> Option<String> {
+    let start = lex.span().start;
+    let len = raw_block_len(&lex.source()[start..])?;
+    lex.bump(len - RAW_PRINT_KEYWORD.len());
+    
+    let block = &lex.source()[start..start + len];
+    let text = block[..block.rfind('\n')?].split_once('\n').map_or("", |(_, text)| text);
+    Some(text.replace("\r\n", "\n"))
+}
+
+/// Keyword that starts a raw JavaScript block
+pub const RAW_JS_KEYWORD: &str = "rajini";
+
+/// Length in bytes of the raw JavaScript block at the start of `source`, if one starts there
+///
+/// The block ends at the brace matching the one after `rajini`. Braces inside string literals
+/// don't count.
+pub fn raw_js_len(source: &str) -> Option<usize> {
+    let rest = source.strip_prefix(RAW_JS_KEYWORD)?;
+    let body = rest.trim_start_matches([' ', '\t', '\r', '\n']).strip_prefix('{')?;
+    let body_start = source.len() - body.len();
+    
+    let mut depth = 1;
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(body_start + i + 1);
+                }
+            }
+            '"' | '\'' | '`' => {
+                // An unterminated quote is an ordinary character
+                if let Some(len) = js_string_len(&body[i..]) {
+                    chars.by_ref().take_while(|(j, _)| *j < i + len - 1).for_each(drop);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Length in bytes of the JavaScript string literal at the start of `source`, if it is closed
+///
+/// Only template literals may span lines.
+fn js_string_len(source: &str) -> Option<usize> {
+    let mut chars = source.char_indices();
+    let (_, quote) = chars.next()?;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' if quote != '`' => return None,
+            c if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Consume a raw JavaScript block after its keyword, producing the code between the braces
+fn lex_raw_js(lex: &mut logos::Lexer<Token>) -> Option<String> {
+    let start = lex.span().start;
+    let len = raw_js_len(&lex.source()[start..])?;
+    lex.bump(len - RAW_JS_KEYWORD.len());
+    
+    let block = &lex.source()[start..start + len - 1];
+    block.split_once('{').map(|(_, code)| code.to_string())
+}
+
+/// Alternative spellings for keywords, e.g. `print` for `bahubali`
+#[derive(Debug, Clone, Default, PartialEq, Hash)]
+pub struct AliasMap {
+    /// Alias mapped to the canonical keyword it stands for
+    aliases: BTreeMap<String, &'static str>,
+}
+
+impl AliasMap {
+    /// Create an empty alias map
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add an alias for a keyword; the canonical keyword keeps working
+    pub fn insert(&mut self, alias: &str, keyword: &str) -> Result<(), String> {
+        let canonical = Token::from_keyword(keyword)
+            .and_then(|token| token.keyword_name())
+            .ok_or_else(|| format!("'{}' is not a TFI keyword", keyword))?;
+        
+        if alias.is_empty() || !alias.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("Alias '{}' must consist of letters only", alias));
+        }
+        if Token::from_keyword(alias).is_some() {
+            return Err(format!("'{}' is already a TFI keyword", alias));
+        }
+        
+        self.aliases.insert(alias.to_string(), canonical);
+        Ok(())
+    }
+    
+    /// Get the canonical keyword an alias stands for
+    pub fn resolve(&self, word: &str) -> Option<&'static str> {
+        self.aliases.get(word).copied()
+    }
+    
+    /// Check if no aliases are defined
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+    
+    /// Rewrite aliases in the source to their canonical keywords, leaving strings and comments alone
+    pub fn apply(&self, source: &str) -> String {
+        let mut output = String::with_capacity(source.len());
+        let mut chars = source.char_indices().peekable();
+        let mut in_string = false;
+        
+        while let Some((start, c)) = chars.next() {
+            if in_string {
+                in_string = c != '"';
+                output.push(c);
+                // An escaped character, such as \", never ends the string
+                if c == '\\' && let Some((_, escaped)) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = true;
+                output.push(c);
+            } else if c == '/' && chars.peek().map(|(_, next)| *next) == Some('/') {
+                // Copy the comment through to the end of the line
+                output.push(c);
+                while let Some((_, c)) = chars.next_if(|(_, c)| *c != '\n') {
+                    output.push(c);
+                }
+            } else if let Some(len) = raw_block_len(&source[start..]).or_else(|| raw_js_len(&source[start..])) {
+                // Raw text and code are copied as written, so aliases are not applied inside them
+                output.push_str(&source[start..start + len]);
+                while chars.next_if(|(i, _)| *i < start + len).is_some() {}
+            } else if c.is_ascii_alphabetic() {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+                    end = i + c.len_utf8();
+                }
+                let word = &source[start..end];
+                output.push_str(self.resolve(word).unwrap_or(word));
+            } else {
+                output.push(c);
+            }
+        }
+        
+        output
     }
 }
 
 /// Lexer for the TFI language
 pub struct Lexer {
     tokens: Vec<Token>,
+    spans: Vec<Range<usize>>,
+    errors: Vec<Range<usize>>,
     position: usize,
 }
 
 impl Lexer {
     /// Create a new lexer from source code
+    ///
+    /// Text that isn't a token is left out of the stream; `error_spans` has its byte ranges.
     pub fn new(source: &str) -> Self {
-        let tokens: Vec<Token> = Token::lexer(source).filter_map(|token| token.ok()).collect();
+        let mut tokens = Vec::new();
+        let mut spans = Vec::new();
+        let mut errors = Vec::new();
+        for (token, span) in Token::lexer(source).spanned() {
+            match token {
+                Ok(token) => {
+                    tokens.push(token);
+                    spans.push(span);
+                }
+                Err(()) => errors.push(span),
+            }
+        }
         Self {
             tokens,
+            spans,
+            errors,
             position: 0,
         }
     }
     
+    /// Create a lexer that also recognizes the given keyword aliases
+    pub fn new_with_aliases(source: &str, aliases: &AliasMap) -> Self {
+        let mut lexer = Self::new(source);
+        for token in &mut lexer.tokens {
+            if let Token::Identifier(word) = token
+                && let Some(keyword) = aliases.resolve(word).and_then(Token::from_keyword)
+            {
+                *token = keyword;
+            }
+        }
+        lexer
+    }
+    
     /// Get the current token
     pub fn current(&self) -> Option<&Token> {
         self.tokens.get(self.position)
@@ -143,6 +437,21 @@ impl Lexer {
         &self.tokens
     }
     
+    /// Get the byte range of the current token in the source
+    pub fn current_span(&self) -> Option<&Range<usize>> {
+        self.spans.get(self.position)
+    }
+    
+    /// Get the byte ranges of all tokens, parallel to `all_tokens`
+    pub fn all_spans(&self) -> &[Range<usize>] {
+        &self.spans
+    }
+    
+    /// Get the byte ranges of source text that couldn't be read as a token
+    pub fn error_spans(&self) -> &[Range<usize>] {
+        &self.errors
+    }
+    
     /// Reset the lexer position
     pub fn reset(&mut self) {
         self.position = 0;
@@ -155,7 +464,7 @@ mod tests {
 
     #[test]
     fn test_keyword_tokens() {
-        let source = "rrr pushpa bahubali magadheera karthikeya pokiri eega";
+        let source = "rrr pushpa bahubali magadheera karthikeya pokiri eega wipe salaar aagipo";
         let mut lexer = Lexer::new(source);
         
         assert_eq!(lexer.current(), Some(&Token::Const));
@@ -171,6 +480,12 @@ mod tests {
         assert_eq!(lexer.current(), Some(&Token::While));
         lexer.advance();
         assert_eq!(lexer.current(), Some(&Token::For));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Clear));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::PrintInline));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Break));
     }
 
     #[test]
@@ -199,9 +514,21 @@ mod tests {
         assert_eq!(lexer.current(), Some(&Token::Number(999)));
     }
 
+    #[test]
+    fn test_float_tokens() {
+        let source = "1.5 2e3 1.5E-2";
+        let mut lexer = Lexer::new(source);
+        
+        assert_eq!(lexer.current(), Some(&Token::Float(1.5)));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Float(2000.0)));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Float(0.015)));
+    }
+
     #[test]
     fn test_operator_tokens() {
-        let source = "= ( ) { } ; + >";
+        let source = "= ( ) { } ; + > ++ --";
         let mut lexer = Lexer::new(source);
         
         assert_eq!(lexer.current(), Some(&Token::Assign));
@@ -219,6 +546,10 @@ mod tests {
         assert_eq!(lexer.current(), Some(&Token::Plus));
         lexer.advance();
         assert_eq!(lexer.current(), Some(&Token::Greater));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Increment));
+        lexer.advance();
+        assert_eq!(lexer.current(), Some(&Token::Decrement));
     }
 
     #[test]
@@ -233,6 +564,23 @@ mod tests {
         assert_eq!(lexer.current(), Some(&Token::Print));
     }
 
+    #[test]
+    fn test_string_and_comment_tokens() {
+        let lexer = Lexer::new("bahubali(\"hello world\", \"say \\\"hi\\\"\"); // say hi\nx");
+        
+        assert_eq!(lexer.all_tokens(), &[
+            Token::Print,
+            Token::LParen,
+            Token::String("hello world".to_string()),
+            Token::Comma,
+            Token::String("say \\\"hi\\\"".to_string()),
+            Token::RParen,
+            Token::Semicolon,
+            Token::Identifier("x".to_string()),
+        ]);
+        assert_eq!(lexer.all_spans()[2], 9..22);
+    }
+
     #[test]
     fn test_token_methods() {
         assert!(Token::Const.is_keyword());
@@ -242,6 +590,156 @@ mod tests {
         assert!(!Token::Identifier("x".to_string()).is_keyword());
     }
 
+    #[test]
+    fn test_operator_table_matches_lexer() {
+        for (token, symbol, _) in OPERATORS {
+            assert!(token.is_operator(), "{:?}", token);
+            assert_eq!(token.operator_symbol(), Some(*symbol));
+            
+            // The symbol must lex to exactly this token, so the table and #[token] attributes agree
+            let lexed: Vec<Token> = Token::lexer(symbol).map(Result::unwrap).collect();
+            assert_eq!(lexed, vec![token.clone()], "{}", symbol);
+        }
+        
+        assert!(!Token::Dot.is_operator());
+        assert_eq!(Token::Semicolon.operator_symbol(), None);
+    }
+
+    #[test]
+    fn test_keyword_table_matches_lexer() {
+        for (token, name, _) in KEYWORDS {
+            assert_eq!(Token::from_keyword(name).as_ref(), Some(token));
+            let lexed: Vec<Token> = Token::lexer(name).map(Result::unwrap).collect();
+            assert_eq!(lexed, vec![token.clone()], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_every_lexer_keyword_is_listed() {
+        // Read the #[token] attributes themselves, so a keyword added there can't be missed here
+        let keywords: Vec<&str> = include_str!("lexer.rs").lines()
+            .filter_map(|line| line.trim().strip_prefix("#[token(\""))
+            .filter_map(|rest| rest.split('"').next())
+            .filter(|text| text.chars().all(|c| c.is_ascii_alphabetic() || c == '_'))
+            .collect();
+        
+        assert_eq!(keywords.len(), keyword_list().len());
+        for keyword in keywords {
+            assert!(keyword_list().iter().any(|(name, _)| *name == keyword), "{} is missing from KEYWORDS", keyword);
+        }
+    }
+
+    #[test]
+    fn test_keyword_and_operator_lists() {
+        assert!(keyword_list().contains(&("bahubali", "print")));
+        assert!(keyword_list().contains(&("rajini", "raw JavaScript block")));
+        assert_eq!(keyword_list().len(), KEYWORDS.len() + 2);
+        assert!(operator_list().contains(&("~/", "integer divide")));
+        assert_eq!(operator_list().len(), OPERATORS.len());
+    }
+
+    #[test]
+    fn test_object_literal_tokens() {
+        let lexer = Lexer::new("{ a: 1, b: 2 }");
+        
+        assert_eq!(lexer.all_tokens()[2], Token::Colon);
+        assert_eq!(lexer.all_tokens()[4], Token::Comma);
+    }
+
+    #[test]
+    fn test_range_loop_tokens() {
+        let lexer = Lexer::new("eega i in 0..5");
+        
+        assert_eq!(lexer.all_tokens(), &[
+            Token::For,
+            Token::Identifier("i".to_string()),
+            Token::In,
+            Token::Number(0),
+            Token::Range,
+            Token::Number(5),
+        ]);
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let source = "rrr x = 42;";
+        let mut lexer = Lexer::new(source);
+        
+        assert_eq!(lexer.all_spans(), &[0..3, 4..5, 6..7, 8..10, 10..11]);
+        assert_eq!(lexer.current_span(), Some(&(0..3)));
+        lexer.advance();
+        assert_eq!(lexer.current_span(), Some(&(4..5)));
+        
+        let lexer = Lexer::new("x = 1 % 2;\r\n");
+        assert_eq!(lexer.all_tokens().len(), 5);
+        assert_eq!(lexer.error_spans().to_vec(), vec![6..7]);
+    }
+
+    #[test]
+    fn test_lexer_with_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("print", "bahubali").unwrap();
+        
+        let lexer = Lexer::new_with_aliases("print(x); bahubali(x);", &aliases);
+        assert_eq!(lexer.all_tokens()[0], Token::Print);
+        assert_eq!(lexer.all_tokens()[5], Token::Print);
+    }
+
+    #[test]
+    fn test_apply_aliases_skips_strings_and_comments() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("print", "bahubali").unwrap();
+        aliases.insert("let", "pushpa").unwrap();
+        
+        let source = "let x = 1; print(\"print me\"); // print it\nprint(x);";
+        assert_eq!(
+            aliases.apply(source),
+            "pushpa x = 1; bahubali(\"print me\"); // print it\nbahubali(x);"
+        );
+        assert_eq!(aliases.apply("print(\"a \\\" print\");"), "bahubali(\"a \\\" print\");");
+    }
+
+    #[test]
+    fn test_raw_print_block() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("print", "bahubali").unwrap();
+        
+        let source = "bahubali_block {\n  print \"{ x }\n}\nprint(1);";
+        let lexer = Lexer::new_with_aliases(source, &aliases);
+        assert_eq!(lexer.all_tokens()[0], Token::RawPrint("  print \"{ x }".to_string()));
+        assert_eq!(lexer.all_tokens()[1], Token::Print);
+        
+        assert_eq!(raw_block_len("bahubali_block { text }"), None);
+        assert_eq!(raw_block_len("bahubali_block {\n  } else {\n"), None);
+        assert_eq!(raw_block_len("bahubali_block {\n}"), Some(18));
+    }
+
+    #[test]
+    fn test_raw_js_block() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("print", "bahubali").unwrap();
+        
+        let source = "rajini { if (print) { f(\"}\"); } }\nprint(1);";
+        let lexer = Lexer::new_with_aliases(source, &aliases);
+        assert_eq!(lexer.all_tokens()[0], Token::RawJs(" if (print) { f(\"}\"); } ".to_string()));
+        assert_eq!(lexer.all_tokens()[1], Token::Print);
+        
+        assert_eq!(raw_js_len("rajini { a('it''s') }"), Some(21));
+        assert_eq!(raw_js_len("rajini { don't }"), Some(16));
+        assert_eq!(raw_js_len("rajini { { }"), None);
+        assert_eq!(raw_js_len("rajini;"), None);
+    }
+
+    #[test]
+    fn test_invalid_aliases() {
+        let mut aliases = AliasMap::new();
+        
+        assert!(aliases.insert("print", "console").is_err());
+        assert!(aliases.insert("rrr", "pushpa").is_err());
+        assert!(aliases.insert("print2", "bahubali").is_err());
+        assert!(aliases.is_empty());
+    }
+
     #[test]
     fn test_lexer_methods() {
         let source = "rrr x = 42";
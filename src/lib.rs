@@ -3,19 +3,80 @@ pub mod ast;
 pub mod parser;
 pub mod generator;
 pub mod validator;
+pub mod types;
+pub mod optimizer;
+pub mod config;
+pub mod color;
+pub mod error_codes;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod compiler;
+pub mod imports;
+pub mod repl;
+pub mod runner;
+pub mod diagnostics;
+pub mod formatter;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 
-pub use ast::{Statement, Expression};
-pub use compiler::compile;
+pub use ast::{Statement, Expression, Spanned};
+pub use compiler::{compile, compile_ast};
+pub use diagnostics::compile_with_diagnostics;
 pub use parser::parse_program;
 pub use validator::validate_program;
-pub use generator::{generate_statement, generate_expression};
+pub use generator::{generate_statement, generate_expression, generate_expression_typed};
 
 /// Main compilation function that takes TFI source code and returns JavaScript
 pub fn compile_tfi_to_js(source: &str) -> Result<String, Box<dyn std::error::Error>> {
     compiler::compile(source)
 }
 
+/// Compiler version and capabilities, for tools that need to detect them
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BuildInfo {
+    /// Crate version
+    pub version: &'static str,
+    /// Language features and enabled Cargo features
+    pub features: Vec<&'static str>,
+}
+
+/// Language features every build of the compiler supports
+const LANGUAGE_FEATURES: &[&str] = &[
+    "floats",
+    "objects",
+    "string-methods",
+    "indexing",
+    "optional-chaining",
+    "range-loops",
+    "labeled-breaks",
+    "imports",
+    "raw-js",
+    "functions",
+];
+
+/// Describe this build of the compiler
+pub fn build_info() -> BuildInfo {
+    let mut features = LANGUAGE_FEATURES.to_vec();
+    if cfg!(feature = "testing") {
+        features.push("testing");
+    }
+    if cfg!(feature = "lsp") {
+        features.push("lsp");
+    }
+    BuildInfo { version: env!("CARGO_PKG_VERSION"), features }
+}
+
+/// Compile TFI source to JavaScript without any I/O, returning errors as strings
+///
+/// Parse errors are returned rather than printed and phase timings are never taken, so this is
+/// safe to call from WebAssembly, where there is no stderr, clock, filesystem or process.
+pub fn compile_to_js_string(source: &str) -> Result<String, String> {
+    let options = compiler::CompilationOptions::new().with_silent_parsing();
+    compiler::compile_with_options(source, &options)
+        .map(|result| result.js_code)
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,7 +169,44 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_compile_to_js_string() {
+        assert!(compile_to_js_string("rrr x = 10;").unwrap().contains("const x = 10"));
+        
+        let error = compile_to_js_string("bahubali(").unwrap_err();
+        assert!(error.contains("Failed to parse TFI code"));
+        assert!(!error.contains("already printed"));
+    }
 
+    #[test]
+    fn test_build_info_json() {
+        let json = serde_json::to_value(build_info()).unwrap();
+        
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        let features = json["features"].as_array().unwrap();
+        for expected in ["floats", "functions"] {
+            assert!(features.iter().any(|feature| feature == expected), "missing {}", expected);
+        }
+    }
 
-
+    #[test]
+    fn test_compile_path_is_io_free() {
+        // Every module compile_to_js_string reaches; config and main do their own I/O
+        let sources = [
+            ("ast.rs", include_str!("ast.rs")),
+            ("lexer.rs", include_str!("lexer.rs")),
+            ("parser.rs", include_str!("parser.rs")),
+            ("validator.rs", include_str!("validator.rs")),
+            ("types.rs", include_str!("types.rs")),
+            ("optimizer.rs", include_str!("optimizer.rs")),
+            ("generator.rs", include_str!("generator.rs")),
+            ("compiler.rs", include_str!("compiler.rs")),
+            ("error_codes.rs", include_str!("error_codes.rs")),
+        ];
+        
+        for (name, source) in sources {
+            assert!(!source.contains(concat!("std::", "process")), "{} uses std::process", name);
+            assert!(!source.contains(concat!("std::", "fs")), "{} uses std::fs", name);
+        }
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,223 @@
+use crate::diagnostics::{compile_with_diagnostics, Diagnostic, Severity};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+/// JSON-RPC error code for a request the server doesn't implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP `TextDocumentSyncKind.Full`: every change sends the whole document
+const FULL_SYNC: u8 = 1;
+
+/// A minimal language server that publishes compiler diagnostics for open `.tfi` documents
+///
+/// Supports `initialize`, `shutdown` and `exit`, and publishes diagnostics whenever a document is
+/// opened or changed. Documents are synced in full, so there are no incremental edits to apply.
+#[derive(Debug, Default)]
+pub struct Server {
+    /// Text of each open document, by URI
+    documents: HashMap<String, String>,
+    /// Set by the `exit` notification
+    exited: bool,
+}
+
+impl Server {
+    /// Create a server with no open documents
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the client has sent `exit`
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handle one incoming message, returning the responses and notifications to send back
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        
+        match method {
+            "initialize" => vec![response(id, json!({
+                "capabilities": { "textDocumentSync": FULL_SYNC },
+                "serverInfo": { "name": "tfi-lsp", "version": env!("CARGO_PKG_VERSION") },
+            }))],
+            "shutdown" => vec![response(id, Value::Null)],
+            "exit" => {
+                self.exited = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                self.update(document["uri"].as_str(), document["text"].as_str())
+            }
+            "textDocument/didChange" => {
+                // With full sync the last change holds the whole new text
+                let text = params["contentChanges"].as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                self.update(params["textDocument"]["uri"].as_str(), text)
+            }
+            "textDocument/didClose" => {
+                let Some(uri) = params["textDocument"]["uri"].as_str() else {
+                    return vec![];
+                };
+                self.documents.remove(uri);
+                vec![publish_diagnostics(uri, &[])]
+            }
+            // Other notifications, such as `initialized`, need no reply
+            _ if id.is_none() => vec![],
+            _ => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("Unsupported method: {}", method) },
+            })],
+        }
+    }
+
+    /// Store a document's new text and publish its diagnostics
+    fn update(&mut self, uri: Option<&str>, text: Option<&str>) -> Vec<Value> {
+        let (Some(uri), Some(text)) = (uri, text) else {
+            return vec![];
+        };
+        let (_, diagnostics) = compile_with_diagnostics(text);
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![publish_diagnostics(uri, &diagnostics)]
+    }
+}
+
+/// Build a successful response to the request with this id
+fn response(id: Option<Value>, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Build a `textDocument/publishDiagnostics` notification replacing a document's diagnostics
+fn publish_diagnostics(uri: &str, diagnostics: &[Diagnostic]) -> Value {
+    let diagnostics = diagnostics.iter().map(|diagnostic| {
+        let mut value = json!({
+            "range": diagnostic.range,
+            // LSP numbers severities: 1 is an error, 2 a warning
+            "severity": match diagnostic.severity { Severity::Error => 1, Severity::Warning => 2 },
+            "source": "tfi-lang",
+            "message": diagnostic.message,
+        });
+        if let Some(code) = diagnostic.code {
+            value["code"] = json!(code);
+        }
+        value
+    }).collect::<Vec<_>>();
+    
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Read one `Content-Length`-framed message, or `None` at the end of the input
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    
+    let length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "message has no Content-Length header"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(io::Error::from)
+}
+
+/// Write one message with its `Content-Length` header
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Serve LSP messages from `reader` until `exit` or the end of the input
+pub fn serve(mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let mut server = Server::new();
+    while let Some(message) = read_message(&mut reader)? {
+        for reply in server.handle(&message) {
+            write_message(&mut writer, &reply)?;
+        }
+        if server.has_exited() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame messages the way an editor would send them
+    fn framed(messages: &[Value]) -> Vec<u8> {
+        let mut input = vec![];
+        for message in messages {
+            write_message(&mut input, message).unwrap();
+        }
+        input
+    }
+
+    /// Every message the server wrote
+    fn replies(mut output: &[u8]) -> Vec<Value> {
+        std::iter::from_fn(|| read_message(&mut output).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_did_change_publishes_diagnostics() {
+        let uri = "file:///prog.tfi";
+        let input = framed(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                "textDocument": { "uri": uri, "languageId": "tfi", "version": 1, "text": "bahubali(1);" },
+            }}),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{ "text": "bahubali(missing);" }],
+            }}),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]);
+        let mut output = vec![];
+        serve(&input[..], &mut output).unwrap();
+        
+        let replies = replies(&output);
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[0]["result"]["capabilities"]["textDocumentSync"], FULL_SYNC);
+        
+        // The valid program clears diagnostics; the bad one reports the undefined variable
+        assert_eq!(replies[1]["params"]["diagnostics"], json!([]));
+        assert_eq!(replies[2]["method"], "textDocument/publishDiagnostics");
+        assert_eq!(replies[2]["params"]["uri"], uri);
+        let diagnostic = &replies[2]["params"]["diagnostics"][0];
+        assert_eq!(diagnostic["severity"], 1);
+        assert!(diagnostic["message"].as_str().unwrap().contains("missing"), "{}", diagnostic);
+    }
+
+    #[test]
+    fn test_unknown_request_is_an_error() {
+        let mut server = Server::new();
+        let replies = server.handle(&json!({ "jsonrpc": "2.0", "id": 7, "method": "textDocument/hover" }));
+        assert_eq!(replies[0]["error"]["code"], METHOD_NOT_FOUND);
+        
+        assert!(server.handle(&json!({ "jsonrpc": "2.0", "method": "$/cancelRequest" })).is_empty());
+    }
+}
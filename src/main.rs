@@ -1,48 +1,157 @@
 
 use std::fs;
 use std::env;
-use std::path::Path;
-use tfi_lang::compiler::{compile, compile_with_options, CompilationOptions, get_compilation_stats};
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use tfi_lang::compiler::{check, compile_profiled, compile_with_options, CompilationOptions, get_compilation_stats};
+use tfi_lang::runner::RuntimeError;
+use tfi_lang::color::{self, Color, ColorChoice};
+use tfi_lang::config::{Manifest, MANIFEST_FILE};
+use tfi_lang::diagnostics::lint;
+use tfi_lang::error_codes;
+use tfi_lang::formatter::format_source;
+use tfi_lang::generator::{IndentStyle, QuoteStyle};
+use tfi_lang::lexer::{AliasMap, Lexer};
+use tfi_lang::repl::{Reply, Session};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// Parsed command line arguments
+struct CliArgs {
+    input_file: String,
+    output_file: String,
+    /// Compile the input directory tree into this directory instead of a single file
+    out_dir: Option<String>,
+    options: CompilationOptions,
+    /// Print the token stream and exit instead of compiling
+    dump_tokens: bool,
+    /// Print how long each compilation phase took
+    profile: bool,
+    /// Print compilation stats as JSON instead of the summary text
+    stats_json: bool,
+    /// Only parse and validate, without writing or running JavaScript
+    check_only: bool,
+    /// Compile fully and report the output path, without writing or running JavaScript
+    dry_run: bool,
+    /// Print the source reformatted as TFI instead of compiling
+    fmt: bool,
+    /// Run the `lint` subcommand, reporting every warning instead of compiling
+    lint: bool,
+    /// Print the lint report as JSON instead of text
+    lint_json: bool,
+    /// Start an interactive session instead of compiling a file
+    repl: bool,
+    /// When to color diagnostics
+    color: ColorChoice,
+}
+
+fn main() {
     let args: Vec<String> = env::args().collect();
     
+    if let Err(e) = run(&args) {
+        eprintln!("{}", color::paint_error(&format!("Error: {}", e), color::enabled()));
+        std::process::exit(1);
+    }
+}
+
+/// Compile and run the program described by the command line
+fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    
+    // Load project defaults, if there is a manifest
+    let manifest = Manifest::load(Path::new(MANIFEST_FILE))?.unwrap_or_default();
+    
     // Parse command line arguments
-    let (input_file, output_file, options) = parse_arguments(&args)?;
+    let CliArgs {
+        input_file, output_file, out_dir, options, dump_tokens, profile, stats_json, check_only, dry_run, fmt, lint: lint_only, lint_json, repl, color
+    } = parse_arguments(args, &manifest)?;
+    color::set_enabled(color.should_color());
+    
+    if repl {
+        return run_repl();
+    }
+    
+    if let Some(out_dir) = out_dir {
+        return build_directory(Path::new(&input_file), Path::new(&out_dir), &options);
+    }
     
     // Validate input file
     if !input_file.ends_with(".tfi") {
-        eprintln!("Error: Input file must have a .tfi extension (e.g., main.tfi)");
-        std::process::exit(1);
+        return Err("Input file must have a .tfi extension (e.g., main.tfi)".into());
     }
     
     // Read source file
     let source = fs::read_to_string(&input_file)?;
+    let options = options.with_source_path(&input_file);
+    
+    if dump_tokens {
+        print_tokens(&source, &options.aliases);
+        return Ok(());
+    }
+    
+    if fmt {
+        print!("{}", format_file(&source, &input_file, &options.aliases)?);
+        return Ok(());
+    }
+    
+    if lint_only {
+        let report = lint(&source, &options);
+        if lint_json {
+            println!("{}", report.to_json());
+        } else {
+            println!("{}", report.summary(&input_file));
+        }
+        if report.has_errors() {
+            return Err(format!("{} has lint errors", input_file).into());
+        }
+        return Ok(());
+    }
+    
+    if check_only {
+        let warnings = check(&source, &options)?;
+        for warning in &warnings {
+            eprintln!("{}", color::paint(warning, Color::Yellow, color::enabled()));
+        }
+        println!("{}: no errors found", input_file);
+        return Ok(());
+    }
     
     // Compile with options
-    let result = if options.format_output || options.add_comments {
-        compile_with_options(&source, &options)?
-    } else {
-        compile(&source).map(|js_code| {
-            tfi_lang::compiler::CompilationResult::new(js_code, 0)
-        })?
-    };
+    let (result, timings) = compile_profiled(&source, &options)?;
     
-    // Write output
-    fs::write(&output_file, &result.js_code)?;
-    println!("Compiled successfully! Output written to: {}", output_file);
+    // Write output, unless this is only a dry run
+    if dry_run {
+        println!("Compiled successfully! Dry run: would write {} bytes to: {}", result.file_contents().len(), output_file);
+    } else {
+        if let Some(dir) = Path::new(&output_file).parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&output_file, result.file_contents())?;
+        println!("Compiled successfully! Output written to: {}", output_file);
+    }
     
     // Print warnings if any
     if result.has_warnings() {
-        eprintln!("Compilation warnings:");
+        eprintln!("{}", color::paint("Compilation warnings:", Color::Yellow, color::enabled()));
         for warning in &result.warnings {
-            eprintln!("  {}", warning);
+            eprintln!("  {}", color::paint(warning, Color::Yellow, color::enabled()));
         }
     }
     
     // Print compilation stats
-    if let Ok(stats) = get_compilation_stats(&source) {
-        println!("{}", stats.summary());
+    if let Ok(stats) = get_compilation_stats(&options.aliases.apply(&source)) {
+        if stats_json {
+            println!("{}", stats.to_json());
+        } else {
+            println!("{}", stats.summary());
+        }
+    }
+    
+    if profile {
+        println!("{}", timings.summary());
+    }
+    
+    if dry_run {
+        return Ok(());
     }
     
     // Execute the generated JavaScript
@@ -58,18 +167,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         eprint!("{}", String::from_utf8_lossy(&output.stderr));
     }
     
+    // The compile succeeded, but a program that throws should still fail the command
+    RuntimeError::check(&output)?;
     Ok(())
 }
 
-/// Parse command line arguments
-fn parse_arguments(args: &[String]) -> Result<(String, String, CompilationOptions), Box<dyn std::error::Error>> {
-    let mut input_file = "main.tfi".to_string();
+/// Parse command line arguments on top of the manifest defaults
+fn parse_arguments(args: &[String], manifest: &Manifest) -> Result<CliArgs, Box<dyn std::error::Error>> {
+    let mut input_file = None;
     let mut output_file = String::new();
-    let mut options = CompilationOptions::new();
+    let mut out_dir = None;
+    let mut options = manifest.compilation_options();
+    let mut dump_tokens = false;
+    let mut profile = false;
+    let mut stats_json = false;
+    let mut check_only = false;
+    let mut dry_run = false;
+    let mut fmt = false;
+    let mut lint_json = false;
+    let mut repl = false;
+    let mut color = ColorChoice::Auto;
     
-    let mut i = 1;
+    // `tfi-lang lint FILE` takes the same options as a compile, where --format picks the report format
+    let lint = args.get(1).is_some_and(|arg| arg == "lint");
+    let mut i = if lint { 2 } else { 1 };
     while i < args.len() {
         match args[i].as_str() {
+            "--format" if lint => {
+                match args.get(i + 1).map(String::as_str) {
+                    Some("json") => lint_json = true,
+                    Some("text") => lint_json = false,
+                    Some(other) => return Err(format!("--format expects text or json, got '{}'", other).into()),
+                    None => return Err("--format option requires text or json".into()),
+                }
+                i += 1;
+            }
             "--output" | "-o" => {
                 if i + 1 < args.len() {
                     output_file = args[i + 1].clone();
@@ -78,9 +210,53 @@ fn parse_arguments(args: &[String]) -> Result<(String, String, CompilationOption
                     return Err("--output option requires a file path".into());
                 }
             }
+            "--out-dir" => {
+                if i + 1 < args.len() {
+                    out_dir = Some(args[i + 1].clone());
+                    i += 1;
+                } else {
+                    return Err("--out-dir option requires a directory path".into());
+                }
+            }
             "--format" | "-f" => {
                 options = options.with_formatting();
             }
+            "--indent-width" => {
+                if i + 1 < args.len() {
+                    let width = args[i + 1].parse::<usize>()
+                        .map_err(|_| format!("--indent-width expects a number, got '{}'", args[i + 1]))?;
+                    options = options.with_indent_width(width);
+                    i += 1;
+                } else {
+                    return Err("--indent-width option requires a number".into());
+                }
+            }
+            "--max-statements" => {
+                if i + 1 < args.len() {
+                    let max = args[i + 1].parse::<usize>()
+                        .map_err(|_| format!("--max-statements expects a number, got '{}'", args[i + 1]))?;
+                    options = options.with_max_statements(max);
+                    i += 1;
+                } else {
+                    return Err("--max-statements option requires a number".into());
+                }
+            }
+            "--loop-guard" => {
+                if i + 1 < args.len() {
+                    let max = args[i + 1].parse::<u32>()
+                        .map_err(|_| format!("--loop-guard expects a number, got '{}'", args[i + 1]))?;
+                    options = options.with_loop_guard(max);
+                    i += 1;
+                } else {
+                    return Err("--loop-guard option requires a number".into());
+                }
+            }
+            "--indent-tabs" => {
+                options = options.with_indent_style(IndentStyle::Tabs);
+            }
+            "--single-quotes" => {
+                options = options.with_quote_style(QuoteStyle::Single);
+            }
             "--comments" | "-c" => {
                 options = options.with_comments();
             }
@@ -90,20 +266,102 @@ fn parse_arguments(args: &[String]) -> Result<(String, String, CompilationOption
             "--minify" | "-m" => {
                 options = options.with_minification();
             }
+            "--mangle" => {
+                options = options.with_mangled_names();
+            }
+            "--wrap-iife" => {
+                options = options.with_iife_wrapping();
+            }
+            "--preserve-comments" => {
+                options = options.with_preserved_comments();
+            }
+            "--explicit-coercion" => {
+                options = options.with_explicit_coercion();
+            }
+            "--stringify-objects" => {
+                options = options.with_stringified_objects();
+            }
+            "--no-semicolons" => {
+                options = options.with_omitted_semicolons();
+            }
+            "--allow-empty-print" => {
+                options = options.with_empty_print();
+            }
+            "--allow-global" => {
+                if i + 1 < args.len() {
+                    options = options.with_allowed_global(args[i + 1].as_str());
+                    i += 1;
+                } else {
+                    return Err("--allow-global option requires a name".into());
+                }
+            }
+            "--optimize" | "-O" => {
+                options = options.with_constant_folding();
+            }
+            "--keep-names" => {
+                options = options.with_kept_names();
+            }
+            "--auto-chain" => {
+                options = options.with_auto_chain();
+            }
+            "--merge-prints" => {
+                options = options.with_merged_prints();
+            }
+            "--dump-tokens" => {
+                dump_tokens = true;
+            }
+            "--profile" => {
+                profile = true;
+            }
+            "--stats-json" => {
+                stats_json = true;
+            }
+            "--check" => {
+                check_only = true;
+            }
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--fmt" => {
+                fmt = true;
+            }
+            "--repl" => {
+                repl = true;
+            }
+            "--color" => {
+                if i + 1 < args.len() {
+                    color = ColorChoice::parse(&args[i + 1])
+                        .ok_or_else(|| format!("--color expects auto, always or never, got '{}'", args[i + 1]))?;
+                    i += 1;
+                } else {
+                    return Err("--color option requires auto, always or never".into());
+                }
+            }
             "--help" | "-h" => {
                 print_usage();
                 std::process::exit(0);
             }
+            "--explain" => {
+                let code = args.get(i + 1).ok_or("--explain option requires an error code")?;
+                let explanation = error_codes::explain(code)
+                    .ok_or_else(|| format!("Unknown error code: {}", code))?;
+                println!("{}", explanation);
+                std::process::exit(0);
+            }
             "--version" | "-v" => {
-                println!("TFI Language Compiler v1.0.0");
+                println!("TFI Language Compiler v{}", tfi_lang::build_info().version);
+                std::process::exit(0);
+            }
+            "--version-json" => {
+                println!("{}", serde_json::to_string(&tfi_lang::build_info())?);
                 std::process::exit(0);
             }
             arg if arg.starts_with('-') => {
                 return Err(format!("Unknown option: {}", arg).into());
             }
             _ => {
-                if input_file == "main.tfi" {
-                    input_file = args[i].clone();
+                if input_file.is_none() {
+                    input_file = Some(args[i].clone());
                 } else {
                     return Err("Multiple input files specified".into());
                 }
@@ -112,19 +370,165 @@ fn parse_arguments(args: &[String]) -> Result<(String, String, CompilationOption
         i += 1;
     }
     
+    let input_file = input_file
+        .or_else(|| manifest.entry.clone())
+        .unwrap_or_else(|| "main.tfi".to_string());
+    
     // Generate default output file if not specified
     if output_file.is_empty() {
-        output_file = generate_default_output_file(&input_file);
+        output_file = manifest.output_file(&input_file, options.output_extension())
+            .unwrap_or_else(|| generate_default_output_file(&input_file, &options));
+    }
+    
+    Ok(CliArgs { input_file, output_file, out_dir, options, dump_tokens, profile, stats_json, check_only, dry_run, fmt, lint, lint_json, repl, color })
+}
+
+/// Node program that runs each line of JSON-encoded JavaScript in one shared global scope
+///
+/// A NUL byte on stderr marks the end of each run, so the prompt waits for the output.
+const REPL_HOST: &str = r#"
+const vm = require("vm");
+require("readline").createInterface({ input: process.stdin }).on("line", (line) => {
+    try { vm.runInThisContext(JSON.parse(line)); } catch (e) { console.error(String(e)); }
+    process.stderr.write("\0");
+});
+"#;
+
+/// Read TFI statements and meta-commands from stdin, running each in a persistent node process
+fn run_repl() -> Result<(), Box<dyn std::error::Error>> {
+    let mut node = std::process::Command::new("node")
+        .args(["-e", REPL_HOST])
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("The interactive mode needs node: {}", e))?;
+    let mut node_input = node.stdin.take().ok_or("Failed to open node's stdin")?;
+    let mut node_errors = node.stderr.take().ok_or("Failed to open node's stderr")?;
+    
+    println!("TFI interactive mode. Type .help for commands.");
+    let mut session = Session::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("tfi> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        
+        match session.eval(&line) {
+            Ok(Reply::Run(js_code)) if js_code.is_empty() => {}
+            Ok(Reply::Run(js_code)) => {
+                writeln!(node_input, "{}", serde_json::to_string(&js_code)?)?;
+                // Echo node's errors until it marks the end of the run
+                let mut byte = [0u8];
+                while node_errors.read(&mut byte)? == 1 && byte[0] != 0 {
+                    std::io::stderr().write_all(&byte)?;
+                }
+            }
+            Ok(Reply::Message(message)) => println!("{}", message),
+            Ok(Reply::Exit) => break,
+            Err(e) => eprintln!("{}", color::paint_error(&e, color::enabled())),
+        }
+    }
+    
+    drop(node_input);
+    node.wait()?;
+    Ok(())
+}
+
+/// Compile every .tfi file under `src_dir`, mirroring the directory structure under `out_dir`
+///
+/// A file that fails doesn't stop the build; every file is tried and the failures are listed
+/// after the summary.
+fn build_directory(src_dir: &Path, out_dir: &Path, options: &CompilationOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if !src_dir.is_dir() {
+        return Err(format!("--out-dir expects a source directory, got '{}'", src_dir.display()).into());
+    }
+    
+    let mut sources = Vec::new();
+    find_sources(src_dir, &mut sources)?;
+    sources.sort();
+    
+    let mut compiled = 0;
+    let mut failures = Vec::new();
+    for source_path in &sources {
+        let output_path = out_dir.join(source_path.strip_prefix(src_dir)?).with_extension(options.output_extension());
+        match compile_file(source_path, &output_path, options) {
+            Ok(()) => {
+                compiled += 1;
+                println!("{} -> {}", source_path.display(), output_path.display());
+            }
+            Err(e) => {
+                eprintln!("{}", color::paint_error(&format!("{}: {}", source_path.display(), e), color::enabled()));
+                failures.push(source_path);
+            }
+        }
     }
     
-    Ok((input_file, output_file, options))
+    println!("{} compiled, {} failed", compiled, failures.len());
+    if failures.len() > 1 {
+        for path in &failures {
+            eprintln!("  failed: {}", path.display());
+        }
+    }
+    if !failures.is_empty() {
+        return Err(format!("{} of {} files failed to compile", failures.len(), sources.len()).into());
+    }
+    Ok(())
+}
+
+/// Recursively collect the .tfi files under a directory
+fn find_sources(dir: &Path, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_sources(&path, found)?;
+        } else if path.extension().is_some_and(|ext| ext == "tfi") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compile one source file and write the JavaScript, creating parent directories as needed
+fn compile_file(source_path: &Path, output_path: &Path, options: &CompilationOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let source = fs::read_to_string(source_path)?;
+    let result = compile_with_options(&source, &options.clone().with_source_path(source_path))?;
+    if let Some(dir) = output_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(output_path, result.file_contents())?;
+    Ok(())
+}
+
+/// Reformat a file as TFI, reading its keyword aliases the way the compiler does
+///
+/// Aliased keywords come back as the TFI keywords they stand for.
+fn format_file(source: &str, input_file: &str, aliases: &AliasMap) -> Result<String, Box<dyn std::error::Error>> {
+    format_source(&aliases.apply(source))
+        .map_err(|_| format!("{} has syntax errors; nothing was formatted", input_file).into())
 }
 
-/// Generate a default output file name based on the input file
-fn generate_default_output_file(input_file: &str) -> String {
+/// Print the lexer token stream, one token per line with its span and source slice
+fn print_tokens(source: &str, aliases: &AliasMap) {
+    let lexer = Lexer::new_with_aliases(source, aliases);
+    // Text the lexer couldn't read is listed in place as an Error, so the dump hides nothing
+    let mut entries = lexer.all_tokens().iter()
+        .map(|token| format!("{:?}", token))
+        .zip(lexer.all_spans().iter().cloned())
+        .chain(lexer.error_spans().iter().map(|span| ("Error".to_string(), span.clone())))
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(_, span)| span.start);
+    for (token, span) in entries {
+        println!("{} @ {}..{} {:?}", token, span.start, span.end, &source[span.clone()]);
+    }
+}
+
+/// Generate a default output file name based on the input file and the output it will hold
+fn generate_default_output_file(input_file: &str, options: &CompilationOptions) -> String {
     let path = Path::new(input_file);
     let stem = path.file_stem().unwrap_or_default().to_string_lossy();
-    format!("{}.js", stem)
+    format!("{}.{}", stem, options.output_extension())
 }
 
 /// Print usage information
@@ -132,18 +536,47 @@ fn print_usage() {
     println!("TFI Language Compiler");
     println!();
     println!("Usage: tfi-lang [OPTIONS] [FILE]");
+    println!("       tfi-lang lint [--format text|json] [OPTIONS] [FILE]");
     println!();
     println!("Arguments:");
-    println!("  FILE                    Input TFI file (default: main.tfi)");
+    println!("  FILE                    Input TFI file (default: entry from tfi.toml, or main.tfi)");
     println!();
     println!("Options:");
-    println!("  -o, --output FILE       Output JavaScript file (default: <input>.js)");
+    println!("  -o, --output FILE       Output JavaScript file (default: <input>.js, or <input>.min.js with --minify)");
+    println!("      --out-dir DIR       Compile every .tfi file under the FILE directory into DIR");
     println!("  -f, --format            Format the output JavaScript code");
+    println!("      --indent-width N    Spaces per indentation level when formatting (default: 4)");
+    println!("      --indent-tabs       Indent formatted output with tabs");
+    println!("      --single-quotes     Emit single-quoted string literals");
+    println!("      --no-semicolons     Leave statements without trailing semicolons");
     println!("  -c, --comments          Add source comments to output");
     println!("  -s, --strict            Enable strict mode");
     println!("  -m, --minify            Minify the output");
+    println!("      --mangle            With --minify, shorten variable names to a, b, c...");
+    println!("      --wrap-iife         Wrap the output in an IIFE to avoid global scope pollution");
+    println!("      --preserve-comments Carry // comments into the output");
+    println!("      --explicit-coercion Wrap numbers joined to strings with + in String(...)");
+    println!("      --stringify-objects Print objects with JSON.stringify");
+    println!("  -O, --optimize          Fold arithmetic on number literals at compile time");
+    println!("      --keep-names        With --optimize, comment folded constants with their expression");
+    println!("      --auto-chain        Compile 0 < x < 10 as (0 < x) && (x < 10)");
+    println!("      --merge-prints      Merge adjacent bahubali calls into one console.log");
+    println!("      --allow-empty-print Allow bahubali() with no arguments to print a blank line");
+    println!("      --allow-global NAME Allow reading the JavaScript global NAME, e.g. Math (repeatable)");
+    println!("      --max-statements N  Reject programs with more than N statements, including nested ones");
+    println!("      --loop-guard N      Make loops throw after N iterations instead of hanging");
+    println!("      --dump-tokens       Print the lexer token stream and exit");
+    println!("      --check             Parse and validate only; write and run nothing");
+    println!("      --fmt               Print the source reformatted as TFI, keeping comments and blank lines");
+    println!("      --dry-run           Compile and report the output path; write and run nothing");
+    println!("      --repl              Start an interactive session (.load, .save, .help, .exit)");
+    println!("      --profile           Print the time spent in each compilation phase");
+    println!("      --stats-json        Print compilation stats as JSON instead of a summary");
+    println!("      --color WHEN        Color diagnostics: auto, always or never (default: auto)");
+    println!("      --explain CODE      Explain an error code, e.g. --explain E0001");
     println!("  -h, --help              Show this help message");
     println!("  -v, --version           Show version information");
+    println!("      --version-json      Print the version and supported features as JSON");
     println!();
     println!("Examples:");
     println!("  tfi-lang main.tfi                           # Output: main.js");
@@ -151,4 +584,110 @@ fn print_usage() {
     println!("  tfi-lang -o dist/script.js program.tfi      # Output: dist/script.js");
     println!("  tfi-lang --format --comments script.tfi     # Output: script.js");
     println!("  tfi-lang -f -c -s -o minified.js app.tfi    # Output: minified.js");
+    println!("  tfi-lang --out-dir dist src/                # Output: dist/**/*.js");
+    println!();
+    println!("Defaults for FILE, the output directory and options can be set in a tfi.toml");
+    println!("manifest in the current directory; command line flags take precedence.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("tfi-lang").chain(list.iter().copied()).map(String::from).collect()
+    }
+
+    #[test]
+    fn test_manifest_options_used_without_flags() {
+        let manifest = Manifest::parse("entry = \"app.tfi\"\noutput_dir = \"dist\"\n[options]\nminify = true").unwrap();
+        let cli = parse_arguments(&args(&[]), &manifest).unwrap();
+        
+        assert!(cli.options.minify);
+        assert_eq!(cli.input_file, "app.tfi");
+        assert_eq!(cli.output_file, Path::new("dist").join("app.min.js").to_string_lossy());
+    }
+
+    #[test]
+    fn test_cli_flags_override_manifest() {
+        let manifest = Manifest::parse("entry = \"app.tfi\"\noutput_dir = \"dist\"\n[options]\nindent_width = 2").unwrap();
+        let cli = parse_arguments(&args(&["--indent-width", "8", "-o", "out.js", "other.tfi"]), &manifest).unwrap();
+        
+        assert_eq!(cli.options.indent_width, 8);
+        assert_eq!(cli.input_file, "other.tfi");
+        assert_eq!(cli.output_file, "out.js");
+    }
+
+    #[test]
+    fn test_fmt_reads_manifest_aliases() {
+        let manifest = Manifest::parse("[aliases]\nconst = \"rrr\"").unwrap();
+        let cli = parse_arguments(&args(&["--fmt", "app.tfi"]), &manifest).unwrap();
+        let source = "const x=1;\nbahubali(x);";
+        
+        let formatted = format_file(source, "app.tfi", &cli.options.aliases).unwrap();
+        assert_eq!(formatted, "rrr x = 1;\nbahubali(x);\n");
+        assert!(format_file(source, "app.tfi", &AliasMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_color_never_has_no_escape_codes() {
+        let cli = parse_arguments(&args(&["--color", "never"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.color, ColorChoice::Never);
+        
+        let report = color::paint_error("❌ Parse Error [E0100]\n   💡 Suggestion: add a semicolon", cli.color.should_color());
+        assert!(!report.contains('\x1b'));
+        
+        assert!(parse_arguments(&args(&["--color", "rainbow"]), &Manifest::default()).is_err());
+    }
+
+    #[test]
+    fn test_max_statements_flag() {
+        let cli = parse_arguments(&args(&["--max-statements", "5"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.options.max_statements, Some(5));
+        
+        assert!(parse_arguments(&args(&["--max-statements", "lots"]), &Manifest::default()).is_err());
+        
+        let cli = parse_arguments(&args(&["--loop-guard", "1000"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.options.loop_guard, Some(1000));
+    }
+
+    #[test]
+    fn test_allow_global_is_repeatable() {
+        let cli = parse_arguments(&args(&["--allow-global", "Math", "--allow-global", "JSON"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.options.allowed_globals, vec!["Math", "JSON"]);
+        
+        assert!(parse_arguments(&args(&["--allow-global"]), &Manifest::default()).is_err());
+    }
+
+    #[test]
+    fn test_defaults_without_manifest() {
+        let cli = parse_arguments(&args(&[]), &Manifest::default()).unwrap();
+        
+        assert!(!cli.options.minify);
+        assert_eq!(cli.input_file, "main.tfi");
+        assert_eq!(cli.output_file, "main.js");
+    }
+
+    #[test]
+    fn test_lint_subcommand() {
+        let cli = parse_arguments(&args(&["lint", "--format", "json", "app.tfi"]), &Manifest::default()).unwrap();
+        assert!(cli.lint && cli.lint_json);
+        assert_eq!(cli.input_file, "app.tfi");
+        
+        // Outside lint, --format still formats the JavaScript
+        let cli = parse_arguments(&args(&["--format", "app.tfi"]), &Manifest::default()).unwrap();
+        assert!(!cli.lint && cli.options.format_output);
+        
+        assert!(parse_arguments(&args(&["lint", "--format", "xml"]), &Manifest::default()).is_err());
+    }
+
+    #[test]
+    fn test_minified_output_defaults_to_min_js() {
+        let cli = parse_arguments(&args(&["--minify", "src/app.tfi"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.output_file, "app.min.js");
+        
+        // An explicit output file is used as given
+        let cli = parse_arguments(&args(&["--minify", "-o", "app.js", "src/app.tfi"]), &Manifest::default()).unwrap();
+        assert_eq!(cli.output_file, "app.js");
+    }
 }
@@ -0,0 +1,841 @@
+use crate::ast::{Expression, Statement, Spanned, COMPARISON_OPERATORS};
+use crate::compiler::CompilationError;
+use crate::error_codes;
+use std::collections::HashMap;
+
+/// A transformation of the validated AST, run just before JavaScript is generated
+///
+/// Implement this to add domain-specific rewrites and pass them to `compile_with_passes`.
+pub trait Pass {
+    /// Transform the program, or fail compilation with an error
+    fn run(&self, statements: Vec<Spanned<Statement>>) -> Result<Vec<Spanned<Statement>>, CompilationError>;
+}
+
+/// Built-in pass that merges adjacent prints, enabled by `--merge-prints`
+pub struct MergePrints;
+
+impl Pass for MergePrints {
+    fn run(&self, statements: Vec<Spanned<Statement>>) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+        Ok(merge_adjacent_prints(statements))
+    }
+}
+
+/// Built-in pass that shortens variable names to `a`, `b`, `c`..., enabled by `--minify --mangle`
+pub struct MangleNames;
+
+impl Pass for MangleNames {
+    fn run(&self, statements: Vec<Spanned<Statement>>) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+        Ok(mangle_names(statements))
+    }
+}
+
+/// JavaScript reserved words and restricted names that a mangled name must never be
+const JS_RESERVED: &[&str] = &[
+    "arguments", "await", "break", "case", "catch", "class", "const", "continue", "debugger",
+    "default", "delete", "do", "else", "enum", "eval", "export", "extends", "false", "finally",
+    "for", "function", "if", "implements", "import", "in", "instanceof", "interface", "let", "new",
+    "null", "package", "private", "protected", "public", "return", "static", "super", "switch",
+    "this", "throw", "true", "try", "typeof", "var", "void", "while", "with", "yield",
+];
+
+/// Rename every variable to a short name, deterministically and respecting block scopes
+///
+/// Names are handed out in declaration order, so the same program always mangles the same way.
+/// A name is only reused once the block that declared it has closed. Labels, properties, object
+/// keys and method names are left unchanged. A program with a `rajini` block is returned as it
+/// is, since its raw JavaScript may use any variable by its original name.
+pub fn mangle_names(statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+    let mut mangler = Mangler { scopes: vec![HashMap::new()], next: 0, saw_raw_js: false };
+    let mangled = mangler.statements(statements.clone());
+    if mangler.saw_raw_js { statements } else { mangled }
+}
+
+/// Renaming state for `mangle_names`
+struct Mangler {
+    /// Original to mangled names, innermost scope last
+    scopes: Vec<HashMap<String, String>>,
+    /// Index of the next short name to hand out
+    next: usize,
+    /// Whether a `rajini` block was found anywhere in the program
+    saw_raw_js: bool,
+}
+
+impl Mangler {
+    /// Give a newly declared variable the next free short name in the current scope
+    fn declare(&mut self, name: String) -> String {
+        let mangled = loop {
+            let candidate = short_name(self.next);
+            self.next += 1;
+            if !JS_RESERVED.contains(&candidate.as_str()) {
+                break candidate;
+            }
+        };
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, mangled.clone());
+        }
+        mangled
+    }
+    
+    /// Look up the mangled name of a variable in the innermost scope that declares it
+    fn resolve(&self, name: String) -> String {
+        self.scopes.iter().rev().find_map(|scope| scope.get(&name).cloned()).unwrap_or(name)
+    }
+    
+    /// Run `f` in a new scope, freeing its names for reuse once it closes
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let next = self.next;
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        self.next = next;
+        result
+    }
+    
+    /// Rename the statements of a block in the current scope
+    fn statements(&mut self, statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+        statements.into_iter().map(|stmt| stmt.map(|node| self.statement(node))).collect()
+    }
+    
+    /// Rename the statements of a block in a scope of its own
+    fn block(&mut self, statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+        self.scoped(|mangler| mangler.statements(statements))
+    }
+    
+    /// Rename a single statement
+    fn statement(&mut self, stmt: Statement) -> Statement {
+        match stmt {
+            Statement::Print(exprs) => Statement::Print(self.expressions(exprs)),
+            Statement::PrintInline(exprs) => Statement::PrintInline(self.expressions(exprs)),
+            Statement::PrintJoined(exprs, sep, end) => {
+                Statement::PrintJoined(self.expressions(exprs), self.expression(sep), self.expression(end))
+            }
+            Statement::Const(name, expr) => {
+                let (name, expr) = self.declaration(name, expr);
+                Statement::Const(name, expr)
+            }
+            Statement::MultiConst(declarators) => Statement::MultiConst(
+                declarators.into_iter().map(|(name, expr)| self.declaration(name, expr)).collect()
+            ),
+            Statement::Let(name, expr) => {
+                let (name, expr) = self.declaration(name, expr);
+                Statement::Let(name, expr)
+            }
+            Statement::Assign(name, expr) => Statement::Assign(self.resolve(name), self.expression(expr)),
+            Statement::Increment(name, decrement) => Statement::Increment(self.resolve(name), decrement),
+            Statement::If(cond, then_block, else_block) => {
+                let cond = self.expression(cond);
+                let then_block = self.block(then_block);
+                Statement::If(cond, then_block, else_block.map(|block| self.block(block)))
+            }
+            Statement::While(cond, block) => {
+                let cond = self.expression(cond);
+                Statement::While(cond, self.block(block))
+            }
+            // The loop variable is scoped to the loop
+            Statement::For(init, cond, update, block) => self.scoped(|mangler| {
+                let init = init.map(|init| Box::new(mangler.statement(*init)));
+                let cond = cond.map(|cond| mangler.expression(cond));
+                let update = update.map(|update| Box::new(mangler.statement(*update)));
+                Statement::For(init, cond, update, mangler.block(block))
+            }),
+            Statement::ForRange(name, start, end, block) => {
+                let start = self.expression(start);
+                let end = self.expression(end);
+                self.scoped(|mangler| {
+                    let name = mangler.declare(name);
+                    Statement::ForRange(name, start, end, mangler.block(block))
+                })
+            }
+            Statement::Labeled(label, body) => Statement::Labeled(label, Box::new(self.statement(*body))),
+            Statement::Expr(expr) => Statement::Expr(self.expression(expr)),
+            Statement::Assert(cond, source) => Statement::Assert(self.expression(cond), source),
+            Statement::Return(value) => Statement::Return(value.map(|value| self.expression(value))),
+            Statement::RawJs(code) => {
+                self.saw_raw_js = true;
+                Statement::RawJs(code)
+            }
+            other => other,
+        }
+    }
+    
+    /// Rename a declared variable and its value
+    ///
+    /// The value is renamed before the declaration takes effect, except for a function, which may
+    /// call itself by name.
+    fn declaration(&mut self, name: String, expr: Expression) -> (String, Expression) {
+        if matches!(expr, Expression::Lambda(_, _)) {
+            let name = self.declare(name);
+            (name, self.expression(expr))
+        } else {
+            let expr = self.expression(expr);
+            (self.declare(name), expr)
+        }
+    }
+    
+    /// Rename a list of expressions
+    fn expressions(&mut self, exprs: Vec<Expression>) -> Vec<Expression> {
+        exprs.into_iter().map(|expr| self.expression(expr)).collect()
+    }
+    
+    /// Rename the variables an expression refers to
+    fn expression(&mut self, expr: Expression) -> Expression {
+        let mut rename = |expr: Box<Expression>| Box::new(self.expression(*expr));
+        match expr {
+            Expression::Identifier(name) => Expression::Identifier(self.resolve(name)),
+            Expression::BinaryOp(left, op, right) => {
+                let left = rename(left);
+                Expression::BinaryOp(left, op, rename(right))
+            }
+            Expression::Member(object, field) => Expression::Member(rename(object), field),
+            Expression::OptionalMember(object, field) => Expression::OptionalMember(rename(object), field),
+            Expression::Grouping(inner) => Expression::Grouping(rename(inner)),
+            Expression::MethodCall(object, method, args) => {
+                let object = rename(object);
+                Expression::MethodCall(object, method, self.expressions(args))
+            }
+            Expression::Object(entries) => Expression::Object(
+                entries.into_iter().map(|(key, value)| (key, self.expression(value))).collect()
+            ),
+            Expression::Index(object, index) => {
+                let object = rename(object);
+                Expression::Index(object, rename(index))
+            }
+            Expression::IndexFromEnd(object, n) => Expression::IndexFromEnd(rename(object), n),
+            Expression::Slice(object, start, end) => {
+                let object = rename(object);
+                let start = rename(start);
+                Expression::Slice(object, start, rename(end))
+            }
+            Expression::Conditional(cond, then_branch, else_branch) => {
+                let cond = rename(cond);
+                let then_branch = rename(then_branch);
+                Expression::Conditional(cond, then_branch, rename(else_branch))
+            }
+            Expression::Folded(value, original) => {
+                let value = rename(value);
+                Expression::Folded(value, rename(original))
+            }
+            // Parameters are declared in the function's own scope
+            Expression::Lambda(params, body) => self.scoped(|mangler| {
+                let params = params.into_iter().map(|param| mangler.declare(param)).collect();
+                Expression::Lambda(params, mangler.statements(body))
+            }),
+            Expression::Call(callee, args) => {
+                let callee = rename(callee);
+                Expression::Call(callee, self.expressions(args))
+            }
+            other => other,
+        }
+    }
+}
+
+/// The short name at an index: a..z, then aa, ab, ...
+fn short_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'a' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Merge immediately-adjacent print statements into a single print
+///
+/// `bahubali("a"); bahubali("b");` becomes `bahubali("a", "b");`. Note that this changes the
+/// output format: console.log joins its arguments with a space on one line, so the merged
+/// values are no longer printed on separate lines.
+pub fn merge_adjacent_prints(statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+    let mut merged: Vec<Spanned<Statement>> = Vec::with_capacity(statements.len());
+    
+    for stmt in statements {
+        let stmt = stmt.map(merge_in_blocks);
+        
+        // The merged print keeps the line of the first statement; an empty print is a blank line
+        // of its own, so it is never merged
+        if let Statement::Print(args) = &stmt.node
+            && let Some(Statement::Print(previous)) = merged.last_mut().map(|last| &mut last.node)
+            && !args.is_empty()
+            && !previous.is_empty()
+        {
+            previous.extend(args.iter().cloned());
+            continue;
+        }
+        
+        merged.push(stmt);
+    }
+    
+    merged
+}
+
+/// Apply print merging inside the blocks of a control structure
+fn merge_in_blocks(stmt: Statement) -> Statement {
+    match stmt {
+        Statement::If(cond, then_block, else_block) => {
+            Statement::If(cond, merge_adjacent_prints(then_block), else_block.map(merge_adjacent_prints))
+        }
+        Statement::While(cond, block) => Statement::While(cond, merge_adjacent_prints(block)),
+        Statement::For(init, cond, update, block) => {
+            Statement::For(init, cond, update, merge_adjacent_prints(block))
+        }
+        Statement::ForRange(name, start, end, block) => {
+            Statement::ForRange(name, start, end, merge_adjacent_prints(block))
+        }
+        Statement::Labeled(label, body) => Statement::Labeled(label, Box::new(merge_in_blocks(*body))),
+        other => other,
+    }
+}
+
+/// Evaluate integer `+`, `-` and `*` on number literals at compile time
+///
+/// `bahubali(2 * 3 + x);` becomes `bahubali(6 + x);`. Operands may also be `rrr` constants whose
+/// value is known, so `rrr w = 5; bahubali(w * 2);` prints `10`; a constant on its own is left as
+/// is. Operations that would overflow, and all divisions, are left for JavaScript to evaluate.
+pub fn fold_constants(statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+    Folder::new(false).statements(statements)
+}
+
+/// Fold constants like `fold_constants`, keeping the original of any expression that read an
+/// `rrr` constant
+///
+/// `rrr area = width * height;` becomes `rrr area = 50;`, annotated with `width * height`.
+pub fn fold_constants_keeping_names(statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+    Folder::new(true).statements(statements)
+}
+
+/// Rewrite chained comparisons such as `0 < x < 10` into `(0 < x) && (x < 10)`
+///
+/// The middle operand is evaluated twice after the rewrite, so it must be a variable or a
+/// literal; anything else is an error.
+pub fn expand_chained_comparisons(statements: Vec<Spanned<Statement>>) -> Result<Vec<Spanned<Statement>>, CompilationError> {
+    statements.into_iter()
+        .map(|Spanned { node, line, file }| {
+            // The line of a statement inlined from an import counts in that file
+            let node = chain_statement(node, line).map_err(|error| match (error, &file) {
+                (CompilationError::ValidationError { code, message, suggestion, .. }, Some(file)) => {
+                    CompilationError::ValidationError {
+                        code,
+                        message,
+                        line: None,
+                        context: Some(format!("in {}, line {}", file, line)),
+                        suggestion,
+                    }
+                }
+                (error, _) => error,
+            })?;
+            Ok(Spanned { node, line, file })
+        })
+        .collect()
+}
+
+/// Expand the chained comparisons in a statement, including inside its blocks
+fn chain_statement(stmt: Statement, line: usize) -> Result<Statement, CompilationError> {
+    let chain_all = |exprs: Vec<Expression>| exprs.into_iter().map(|expr| chain_expression(expr, line)).collect::<Result<_, _>>();
+    Ok(match stmt {
+        Statement::Print(exprs) => Statement::Print(chain_all(exprs)?),
+        Statement::PrintInline(exprs) => Statement::PrintInline(chain_all(exprs)?),
+        Statement::PrintJoined(exprs, sep, end) => {
+            Statement::PrintJoined(chain_all(exprs)?, chain_expression(sep, line)?, chain_expression(end, line)?)
+        }
+        Statement::Const(name, expr) => Statement::Const(name, chain_expression(expr, line)?),
+        Statement::MultiConst(declarators) => Statement::MultiConst(
+            declarators.into_iter()
+                .map(|(name, expr)| Ok((name, chain_expression(expr, line)?)))
+                .collect::<Result<_, CompilationError>>()?
+        ),
+        Statement::Let(name, expr) => Statement::Let(name, chain_expression(expr, line)?),
+        Statement::Assign(name, expr) => Statement::Assign(name, chain_expression(expr, line)?),
+        Statement::If(cond, then_block, else_block) => Statement::If(
+            chain_expression(cond, line)?,
+            expand_chained_comparisons(then_block)?,
+            else_block.map(expand_chained_comparisons).transpose()?
+        ),
+        Statement::While(cond, block) => Statement::While(chain_expression(cond, line)?, expand_chained_comparisons(block)?),
+        Statement::For(init, cond, update, block) => Statement::For(
+            init.map(|init| chain_statement(*init, line).map(Box::new)).transpose()?,
+            cond.map(|cond| chain_expression(cond, line)).transpose()?,
+            update.map(|update| chain_statement(*update, line).map(Box::new)).transpose()?,
+            expand_chained_comparisons(block)?
+        ),
+        Statement::ForRange(name, start, end, block) => Statement::ForRange(
+            name,
+            chain_expression(start, line)?,
+            chain_expression(end, line)?,
+            expand_chained_comparisons(block)?
+        ),
+        Statement::Labeled(label, body) => Statement::Labeled(label, Box::new(chain_statement(*body, line)?)),
+        Statement::Expr(expr) => Statement::Expr(chain_expression(expr, line)?),
+        Statement::Assert(cond, source) => Statement::Assert(chain_expression(cond, line)?, source),
+        Statement::Return(value) => Statement::Return(value.map(|value| chain_expression(value, line)).transpose()?),
+        other => other,
+    })
+}
+
+/// Expand a chained comparison, and any inside its operands
+fn chain_expression(expr: Expression, line: usize) -> Result<Expression, CompilationError> {
+    let chain = |expr: Box<Expression>| chain_expression(*expr, line).map(Box::new);
+    Ok(match expr {
+        Expression::BinaryOp(left, op, right) if is_comparison(&left) && COMPARISON_OPERATORS.contains(&op.as_str()) => {
+            // Operators associate to the left, so `a < b < c` arrives as `(a < b) < c`
+            let mut operands = vec![chain(right)?];
+            let mut operators = vec![op];
+            let mut rest = *left;
+            loop {
+                match rest {
+                    Expression::BinaryOp(left, op, right) if COMPARISON_OPERATORS.contains(&op.as_str()) => {
+                        operands.push(chain(right)?);
+                        operators.push(op);
+                        rest = *left;
+                    }
+                    first => {
+                        operands.push(chain(Box::new(first))?);
+                        break;
+                    }
+                }
+            }
+            operands.reverse();
+            operators.reverse();
+            
+            for middle in &operands[1..operands.len() - 1] {
+                if !matches!(**middle, Expression::Identifier(_) | Expression::Number(_) | Expression::Float(_) | Expression::String(_)) {
+                    return Err(CompilationError::ValidationError {
+                        code: error_codes::INVALID_EXPRESSION,
+                        message: "Validation failed: the middle of a chained comparison must be a variable or a literal".to_string(),
+                        line: Some(line),
+                        context: None,
+                        suggestion: Some("Store the middle value in a variable first, e.g. rrr v = s.trim(); \"a\" < v < \"z\"".to_string()),
+                    });
+                }
+            }
+            
+            let mut comparisons = operators.into_iter().enumerate()
+                .map(|(i, op)| Expression::BinaryOp(operands[i].clone(), op, operands[i + 1].clone()));
+            let first = comparisons.next().unwrap_or(Expression::Number(0));
+            comparisons.fold(first, |all, next| Expression::BinaryOp(Box::new(all), "&&".to_string(), Box::new(next)))
+        }
+        Expression::BinaryOp(left, op, right) => Expression::BinaryOp(chain(left)?, op, chain(right)?),
+        Expression::Grouping(inner) => Expression::Grouping(chain(inner)?),
+        Expression::Member(object, field) => Expression::Member(chain(object)?, field),
+        Expression::OptionalMember(object, field) => Expression::OptionalMember(chain(object)?, field),
+        Expression::MethodCall(object, method, args) => Expression::MethodCall(
+            chain(object)?,
+            method,
+            args.into_iter().map(|arg| chain_expression(arg, line)).collect::<Result<_, _>>()?
+        ),
+        Expression::Object(entries) => Expression::Object(
+            entries.into_iter()
+                .map(|(key, value)| Ok((key, chain_expression(value, line)?)))
+                .collect::<Result<_, CompilationError>>()?
+        ),
+        Expression::Index(object, index) => Expression::Index(chain(object)?, chain(index)?),
+        Expression::IndexFromEnd(object, n) => Expression::IndexFromEnd(chain(object)?, n),
+        Expression::Slice(object, start, end) => Expression::Slice(chain(object)?, chain(start)?, chain(end)?),
+        Expression::Conditional(cond, then_branch, else_branch) => {
+            Expression::Conditional(chain(cond)?, chain(then_branch)?, chain(else_branch)?)
+        }
+        Expression::Lambda(params, body) => Expression::Lambda(params, expand_chained_comparisons(body)?),
+        Expression::Call(callee, args) => Expression::Call(
+            chain(callee)?,
+            args.into_iter().map(|arg| chain_expression(arg, line)).collect::<Result<_, _>>()?
+        ),
+        other => other,
+    })
+}
+
+/// Check whether an expression is an ungrouped comparison
+fn is_comparison(expr: &Expression) -> bool {
+    matches!(expr, Expression::BinaryOp(_, op, _) if COMPARISON_OPERATORS.contains(&op.as_str()))
+}
+
+/// Folding state for `fold_constants`
+struct Folder {
+    /// Values of `rrr` constants known at compile time, innermost scope last. `None` marks a
+    /// variable whose value isn't known, hiding any outer constant of the same name
+    scopes: Vec<HashMap<String, Option<i32>>>,
+    /// Annotate folded expressions that read a constant with the expression they replace
+    keep_names: bool,
+}
+
+impl Folder {
+    /// Create a folder with an empty top-level scope
+    fn new(keep_names: bool) -> Self {
+        Self { scopes: vec![HashMap::new()], keep_names }
+    }
+    
+    /// Record a variable declared in the current scope, with its value if it is a known constant
+    fn declare(&mut self, name: &str, value: Option<i32>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+    
+    /// Get the compile-time value of a number literal or a constant in scope
+    fn value(&self, expr: &Expression) -> Option<i32> {
+        match expr {
+            Expression::Number(n) => Some(*n),
+            Expression::Identifier(name) => self.scopes.iter().rev().find_map(|scope| scope.get(name)).copied().flatten(),
+            _ => None,
+        }
+    }
+    
+    /// Run `f` in a new scope
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+    
+    /// Fold the statements of a block in the current scope
+    fn statements(&mut self, statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+        statements.into_iter().map(|stmt| stmt.map(|node| self.statement(node))).collect()
+    }
+    
+    /// Fold the statements of a block in a scope of its own
+    fn block(&mut self, statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+        self.scoped(|folder| folder.statements(statements))
+    }
+    
+    /// Fold the expressions of a statement, including inside its blocks
+    fn statement(&mut self, stmt: Statement) -> Statement {
+        match stmt {
+            Statement::Print(exprs) => Statement::Print(exprs.into_iter().map(|e| self.root(e)).collect()),
+            Statement::PrintInline(exprs) => Statement::PrintInline(exprs.into_iter().map(|e| self.root(e)).collect()),
+            Statement::PrintJoined(exprs, sep, end) => Statement::PrintJoined(
+                exprs.into_iter().map(|e| self.root(e)).collect(),
+                self.root(sep),
+                self.root(end),
+            ),
+            Statement::Const(name, expr) => {
+                let expr = self.root(expr);
+                self.declare(&name, self.value(unannotated(&expr)));
+                Statement::Const(name, expr)
+            }
+            Statement::MultiConst(declarators) => Statement::MultiConst(
+                declarators.into_iter().map(|(name, expr)| {
+                    let expr = self.root(expr);
+                    self.declare(&name, self.value(unannotated(&expr)));
+                    (name, expr)
+                }).collect()
+            ),
+            Statement::Let(name, expr) => {
+                let expr = self.root(expr);
+                self.declare(&name, None);
+                Statement::Let(name, expr)
+            }
+            Statement::Assign(name, expr) => Statement::Assign(name, self.root(expr)),
+            Statement::If(cond, then_block, else_block) => {
+                let cond = self.root(cond);
+                let then_block = self.block(then_block);
+                Statement::If(cond, then_block, else_block.map(|block| self.block(block)))
+            }
+            Statement::While(cond, block) => {
+                let cond = self.root(cond);
+                Statement::While(cond, self.block(block))
+            }
+            Statement::For(init, cond, update, block) => self.scoped(|folder| {
+                let init = init.map(|init| Box::new(folder.statement(*init)));
+                let cond = cond.map(|cond| folder.root(cond));
+                let update = update.map(|update| Box::new(folder.statement(*update)));
+                Statement::For(init, cond, update, folder.block(block))
+            }),
+            Statement::ForRange(name, start, end, block) => {
+                let start = self.root(start);
+                let end = self.root(end);
+                self.scoped(|folder| {
+                    folder.declare(&name, None);
+                    Statement::ForRange(name, start, end, folder.block(block))
+                })
+            }
+            Statement::Labeled(label, body) => Statement::Labeled(label, Box::new(self.statement(*body))),
+            Statement::Expr(expr) => Statement::Expr(self.root(expr)),
+            Statement::Assert(cond, source) => Statement::Assert(self.root(cond), source),
+            Statement::Return(value) => Statement::Return(value.map(|value| self.root(value))),
+            other => other,
+        }
+    }
+    
+    /// Fold a whole expression, annotating it with the original when a constant was folded away
+    fn root(&self, expr: Expression) -> Expression {
+        let original = self.keep_names.then(|| expr.clone());
+        let mut read_constant = false;
+        let folded = self.expression(expr, &mut read_constant);
+        match original {
+            Some(original) if read_constant => Expression::Folded(Box::new(folded), Box::new(original)),
+            _ => folded,
+        }
+    }
+    
+    /// Fold an expression bottom-up, noting in `read_constant` whether a constant was folded away
+    fn expression(&self, expr: Expression, read_constant: &mut bool) -> Expression {
+        let mut fold = |expr: Box<Expression>| Box::new(self.expression(*expr, read_constant));
+        match expr {
+            Expression::BinaryOp(left, op, right) => {
+                let left = fold(left);
+                let right = fold(right);
+                let folded = match (self.value(&left), op.as_str(), self.value(&right)) {
+                    (Some(a), "+", Some(b)) => a.checked_add(b),
+                    (Some(a), "-", Some(b)) => a.checked_sub(b),
+                    (Some(a), "*", Some(b)) => a.checked_mul(b),
+                    _ => None,
+                };
+                match folded {
+                    Some(n) => {
+                        *read_constant |= [&left, &right].iter().any(|side| matches!(***side, Expression::Identifier(_)));
+                        Expression::Number(n)
+                    }
+                    None => Expression::BinaryOp(left, op, right),
+                }
+            }
+            Expression::Grouping(inner) => match *fold(inner) {
+                // A negative result keeps its parentheses so it can't merge with a neighbouring operator
+                Expression::Number(n) if n >= 0 => Expression::Number(n),
+                inner => Expression::Grouping(Box::new(inner)),
+            },
+            Expression::Member(object, field) => Expression::Member(fold(object), field),
+            Expression::OptionalMember(object, field) => Expression::OptionalMember(fold(object), field),
+            Expression::MethodCall(object, method, args) => {
+                let object = fold(object);
+                let args = args.into_iter().map(|arg| self.expression(arg, read_constant)).collect();
+                Expression::MethodCall(object, method, args)
+            }
+            Expression::Object(entries) => Expression::Object(
+                entries.into_iter().map(|(key, value)| (key, self.expression(value, read_constant))).collect()
+            ),
+            Expression::Index(object, index) => {
+                let object = fold(object);
+                Expression::Index(object, fold(index))
+            }
+            Expression::IndexFromEnd(object, n) => Expression::IndexFromEnd(fold(object), n),
+            Expression::Slice(object, start, end) => {
+                let object = fold(object);
+                let start = fold(start);
+                Expression::Slice(object, start, fold(end))
+            }
+            Expression::Conditional(cond, then_branch, else_branch) => {
+                let cond = fold(cond);
+                let then_branch = fold(then_branch);
+                Expression::Conditional(cond, then_branch, fold(else_branch))
+            }
+            // The body is folded on its own, seeing the constants around it but not its parameters
+            Expression::Lambda(params, body) => {
+                let mut body_folder = Folder { scopes: self.scopes.clone(), keep_names: self.keep_names };
+                body_folder.scoped(|folder| {
+                    for param in &params {
+                        folder.declare(param, None);
+                    }
+                    Expression::Lambda(params, folder.statements(body))
+                })
+            }
+            Expression::Call(callee, args) => {
+                let callee = fold(callee);
+                let args = args.into_iter().map(|arg| self.expression(arg, read_constant)).collect();
+                Expression::Call(callee, args)
+            }
+            other => other,
+        }
+    }
+}
+
+/// Get the folded value of an expression, looking past any annotation
+fn unannotated(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::Folded(value, _) => value,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn print(s: &str) -> Spanned<Statement> {
+        Statement::Print(vec![Expression::String(s.to_string())]).into()
+    }
+
+    #[test]
+    fn test_adjacent_prints_merge() {
+        let statements = vec![print("a"), print("b"), print("c")];
+        let merged = merge_adjacent_prints(statements);
+        
+        assert_eq!(merged, vec![Statement::Print(vec![
+            Expression::String("a".to_string()),
+            Expression::String("b".to_string()),
+            Expression::String("c".to_string()),
+        ])]);
+    }
+
+    #[test]
+    fn test_non_adjacent_prints_stay_separate() {
+        let statements = vec![
+            print("a"),
+            Statement::Let("x".to_string(), Expression::Number(1)).into(),
+            print("b"),
+        ];
+        let merged = merge_adjacent_prints(statements.clone());
+        
+        assert_eq!(merged, statements);
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let binary = |left, op: &str, right| Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right));
+        let statements = vec![Statement::Print(vec![
+            binary(binary(Expression::Number(2), "*", Expression::Number(3)), "+", Expression::Identifier("x".to_string())),
+            binary(Expression::Number(10), "/", Expression::Grouping(Box::new(binary(Expression::Number(5), "-", Expression::Number(5))))),
+            binary(Expression::Number(i32::MAX), "+", Expression::Number(1)),
+        ]).into()];
+        
+        assert_eq!(fold_constants(statements), vec![Statement::Print(vec![
+            binary(Expression::Number(6), "+", Expression::Identifier("x".to_string())),
+            binary(Expression::Number(10), "/", Expression::Number(0)),
+            binary(Expression::Number(i32::MAX), "+", Expression::Number(1)),
+        ])]);
+    }
+
+    #[test]
+    fn test_fold_constants_through_named_constants() {
+        let binary = |left, op: &str, right| Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right));
+        let ident = |name: &str| Expression::Identifier(name.to_string());
+        let area = || binary(ident("width"), "*", ident("height"));
+        let statements = vec![
+            Statement::MultiConst(vec![
+                ("width".to_string(), Expression::Number(5)),
+                ("height".to_string(), binary(Expression::Number(2), "*", Expression::Number(5))),
+            ]).into(),
+            Statement::Const("area".to_string(), area()).into(),
+            Statement::Print(vec![binary(ident("area"), "+", Expression::Number(1)), ident("width")]).into(),
+            // A pushpa of the same name hides the constant inside the loop
+            Statement::ForRange("width".to_string(), Expression::Number(0), Expression::Number(2), vec![
+                Statement::Print(vec![area()]).into(),
+            ]).into(),
+        ];
+        
+        let folded = fold_constants(statements.clone());
+        assert_eq!(folded[1], Statement::Const("area".to_string(), Expression::Number(50)));
+        assert_eq!(folded[2], Statement::Print(vec![Expression::Number(51), ident("width")]));
+        assert_eq!(folded[3], Statement::ForRange("width".to_string(), Expression::Number(0), Expression::Number(2), vec![
+            Statement::Print(vec![area()]).into(),
+        ]));
+        
+        let kept = fold_constants_keeping_names(statements);
+        assert_eq!(kept[0], Statement::MultiConst(vec![
+            ("width".to_string(), Expression::Number(5)),
+            ("height".to_string(), Expression::Number(10)),
+        ]));
+        assert_eq!(kept[1], Statement::Const(
+            "area".to_string(),
+            Expression::Folded(Box::new(Expression::Number(50)), Box::new(area()))
+        ));
+    }
+
+    #[test]
+    fn test_expand_chained_comparisons() {
+        let binary = |left, op: &str, right| Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right));
+        let x = || Expression::Identifier("x".to_string());
+        let y = || Expression::Identifier("y".to_string());
+        let chain = binary(binary(binary(Expression::Number(0), "<", x()), "<=", y()), "<", Expression::Number(10));
+        let grouped = binary(Expression::Grouping(Box::new(binary(x(), "<", y()))), "==", Expression::Number(1));
+        let statements = vec![Statement::Print(vec![chain, grouped.clone()]).into()];
+        
+        assert_eq!(expand_chained_comparisons(statements).unwrap(), vec![Statement::Print(vec![
+            binary(
+                binary(binary(Expression::Number(0), "<", x()), "&&", binary(x(), "<=", y())),
+                "&&",
+                binary(y(), "<", Expression::Number(10))
+            ),
+            grouped,
+        ])]);
+    }
+
+    #[test]
+    fn test_chained_comparison_rejects_computed_middle() {
+        let binary = |left, op: &str, right| Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right));
+        let middle = Expression::MethodCall(Box::new(Expression::Identifier("s".to_string())), "trim".to_string(), vec![]);
+        let chain = binary(binary(Expression::String("a".to_string()), "<", middle), "<", Expression::String("z".to_string()));
+        let statements = vec![Spanned::new(Statement::Print(vec![chain]), 3)];
+        
+        let error = expand_chained_comparisons(statements).unwrap_err();
+        assert_eq!(error.code(), error_codes::INVALID_EXPRESSION);
+        assert!(matches!(error, CompilationError::ValidationError { line: Some(3), .. }));
+    }
+
+    #[test]
+    fn test_empty_prints_stay_separate() {
+        let statements = vec![print("a"), Statement::Print(vec![]).into(), print("b")];
+        let merged = merge_adjacent_prints(statements.clone());
+        
+        assert_eq!(merged, statements);
+    }
+
+    #[test]
+    fn test_mangle_names() {
+        let ident = |name: &str| Expression::Identifier(name.to_string());
+        let statements = vec![
+            Statement::Const("total".to_string(), Expression::Number(1)).into(),
+            Statement::Let("count".to_string(), ident("total")).into(),
+            Statement::Print(vec![ident("total"), ident("count")]).into(),
+        ];
+        
+        assert_eq!(mangle_names(statements), vec![
+            Statement::Const("a".to_string(), Expression::Number(1)),
+            Statement::Let("b".to_string(), ident("a")),
+            Statement::Print(vec![ident("a"), ident("b")]),
+        ]);
+    }
+
+    #[test]
+    fn test_mangle_names_skips_programs_with_raw_js() {
+        let body = vec![Statement::RawJs(" console.log(total); ".to_string()).into()];
+        let statements = vec![
+            Statement::Const("total".to_string(), Expression::Number(3)).into(),
+            Statement::Const("show".to_string(), Expression::Lambda(vec![], body)).into(),
+        ];
+        
+        // Even a rajini block inside a function keeps every name as written
+        assert_eq!(mangle_names(statements.clone()), statements);
+    }
+
+    #[test]
+    fn test_mangle_names_reuses_closed_scopes_and_skips_keywords() {
+        let declare = |name: &str| -> Spanned<Statement> { Statement::Const(name.to_string(), Expression::Number(0)).into() };
+        let mut statements: Vec<Spanned<Statement>> = (0..4).map(|i| declare(&format!("v{}", i))).collect();
+        statements.push(Statement::While(Expression::Number(1), vec![declare("inner")]).into());
+        statements.push(declare("after"));
+        
+        let names: Vec<String> = mangle_names(statements).into_iter().map(|stmt| match stmt.node {
+            Statement::Const(name, _) => name,
+            Statement::While(_, block) => match &block[0].node {
+                Statement::Const(name, _) => format!("inner {}", name),
+                _ => panic!("Expected const statement"),
+            },
+            _ => panic!("Expected const or while statement"),
+        }).collect();
+        assert_eq!(names, vec!["a", "b", "c", "d", "inner e", "e"]);
+        
+        // "do" and "if" are keywords, so they are never handed out
+        let many: Vec<Spanned<Statement>> = (0..120).map(|i| declare(&format!("v{}", i))).collect();
+        let mangled: Vec<String> = mangle_names(many).into_iter().filter_map(|stmt| match stmt.node {
+            Statement::Const(name, _) => Some(name),
+            _ => None,
+        }).collect();
+        assert!(mangled.contains(&"dn".to_string()));
+        assert!(!mangled.contains(&"do".to_string()));
+        assert!(!mangled.contains(&"if".to_string()));
+    }
+
+    #[test]
+    fn test_prints_merge_inside_blocks() {
+        let statements = vec![Statement::While(Expression::Number(1), vec![print("a"), print("b")]).into()];
+        let merged = merge_adjacent_prints(statements);
+        
+        if let Statement::While(_, block) = &merged[0].node {
+            assert_eq!(block.len(), 1);
+        } else {
+            panic!("Expected while statement");
+        }
+    }
+}
@@ -1,6 +1,13 @@
-use crate::ast::{Statement, Expression};
+// pest errors are returned by value throughout the parser's public API
+#![allow(clippy::result_large_err)]
+
+use crate::ast::{precedence, Statement, Expression, Spanned};
+use crate::color;
+use crate::lexer::{raw_block_len, raw_js_len};
+use crate::error_codes;
 use pest::Parser;
 use pest_derive::Parser;
+use std::collections::BTreeSet;
 
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
@@ -16,34 +23,235 @@ pub struct ParseErrorInfo {
     pub suggestion: Option<String>,
 }
 
+/// Default limit on nesting, kept low enough that pest's recursive descent can't overflow the stack
+///
+/// Each operator in a chain like `1 + 1 + 1` nests the tree one level deeper, so it counts as a level.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 100;
+
 /// Parse a complete TFI program into a vector of statements
-pub fn parse_program(input: &str) -> Result<Vec<Statement>, pest::error::Error<Rule>> {
-    let pairs = MyLanguageParser::parse(Rule::program, input).map_err(|e| {
+///
+/// Safe to call on untrusted input: every malformed program, including out-of-range number
+/// literals, runaway nesting and overlong operator or member chains, is reported as an error
+/// rather than a panic or a stack overflow.
+pub fn parse_program(input: &str) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    parse_program_with_nesting_limit(input, false, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// Parse a complete TFI program, keeping `//` comments as `Statement::Comment` nodes
+pub fn parse_program_with_comments(input: &str) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    parse_program_with_nesting_limit(input, true, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// A parsed program together with the layout a TFI formatter should keep
+///
+/// Comments are kept as `Statement::Comment` nodes at every depth, on the line they were
+/// written, so a comment sharing a line with a statement can stay at the end of that line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormattedProgram {
+    pub statements: Vec<Spanned<Statement>>,
+    /// Non-blank source lines that directly follow one or more blank lines
+    pub blank_line_before: BTreeSet<usize>,
+}
+
+impl FormattedProgram {
+    /// Whether the statement starting on `line` was set apart by a blank line before it
+    pub fn has_blank_line_before(&self, line: usize) -> bool {
+        self.blank_line_before.contains(&line)
+    }
+}
+
+/// Parse a complete TFI program for reformatting, keeping comments and blank lines
+pub fn parse_formatted(input: &str) -> Result<FormattedProgram, pest::error::Error<Rule>> {
+    let statements = parse_program_with_comments(input)?;
+    let lines: Vec<&str> = input.lines().collect();
+    let blank_line_before = lines.windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0].trim().is_empty() && !pair[1].trim().is_empty())
+        // The window starting at index i ends on 1-based line i + 2
+        .map(|(i, _)| i + 2)
+        .collect();
+    
+    Ok(FormattedProgram { statements, blank_line_before })
+}
+
+/// Parse a complete TFI program, rejecting bracket nesting and operator chains deeper than `max_depth`
+pub fn parse_program_with_nesting_limit(
+    input: &str,
+    keep_comments: bool,
+    max_depth: usize
+) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    parse_program_reporting(input, keep_comments, max_depth, true)
+}
+
+/// Parse like `parse_program_with_nesting_limit`, returning errors without printing them to stderr
+pub fn parse_program_silent(
+    input: &str,
+    keep_comments: bool,
+    max_depth: usize
+) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    parse_program_reporting(input, keep_comments, max_depth, false)
+}
+
+/// Parse a program, printing a detailed report of any error to stderr when `report` is set
+fn parse_program_reporting(
+    input: &str,
+    keep_comments: bool,
+    max_depth: usize,
+    report: bool
+) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    check_nesting_depth(input, max_depth, report)?;
+    
+    let statements = parse_statements(input, report)?;
+    if keep_comments {
+        Ok(statements)
+    } else {
+        Ok(strip_comments(statements))
+    }
+}
+
+/// Reject input nested deeper than `max_depth` before handing it to pest
+///
+/// Every open (), {} or [] counts as a level, and so does every operator in the chains still
+/// open around a position. Member accesses like `.name` and chained calls or indexes like `f()()`
+/// each nest one level too. A `;` or `,` ends the chain at its level.
+fn check_nesting_depth(input: &str, max_depth: usize, report: bool) -> Result<(), pest::error::Error<Rule>> {
+    let mut depth = 0usize;
+    // Operators in the current chain at each open bracket, outermost first
+    let mut chains = vec![0usize];
+    let mut operators = 0usize;
+    let mut after_operator = false;
+    let mut previous = ' ';
+    let mut in_string = false;
+    let mut chars = input.char_indices().peekable();
+    
+    while let Some((pos, c)) = chars.next() {
+        // `>=`, `~/` and the like are one operator
+        let continues_operator = after_operator;
+        after_operator = false;
+        let too_deep = match c {
+            '"' => {
+                in_string = !in_string;
+                false
+            }
+            '\\' if in_string => {
+                chars.next();
+                false
+            }
+            _ if in_string => false,
+            // Brackets and operators in raw print text are not code
+            'b' if let Some(len) = raw_block_len(&input[pos..]) => {
+                while chars.next_if(|(i, _)| *i < pos + len).is_some() {}
+                false
+            }
+            // Nor are those in raw JavaScript
+            'r' if let Some(len) = raw_js_len(&input[pos..]) => {
+                while chars.next_if(|(i, _)| *i < pos + len).is_some() {}
+                false
+            }
+            '/' if chars.peek().map(|(_, next)| *next) == Some('/') => {
+                // Skip line comments
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                false
+            }
+            '(' | '{' | '[' => {
+                if matches!(previous, ')' | ']') {
+                    add_operator(&mut chains, &mut operators);
+                }
+                depth += 1;
+                chains.push(0);
+                depth + operators > max_depth
+            }
+            ')' | '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                if chains.len() > 1 {
+                    operators -= chains.pop().unwrap_or(0);
+                }
+                false
+            }
+            ';' | ',' => {
+                if let Some(chain) = chains.last_mut() {
+                    operators -= *chain;
+                    *chain = 0;
+                }
+                false
+            }
+            '+' | '-' | '*' | '/' | '~' | '<' | '>' | '=' | '!' | '?' => {
+                after_operator = true;
+                if continues_operator {
+                    false
+                } else {
+                    add_operator(&mut chains, &mut operators);
+                    depth + operators > max_depth
+                }
+            }
+            // Not the point in a float or the `..` of a range
+            '.' if chars.peek().is_some_and(|(_, next)| next.is_ascii_alphabetic()) => {
+                add_operator(&mut chains, &mut operators);
+                depth + operators > max_depth
+            }
+            _ => false,
+        };
+        if !c.is_whitespace() {
+            previous = c;
+        }
+        
+        if too_deep {
+            let error_info = ParseErrorInfo {
+                message: format!("Expression too deeply nested (limit is {} levels)", max_depth),
+                line: input[..pos].matches('\n').count() + 1,
+                column: pos - input[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0) + 1,
+                source_line: input[..pos].lines().last().unwrap_or("").to_string(),
+                suggestion: Some("Split the expression into smaller parts using intermediate variables".to_string()),
+            };
+            if report {
+                eprintln!("{}", color::paint_error(&format_parse_error(&error_info), color::enabled()));
+            }
+            return Err(pest::error::Error::new_from_pos(
+                pest::error::ErrorVariant::CustomError { message: error_info.message },
+                pest::Position::new(input, pos).unwrap_or_else(|| pest::Position::from_start(input)),
+            ));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Count one more operator in the innermost open chain
+fn add_operator(chains: &mut [usize], operators: &mut usize) {
+    *operators += 1;
+    if let Some(chain) = chains.last_mut() {
+        *chain += 1;
+    }
+}
+
+/// Run the pest parser and build statements, keeping comments
+fn parse_statements(input: &str, report: bool) -> Result<Vec<Spanned<Statement>>, pest::error::Error<Rule>> {
+    let pairs = MyLanguageParser::parse(Rule::program, input).inspect_err(|e| {
         // Print enhanced error message
-        let error_info = create_error_info_from_pest(&e, input);
-        eprintln!("{}", format_parse_error(&error_info));
-        e
+        if report {
+            let error_info = create_error_info_from_pest(e, input);
+            eprintln!("{}", color::paint_error(&format_parse_error(&error_info), color::enabled()));
+        }
     })?;
     
     let mut statements = vec![];
     for pair in pairs {
-        match pair.as_rule() {
-            Rule::program => {
-                for inner_pair in pair.into_inner() {
-                    match inner_pair.as_rule() {
-                        Rule::statement => {
-                            let stmt = parse_statement(inner_pair)?;
-                            statements.push(stmt);
-                        }
-                        _ => {}
-                    }
+        if pair.as_rule() == Rule::program {
+            for inner_pair in pair.into_inner() {
+                match inner_pair.as_rule() {
+                    Rule::statement => push_statement(&mut statements, inner_pair)?,
+                    Rule::COMMENT => statements.push(parse_comment(inner_pair)),
+                    _ => {}
                 }
             }
-            _ => {}
         }
     }
     
-    if statements.is_empty() {
+    if statements.iter().all(|stmt| matches!(stmt.node, Statement::Comment(_))) {
         let error_info = ParseErrorInfo {
             message: "No valid statements found. Check your syntax.".to_string(),
             line: 1,
@@ -51,12 +259,18 @@ pub fn parse_program(input: &str) -> Result<Vec<Statement>, pest::error::Error<R
             source_line: input.lines().next().unwrap_or("").to_string(),
             suggestion: Some("Make sure your TFI file contains valid statements like 'bahubali(\"Hello\");' or 'rrr x = 10;'".to_string()),
         };
-        eprintln!("{}", format_parse_error(&error_info));
+        if report {
+            eprintln!("{}", color::paint_error(&format_parse_error(&error_info), color::enabled()));
+        }
         return Err(pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { 
                 message: error_info.message
             },
-            pest::Span::new(input, 0, input.len()).unwrap(),
+            // The whole input is always a valid span; the fallback only avoids an unwrap
+            pest::Span::new(input, 0, input.len()).unwrap_or_else(|| {
+                let start = pest::Position::from_start(input);
+                start.span(&start)
+            }),
         ));
     }
     
@@ -75,21 +289,21 @@ fn create_error_info_from_pest(error: &pest::error::Error<Rule>, source: &str) -
     let mut source_line = source.lines().next().unwrap_or("").to_string();
     
     // Parse line:column from pest error format like " --> 2:1"
-    if lines.len() >= 1 {
+    if !lines.is_empty() {
         let error_line = lines[0].trim();
         if error_line.starts_with("-->") {
             let parts: Vec<&str> = error_line.split(':').collect();
             if parts.len() >= 2 {
-                if let Some(line_str) = parts[0].split_whitespace().last() {
-                    if let Ok(l) = line_str.parse::<usize>() {
-                        line = l;
-                        source_line = source.lines().nth(line - 1).unwrap_or("").to_string();
-                    }
+                if let Some(line_str) = parts[0].split_whitespace().last()
+                    && let Ok(l) = line_str.parse::<usize>()
+                {
+                    line = l;
+                    source_line = source.lines().nth(line.saturating_sub(1)).unwrap_or("").to_string();
                 }
-                if let Some(col_str) = parts[1].split_whitespace().next() {
-                    if let Ok(c) = col_str.parse::<usize>() {
-                        column = c;
-                    }
+                if let Some(col_str) = parts[1].split_whitespace().next()
+                    && let Ok(c) = col_str.parse::<usize>()
+                {
+                    column = c;
                 }
             }
         }
@@ -122,7 +336,7 @@ fn generate_generic_suggestion(source_line: &str) -> Option<String> {
     if source_line.trim().is_empty() {
         Some("Add a valid TFI statement like 'bahubali(\"Hello\");'".to_string())
     } else if source_line.contains('=') && !source_line.contains("rrr") && !source_line.contains("pushpa") {
-        Some("Variable assignments need 'rrr' (const) or 'pushpa' (let) keyword".to_string())
+        Some("New variables need 'rrr' (const) or 'pushpa' (let); assignments look like 'x = value;'".to_string())
     } else if source_line.contains("bahubali") && !source_line.contains('(') {
         Some("bahubali statements need parentheses: bahubali(\"message\");".to_string())
     } else if source_line.contains("magadheera") && !source_line.contains('(') {
@@ -139,10 +353,10 @@ fn generate_generic_suggestion(source_line: &str) -> Option<String> {
 /// Format parse error with nice formatting
 fn format_parse_error(error_info: &ParseErrorInfo) -> String {
     let mut output = String::new();
-    output.push_str(&format!("❌ Parse Error at line {}, column {}\n", error_info.line, error_info.column));
+    output.push_str(&format!("❌ Parse Error [{}] at line {}, column {}\n", error_codes::SYNTAX_ERROR, error_info.line, error_info.column));
     output.push_str(&format!("   {}\n", error_info.message));
     output.push_str(&format!("   {}\n", error_info.source_line));
-    output.push_str(&format!("   {}^\n", " ".repeat(error_info.column - 1)));
+    output.push_str(&format!("   {}^\n", " ".repeat(error_info.column.saturating_sub(1))));
     
     if let Some(ref suggestion) = error_info.suggestion {
         output.push_str(&format!("   💡 Suggestion: {}\n", suggestion));
@@ -151,11 +365,93 @@ fn format_parse_error(error_info: &ParseErrorInfo) -> String {
     output
 }
 
-/// Parse a single statement from a pest pair
-fn parse_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+/// Get the next child pair, skipping comments captured between tokens
+fn next_code_pair<'i>(inner: &mut pest::iterators::Pairs<'i, Rule>) -> Option<pest::iterators::Pair<'i, Rule>> {
+    inner.find(|pair| pair.as_rule() != Rule::COMMENT)
+}
+
+/// Get the 1-based line a pair starts on
+fn line_of(pair: &pest::iterators::Pair<Rule>) -> usize {
+    pair.as_span().start_pos().line_col().0
+}
+
+/// Parse a `// text` comment into a comment statement
+fn parse_comment(pair: pest::iterators::Pair<Rule>) -> Spanned<Statement> {
+    let line = line_of(&pair);
+    Spanned::new(Statement::Comment(pair.as_str()[2..].trim().to_string()), line)
+}
+
+/// Remove comment statements from a program, including inside blocks
+fn strip_comments(statements: Vec<Spanned<Statement>>) -> Vec<Spanned<Statement>> {
+    statements.into_iter()
+        .filter(|stmt| !matches!(stmt.node, Statement::Comment(_)))
+        .map(|stmt| stmt.map(|stmt| match stmt {
+            Statement::If(cond, then_block, else_block) => {
+                Statement::If(cond, strip_comments(then_block), else_block.map(strip_comments))
+            }
+            Statement::While(cond, block) => Statement::While(cond, strip_comments(block)),
+            Statement::For(init, cond, update, block) => {
+                Statement::For(init, cond, update, strip_comments(block))
+            }
+            Statement::ForRange(name, start, end, block) => {
+                Statement::ForRange(name, start, end, strip_comments(block))
+            }
+            other => other,
+        }))
+        .collect()
+}
+
+/// Parse a single statement from a pest pair, recording the line it starts on
+fn parse_statement(pair: pest::iterators::Pair<Rule>) -> Result<Spanned<Statement>, pest::error::Error<Rule>> {
+    let line = line_of(&pair);
+    parse_statement_node(pair).map(|stmt| Spanned::new(stmt, line))
+}
+
+/// Parse a `statement` pair onto the end of a block
+///
+/// An if statement without an else consumes the comments after its closing brace while it looks
+/// for `karthikeya`, so those comments are pushed after it, where they were written.
+fn push_statement(
+    statements: &mut Vec<Spanned<Statement>>,
+    pair: pest::iterators::Pair<Rule>
+) -> Result<(), pest::error::Error<Rule>> {
+    let trailing = match pair.clone().into_inner().find(|inner| inner.as_rule() == Rule::if_statement) {
+        Some(if_pair) if !if_pair.clone().into_inner().any(|inner| inner.as_rule() == Rule::else_block) => {
+            let then_end = then_block_end(&if_pair);
+            if_pair.into_inner()
+                .filter(|inner| inner.as_rule() == Rule::COMMENT && inner.as_span().start() >= then_end)
+                .map(parse_comment)
+                .collect()
+        }
+        _ => vec![],
+    };
+    
+    statements.push(parse_statement(pair)?);
+    statements.extend(trailing);
+    Ok(())
+}
+
+/// Byte offset just past the `}` closing an if statement's then-block
+fn then_block_end(pair: &pest::iterators::Pair<Rule>) -> usize {
+    let span = pair.as_span();
+    let text = pair.as_str();
+    // Between the inner pairs there is only whitespace and punctuation, so the first `}` found
+    // there is the then-block's
+    let mut gap_start = span.start();
+    for inner in pair.clone().into_inner() {
+        if let Some(brace) = text[gap_start - span.start()..inner.as_span().start() - span.start()].find('}') {
+            return gap_start + brace + 1;
+        }
+        gap_start = inner.as_span().end();
+    }
+    span.end()
+}
+
+/// Parse the statement inside a `statement` pair
+fn parse_statement_node(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
     let span = pair.as_span();
     let mut inner = pair.into_inner();
-    let inner_pair = inner.next().ok_or_else(|| {
+    let inner_pair = next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected statement content".to_string() },
             span,
@@ -163,12 +459,39 @@ fn parse_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest:
     })?;
     
     match inner_pair.as_rule() {
+        Rule::import_statement => parse_import_statement(inner_pair),
+        Rule::raw_print_statement => Ok(parse_raw_print_statement(inner_pair)),
+        Rule::raw_js_statement => {
+            let code = inner_pair.into_inner().next().map(|pair| pair.as_str()).unwrap_or_default();
+            Ok(Statement::RawJs(code.to_string()))
+        }
         Rule::print_statement => parse_print_statement(inner_pair),
+        Rule::print_inline_statement => parse_print_inline_statement(inner_pair),
+        Rule::clear_statement => Ok(Statement::Clear),
         Rule::const_statement => parse_const_statement(inner_pair),
         Rule::let_statement => parse_let_statement(inner_pair),
+        Rule::assign_statement => parse_assign_statement(inner_pair),
+        Rule::increment_statement => parse_increment_statement(inner_pair),
+        Rule::break_statement => Ok(parse_break_statement(inner_pair)),
+        Rule::assert_statement => parse_assert_statement(inner_pair),
+        Rule::call_statement => {
+            let mut inner = inner_pair.into_inner().filter(|pair| pair.as_rule() != Rule::COMMENT);
+            let callee = Expression::Identifier(inner.next().map(|pair| pair.as_str().to_string()).unwrap_or_default());
+            let args = inner.next()
+                .map(|call| call.into_inner().filter(|pair| pair.as_rule() != Rule::COMMENT).map(parse_expression).collect())
+                .transpose()?
+                .unwrap_or_default();
+            Ok(Statement::Expr(Expression::Call(Box::new(callee), args)))
+        }
+        Rule::return_statement => {
+            let value = next_code_pair(&mut inner_pair.into_inner()).map(parse_expression).transpose()?;
+            Ok(Statement::Return(value))
+        }
         Rule::if_statement => parse_if_statement(inner_pair),
+        Rule::labeled_statement => parse_labeled_statement(inner_pair),
         Rule::while_statement => parse_while_statement(inner_pair),
         Rule::for_statement => parse_for_statement(inner_pair),
+        Rule::for_range_statement => parse_for_range_statement(inner_pair),
         _ => Err(pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: format!("Unknown statement type: {:?}", inner_pair.as_rule()) },
             inner_pair.as_span(),
@@ -176,48 +499,139 @@ fn parse_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest:
     }
 }
 
+/// Parse an import statement: import "file.tfi";
+fn parse_import_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    match parse_term(pair)? {
+        Expression::String(path) => Ok(Statement::Import(path)),
+        _ => Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected a file path after import".to_string() },
+            span,
+        ))
+    }
+}
+
+/// Parse a raw print block: bahubali_block { ... }
+///
+/// The text starts on the line after the opening brace and ends before the closing one.
+fn parse_raw_print_statement(pair: pest::iterators::Pair<Rule>) -> Statement {
+    let raw = pair.into_inner().next().map(|pair| pair.as_str()).unwrap_or_default();
+    let text = raw.split_once('\n').map_or("", |(_, text)| text);
+    Statement::RawPrint(text.replace("\r\n", "\n"))
+}
+
 /// Parse a print statement: bahubali(expr1, expr2, ...)
+///
+/// An empty `bahubali()` is accepted here; the validator rejects it unless empty prints are allowed.
 fn parse_print_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
-    let span = pair.as_span();
-    let inner = pair.into_inner();
-    let mut expressions = vec![];
+    let error = |message: String, span| pest::error::Error::new_from_span(
+        pest::error::ErrorVariant::CustomError { message },
+        span,
+    );
     
-    for pair in inner {
-        if pair.as_rule() == Rule::expression {
-            expressions.push(parse_expression(pair)?);
+    let mut expressions = vec![];
+    let mut sep = None;
+    let mut end = None;
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::expression if sep.is_some() || end.is_some() => {
+                return Err(error("bahubali values must come before sep= and end=".to_string(), pair.as_span()));
+            }
+            Rule::expression => expressions.push(parse_expression(pair)?),
+            Rule::print_keyword_arg => {
+                let span = pair.as_span();
+                let mut inner = pair.into_inner();
+                let name = next_code_pair(&mut inner).map(|name| name.as_str()).unwrap_or_default();
+                let value = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
+                    error(format!("Expected a value for {}=", name), span)
+                })?)?;
+                let slot = match name {
+                    "sep" => &mut sep,
+                    "end" => &mut end,
+                    _ => return Err(error(format!("Unknown bahubali argument '{}'; expected sep or end", name), span)),
+                };
+                if slot.replace(value).is_some() {
+                    return Err(error(format!("bahubali argument '{}' is given more than once", name), span));
+                }
+            }
+            _ => {}
         }
     }
     
+    if sep.is_none() && end.is_none() {
+        return Ok(Statement::Print(expressions));
+    }
+    Ok(Statement::PrintJoined(
+        expressions,
+        sep.unwrap_or_else(|| Expression::String(" ".to_string())),
+        end.unwrap_or_else(|| Expression::String("\n".to_string())),
+    ))
+}
+
+/// Parse an inline print statement: salaar(expr1, expr2, ...)
+fn parse_print_inline_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let expressions = parse_print_arguments(pair)?;
+    
     if expressions.is_empty() {
         return Err(pest::error::Error::new_from_span(
-            pest::error::ErrorVariant::CustomError { message: "bahubali() requires at least one argument".to_string() },
+            pest::error::ErrorVariant::CustomError { message: "salaar() requires at least one argument".to_string() },
             span,
         ));
     }
     
-    Ok(Statement::Print(expressions))
+    Ok(Statement::PrintInline(expressions))
 }
 
-/// Parse a const declaration: rrr name = value
+/// Parse the arguments of a print-style statement
+fn parse_print_arguments(pair: pest::iterators::Pair<Rule>) -> Result<Vec<Expression>, pest::error::Error<Rule>> {
+    let mut expressions = vec![];
+    for pair in pair.into_inner() {
+        if pair.as_rule() == Rule::expression {
+            expressions.push(parse_expression(pair)?);
+        }
+    }
+    
+    Ok(expressions)
+}
+
+/// Parse a const declaration: rrr name = value, or rrr a = 1, b = 2
 fn parse_const_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let mut declarators = vec![];
+    for pair in pair.into_inner() {
+        if pair.as_rule() == Rule::declarator {
+            declarators.push(parse_declarator(pair)?);
+        }
+    }
+    
+    if declarators.len() == 1 {
+        let (ident, expr) = declarators.remove(0);
+        Ok(Statement::Const(ident, expr))
+    } else {
+        Ok(Statement::MultiConst(declarators))
+    }
+}
+
+/// Parse a single name = value declarator of an rrr declaration
+fn parse_declarator(pair: pest::iterators::Pair<Rule>) -> Result<(String, Expression), pest::error::Error<Rule>> {
     let span = pair.as_span();
     let mut inner = pair.into_inner();
     
-    let ident = inner.next().ok_or_else(|| {
+    let ident = next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected identifier in rrr declaration".to_string() },
             span,
         )
     })?.as_str().to_string();
     
-    let expr = parse_expression(inner.next().ok_or_else(|| {
+    let expr = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected expression in rrr declaration".to_string() },
             span,
         )
     })?)?;
     
-    Ok(Statement::Const(ident, expr))
+    Ok((ident, expr))
 }
 
 /// Parse a let declaration: pushpa name = value
@@ -225,14 +639,14 @@ fn parse_let_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, p
     let span = pair.as_span();
     let mut inner = pair.into_inner();
     
-    let ident = inner.next().ok_or_else(|| {
+    let ident = next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected identifier in pushpa declaration".to_string() },
             span,
         )
     })?.as_str().to_string();
     
-    let expr = parse_expression(inner.next().ok_or_else(|| {
+    let expr = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected expression in pushpa declaration".to_string() },
             span,
@@ -242,31 +656,148 @@ fn parse_let_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, p
     Ok(Statement::Let(ident, expr))
 }
 
+/// Parse an assignment: name = value, or name op= value
+fn parse_assign_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    
+    let ident = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected identifier in assignment".to_string() },
+            span,
+        )
+    })?.as_str().to_string();
+    
+    let op = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected '=' in assignment".to_string() },
+            span,
+        )
+    })?.as_str().to_string();
+    
+    let expr = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected expression in assignment".to_string() },
+            span,
+        )
+    })?)?;
+    
+    // Desugar `name op= value` into `name = name op value`
+    let value = match op.strip_suffix('=') {
+        Some(binary_op) if !binary_op.is_empty() => Expression::BinaryOp(
+            Box::new(Expression::Identifier(ident.clone())),
+            binary_op.to_string(),
+            Box::new(expr)
+        ),
+        _ => expr,
+    };
+    
+    Ok(Statement::Assign(ident, value))
+}
+
+/// Parse a break statement: aagipo; or aagipo label;
+fn parse_break_statement(pair: pest::iterators::Pair<Rule>) -> Statement {
+    let label = pair.into_inner()
+        .find(|pair| pair.as_rule() == Rule::ident)
+        .map(|pair| pair.as_str().to_string());
+    Statement::Break(label)
+}
+
+/// Parse an assertion: nammaka(condition);
+fn parse_assert_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let cond_pair = next_code_pair(&mut pair.into_inner()).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected condition in nammaka statement".to_string() },
+            span,
+        )
+    })?;
+    
+    // The failure message quotes the condition as written, on one line
+    let source = cond_pair.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+    Ok(Statement::Assert(parse_condition(cond_pair, "nammaka")?, source))
+}
+
+/// Parse a labeled loop: label: pokiri(...) { ... } or label: eega(...) { ... }
+fn parse_labeled_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    
+    let label = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected label name".to_string() },
+            span,
+        )
+    })?.as_str().to_string();
+    
+    let loop_pair = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected pokiri or eega loop after label".to_string() },
+            span,
+        )
+    })?;
+    let body = match loop_pair.as_rule() {
+        Rule::while_statement => parse_while_statement(loop_pair)?,
+        Rule::for_range_statement => parse_for_range_statement(loop_pair)?,
+        _ => parse_for_statement(loop_pair)?,
+    };
+    
+    Ok(Statement::Labeled(label, Box::new(body)))
+}
+
+/// Parse an increment or decrement: name++ / name--
+fn parse_increment_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    
+    let ident = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected identifier before ++ or --".to_string() },
+            span,
+        )
+    })?.as_str().to_string();
+    
+    let op = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected ++ or --".to_string() },
+            span,
+        )
+    })?;
+    
+    Ok(Statement::Increment(ident, op.as_str() == "--"))
+}
+
 /// Parse an if statement: magadheera(condition) { ... } karthikeya { ... }
 fn parse_if_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
     let span = pair.as_span();
+    let then_end = then_block_end(&pair);
     let mut inner = pair.into_inner();
     
-    let cond = parse_expression(inner.next().ok_or_else(|| {
+    let cond = parse_condition(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected condition in magadheera statement".to_string() },
             span,
         )
-    })?)?;
+    })?, "magadheera")?;
     
     let mut then_statements = vec![];
+    let mut after_then = vec![];
     let mut else_statements = None;
     
     for pair in inner {
         match pair.as_rule() {
-            Rule::statement => then_statements.push(parse_statement(pair)?),
+            Rule::statement => push_statement(&mut then_statements, pair)?,
+            Rule::COMMENT if pair.as_span().start() >= then_end => after_then.push(parse_comment(pair)),
+            Rule::COMMENT => then_statements.push(parse_comment(pair)),
             Rule::WHITESPACE => {}
             Rule::else_block => {
                 // Parse the else block
                 let mut else_block = vec![];
                 for stmt_pair in pair.into_inner() {
-                    if stmt_pair.as_rule() == Rule::statement {
-                        else_block.push(parse_statement(stmt_pair)?);
+                    match stmt_pair.as_rule() {
+                        Rule::statement => push_statement(&mut else_block, stmt_pair)?,
+                        Rule::COMMENT => else_block.push(parse_comment(stmt_pair)),
+                        _ => {}
                     }
                 }
                 else_statements = Some(else_block);
@@ -275,6 +806,12 @@ fn parse_if_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pe
         }
     }
     
+    // Comments between the two blocks open the else block; without one, `push_statement` puts
+    // them after the if statement
+    if let Some(else_block) = &mut else_statements {
+        else_block.splice(0..0, after_then);
+    }
+    
     Ok(Statement::If(cond, then_statements, else_statements))
 }
 
@@ -283,17 +820,19 @@ fn parse_while_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement,
     let span = pair.as_span();
     let mut inner = pair.into_inner();
     
-    let cond = parse_expression(inner.next().ok_or_else(|| {
+    let cond = parse_condition(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected condition in pokiri statement".to_string() },
             span,
         )
-    })?)?;
+    })?, "pokiri")?;
     
     let mut statements = vec![];
     for pair in inner {
-        if pair.as_rule() == Rule::statement {
-            statements.push(parse_statement(pair)?);
+        match pair.as_rule() {
+            Rule::statement => push_statement(&mut statements, pair)?,
+            Rule::COMMENT => statements.push(parse_comment(pair)),
+            _ => {}
         }
     }
     
@@ -302,61 +841,171 @@ fn parse_while_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement,
 
 /// Parse a for loop: eega(init; condition; update) { ... }
 fn parse_for_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner().peekable();
+    let skip_comments = |inner: &mut std::iter::Peekable<pest::iterators::Pairs<'_, Rule>>| {
+        while inner.next_if(|pair| pair.as_rule() == Rule::COMMENT).is_some() {}
+    };
+    let missing = |message: &str, at: pest::Span| pest::error::Error::new_from_span(
+        pest::error::ErrorVariant::CustomError { message: message.to_string() },
+        at,
+    );
+    
+    // Each clause may be empty, as in `eega(;;)`, but both separators must be present
+    skip_comments(&mut inner);
+    let init = match inner.next() {
+        Some(init) if init.as_rule() == Rule::statement => Some(Box::new(parse_statement_node(init)?)),
+        Some(_) => None,
+        None => return Err(missing("eega loop is missing its header", span)),
+    };
+    
+    skip_comments(&mut inner);
+    let cond = inner.next_if(|pair| !matches!(pair.as_rule(), Rule::for_separator | Rule::for_update | Rule::statement))
+        .map(|cond| parse_condition(cond, "eega"))
+        .transpose()?;
+    
+    skip_comments(&mut inner);
+    let separator = inner.next_if(|pair| pair.as_rule() == Rule::for_separator);
+    skip_comments(&mut inner);
+    let update = inner.next_if(|pair| pair.as_rule() == Rule::for_update);
+    if separator.is_none() {
+        let message = match update {
+            Some(_) => "eega loop needs a ';' between its condition and its update",
+            None => "eega loop header needs a second ';', e.g. eega(pushpa i = 0; i < 10; i++) or eega(;;)",
+        };
+        return Err(missing(message, update.map_or(span, |update| update.as_span())));
+    }
+    let update = update.map(parse_for_update).transpose()?.map(Box::new);
+    
+    let mut statements = vec![];
+    for pair in inner {
+        match pair.as_rule() {
+            Rule::statement => push_statement(&mut statements, pair)?,
+            Rule::COMMENT => statements.push(parse_comment(pair)),
+            _ => {}
+        }
+    }
+    
+    Ok(Statement::For(init, cond, update, statements))
+}
+
+/// Parse a range loop: eega name in start..end { ... }
+fn parse_for_range_statement(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
     let span = pair.as_span();
     let mut inner = pair.into_inner();
     
-    let init = parse_statement(inner.next().ok_or_else(|| {
+    let name = next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
-            pest::error::ErrorVariant::CustomError { message: "Expected initialization in eega statement".to_string() },
+            pest::error::ErrorVariant::CustomError { message: "Expected loop variable in eega statement".to_string() },
             span,
         )
-    })?)?;
+    })?.as_str().to_string();
     
-    let cond = parse_expression(inner.next().ok_or_else(|| {
+    let start = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
-            pest::error::ErrorVariant::CustomError { message: "Expected condition in eega statement".to_string() },
+            pest::error::ErrorVariant::CustomError { message: "Expected range start in eega statement".to_string() },
             span,
         )
     })?)?;
     
-    let update = parse_expression(inner.next().ok_or_else(|| {
+    let end = parse_expression(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
-            pest::error::ErrorVariant::CustomError { message: "Expected update expression in eega statement".to_string() },
+            pest::error::ErrorVariant::CustomError { message: "Expected range end in eega statement".to_string() },
             span,
         )
     })?)?;
     
     let mut statements = vec![];
     for pair in inner {
-        if pair.as_rule() == Rule::statement {
-            statements.push(parse_statement(pair)?);
+        match pair.as_rule() {
+            Rule::statement => push_statement(&mut statements, pair)?,
+            Rule::COMMENT => statements.push(parse_comment(pair)),
+            _ => {}
         }
     }
     
-    Ok(Statement::For(Box::new(init), cond, update, statements))
+    Ok(Statement::ForRange(name, start, end, statements))
+}
+
+/// Parse the condition of a magadheera, pokiri or eega statement, rejecting `=` where `==` was meant
+fn parse_condition(pair: pest::iterators::Pair<Rule>, keyword: &str) -> Result<Expression, pest::error::Error<Rule>> {
+    if pair.as_rule() == Rule::assign_condition {
+        return Err(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!("Assignment '=' used as a {} condition; use '==' to compare values", keyword)
+            },
+            pair.as_span(),
+        ));
+    }
+    
+    parse_expression(pair)
+}
+
+/// Parse a for-loop update: name++, name--, name = value, name op= value, or a plain expression
+fn parse_for_update(pair: pest::iterators::Pair<Rule>) -> Result<Statement, pest::error::Error<Rule>> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    let update = next_code_pair(&mut inner).ok_or_else(|| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message: "Expected update expression in eega statement".to_string() },
+            span,
+        )
+    })?;
+    
+    match update.as_rule() {
+        Rule::increment_update => parse_increment_statement(update),
+        Rule::assign_update => parse_assign_statement(update),
+        _ => parse_expression(update).map(Statement::Expr),
+    }
 }
 
 /// Parse an expression
 fn parse_expression(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pest::error::Error<Rule>> {
     let span = pair.as_span();
     let mut inner = pair.into_inner();
-    let mut left = parse_term(inner.next().ok_or_else(|| {
+    let first = parse_term(next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected term in expression".to_string() },
             span,
         )
     })?)?;
-
-    while let Some(op_pair) = inner.next() {
+    
+    // Operands wait on a stack until an operator of lower or equal precedence shows up, so
+    // tighter operators group first and operators of the same level group left to right
+    let mut operands = vec![first];
+    let mut operators: Vec<String> = vec![];
+    while let Some(op_pair) = next_code_pair(&mut inner) {
         if op_pair.as_rule() == Rule::operator {
             let op = op_pair.as_str().to_string();
-            let right = parse_term(inner.next().ok_or_else(|| {
+            let right = parse_term(next_code_pair(&mut inner).ok_or_else(|| {
                 pest::error::Error::new_from_span(
                     pest::error::ErrorVariant::CustomError { message: "Expected right operand".to_string() },
                     span,
                 )
             })?)?;
-            left = Expression::BinaryOp(Box::new(left), op, Box::new(right));
+            while operators.last().is_some_and(|top| precedence(top) >= precedence(&op)) {
+                reduce_binary_op(&mut operands, &mut operators);
+            }
+            operators.push(op);
+            operands.push(right);
+        } else if op_pair.as_rule() == Rule::conditional {
+            // The grammar only allows the conditional last, after every binary operator
+            while !operators.is_empty() {
+                reduce_binary_op(&mut operands, &mut operators);
+            }
+            let mut branches = op_pair.into_inner().filter(|pair| pair.as_rule() == Rule::expression);
+            let (Some(then_branch), Some(else_branch)) = (branches.next(), branches.next()) else {
+                return Err(pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError { message: "Expected both branches of ?:".to_string() },
+                    span,
+                ));
+            };
+            let cond = operands.pop().unwrap_or(Expression::Number(0));
+            operands.push(Expression::Conditional(
+                Box::new(cond),
+                Box::new(parse_expression(then_branch)?),
+                Box::new(parse_expression(else_branch)?),
+            ));
         } else {
             return Err(pest::error::Error::new_from_span(
                 pest::error::ErrorVariant::CustomError { message: format!("Unexpected pair in expression: {:?}", op_pair.as_rule()) },
@@ -364,42 +1013,213 @@ fn parse_expression(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pes
             ));
         }
     }
+    
+    while !operators.is_empty() {
+        reduce_binary_op(&mut operands, &mut operators);
+    }
+    Ok(operands.pop().unwrap_or(Expression::Number(0)))
+}
 
-    Ok(left)
+/// Replace the top two operands with the top operator applied to them
+fn reduce_binary_op(operands: &mut Vec<Expression>, operators: &mut Vec<String>) {
+    if let (Some(op), Some(right), Some(left)) = (operators.pop(), operands.pop(), operands.pop()) {
+        operands.push(Expression::BinaryOp(Box::new(left), op, Box::new(right)));
+    }
 }
 
-/// Parse a term (number, identifier, string, or parenthesized expression)
+/// Parse an anonymous function: fn(a, b) { ... }
+fn parse_lambda(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pest::error::Error<Rule>> {
+    let mut params = vec![];
+    let mut body = vec![];
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::ident => params.push(pair.as_str().to_string()),
+            Rule::statement => push_statement(&mut body, pair)?,
+            Rule::COMMENT => body.push(parse_comment(pair)),
+            _ => {}
+        }
+    }
+    Ok(Expression::Lambda(params, body))
+}
+
+/// Parse an object literal: { key: value, ... }
+fn parse_object(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pest::error::Error<Rule>> {
+    let mut entries = vec![];
+    for entry in pair.into_inner() {
+        if entry.as_rule() == Rule::object_entry {
+            let mut parts = entry.into_inner().filter(|pair| pair.as_rule() != Rule::COMMENT);
+            let key = parts.next().map(|pair| pair.as_str().to_string()).unwrap_or_default();
+            if let Some(value) = parts.next() {
+                entries.push((key, parse_expression(value)?));
+            }
+        }
+    }
+    
+    Ok(Expression::Object(entries))
+}
+
+/// Resolve the escape sequences in a string literal's contents
+///
+/// Supports `\n`, `\t`, `\r`, `\"`, `\\`, `\uXXXX` and `\u{X...}`; anything else is an error.
+fn unescape_string(raw: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('"') => unescaped.push('"'),
+            Some('\\') => unescaped.push('\\'),
+            Some('u') => unescaped.push(parse_unicode_escape(&mut chars)?),
+            Some(other) => return Err(format!("Unknown escape sequence '\\{}' in string", other)),
+            None => return Err("String ends with an unfinished escape sequence".to_string()),
+        }
+    }
+    
+    Ok(unescaped)
+}
+
+/// Parse the part of a unicode escape after `\u`: four hex digits, or 1-6 hex digits in braces
+fn parse_unicode_escape(chars: &mut std::str::Chars) -> Result<char, String> {
+    let rest = chars.as_str();
+    let digits = if let Some(braced) = rest.strip_prefix('{') {
+        let close = braced.find('}').ok_or("Unicode escape '\\u{...}' is missing its closing brace")?;
+        let digits = &braced[..close];
+        if digits.is_empty() || digits.len() > 6 {
+            return Err("Unicode escape '\\u{...}' needs 1 to 6 hex digits".to_string());
+        }
+        *chars = braced[close + 1..].chars();
+        digits
+    } else {
+        let digits = rest.get(..4).ok_or("Unicode escape '\\uXXXX' needs exactly 4 hex digits")?;
+        *chars = rest[4..].chars();
+        digits
+    };
+    
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex digits '{}' in unicode escape", digits));
+    }
+    
+    // At most 6 hex digits, so this always fits in a u32
+    let code = u32::from_str_radix(digits, 16).unwrap_or(u32::MAX);
+    char::from_u32(code).ok_or_else(|| format!("'\\u{{{}}}' is not a valid Unicode code point", digits))
+}
+
+/// Parse a term (number, identifier, string, object, or parenthesized expression) with any property accesses
 fn parse_term(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pest::error::Error<Rule>> {
     let span = pair.as_span();
     let mut inner = pair.into_inner();
-    let inner_pair = inner.next().ok_or_else(|| {
+    let inner_pair = next_code_pair(&mut inner).ok_or_else(|| {
         pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Expected term content".to_string() },
             span,
         )
     })?;
     
-    match inner_pair.as_rule() {
+    let mut expr = match inner_pair.as_rule() {
         Rule::number => {
-            let num = inner_pair.as_str().parse().unwrap();
-            Ok(Expression::Number(num))
+            let num = inner_pair.as_str().parse().map_err(|_| {
+                pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError {
+                        message: format!("Number literal '{}' is too large (maximum is {})", inner_pair.as_str(), i32::MAX)
+                    },
+                    inner_pair.as_span(),
+                )
+            })?;
+            Expression::Number(num)
         }
+        Rule::float => Expression::Float(inner_pair.as_str().to_string()),
         Rule::ident => {
             let ident = inner_pair.as_str().to_string();
-            Ok(Expression::Identifier(ident))
+            Expression::Identifier(ident)
         }
         Rule::string => {
             // Remove the surrounding quotes
             let s = inner_pair.as_str();
-            let s = s[1..s.len()-1].to_string();
-            Ok(Expression::String(s))
+            let s = unescape_string(&s[1..s.len()-1]).map_err(|message| {
+                pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError { message },
+                    inner_pair.as_span(),
+                )
+            })?;
+            Expression::String(s)
         }
-        Rule::expression => parse_expression(inner_pair),
-        _ => Err(pest::error::Error::new_from_span(
+        Rule::expression => Expression::Grouping(Box::new(parse_expression(inner_pair)?)),
+        Rule::object => parse_object(inner_pair)?,
+        Rule::lambda => parse_lambda(inner_pair)?,
+        _ => return Err(pest::error::Error::new_from_span(
             pest::error::ErrorVariant::CustomError { message: "Unknown term type".to_string() },
             inner_pair.as_span(),
         ))
+    };
+    
+    for access in inner {
+        match access.as_rule() {
+            Rule::member_access => {
+                let field = access.into_inner().as_str().to_string();
+                expr = Expression::Member(Box::new(expr), field);
+            }
+            Rule::optional_member_access => {
+                let field = access.into_inner().as_str().to_string();
+                expr = Expression::OptionalMember(Box::new(expr), field);
+            }
+            Rule::method_call => {
+                let mut parts = access.into_inner().filter(|pair| pair.as_rule() != Rule::COMMENT);
+                let method = parts.next().map(|pair| pair.as_str().to_string()).unwrap_or_default();
+                let args = parts.map(parse_expression).collect::<Result<Vec<_>, _>>()?;
+                expr = Expression::MethodCall(Box::new(expr), method, args);
+            }
+            Rule::call => {
+                let args = access.into_inner()
+                    .filter(|pair| pair.as_rule() != Rule::COMMENT)
+                    .map(parse_expression)
+                    .collect::<Result<Vec<_>, _>>()?;
+                expr = Expression::Call(Box::new(expr), args);
+            }
+            Rule::index_access => {
+                let bounds: Vec<_> = access.into_inner().filter(|pair| pair.as_rule() != Rule::COMMENT).collect();
+                // Only a negative index written as a literal counts from the end; one computed by
+                // an expression like 0 - 1 indexes as it would in JavaScript
+                let from_end = matches!(bounds.as_slice(), [bound] if bound.as_rule() == Rule::negative_index);
+                let mut bounds = bounds.into_iter().map(parse_index_bound).collect::<Result<Vec<_>, _>>()?;
+                expr = match (bounds.pop(), bounds.pop()) {
+                    (Some(end), Some(start)) => Expression::Slice(Box::new(expr), Box::new(start), Box::new(end)),
+                    (Some(Expression::Number(n)), None) if from_end && n < 0 => {
+                        Expression::IndexFromEnd(Box::new(expr), n.unsigned_abs())
+                    }
+                    (Some(index), None) => Expression::Index(Box::new(expr), Box::new(index)),
+                    _ => expr,
+                };
+            }
+            _ => {}
+        }
     }
+    
+    Ok(expr)
+}
+
+/// Parse an index or slice bound, where a negative literal such as -1 is also allowed
+fn parse_index_bound(pair: pest::iterators::Pair<Rule>) -> Result<Expression, pest::error::Error<Rule>> {
+    if pair.as_rule() != Rule::negative_index {
+        return parse_expression(pair);
+    }
+    
+    let index = pair.as_str().parse().map_err(|_| {
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: format!("Index '{}' is too small (minimum is {})", pair.as_str(), i32::MIN)
+            },
+            pair.as_span(),
+        )
+    })?;
+    Ok(Expression::Number(index))
 }
 
 #[cfg(test)]
@@ -416,7 +1236,7 @@ mod tests {
         let statements = result.unwrap();
         assert_eq!(statements.len(), 1);
         
-        if let Statement::Print(expressions) = &statements[0] {
+        if let Statement::Print(expressions) = &statements[0].node {
             assert_eq!(expressions.len(), 1);
             if let Expression::String(s) = &expressions[0] {
                 assert_eq!(s, "Hello, world!");
@@ -428,6 +1248,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_print_inline_statement() {
+        let statements = parse_program(r#"salaar("x =", 1);"#).unwrap();
+        assert_eq!(statements, vec![Statement::PrintInline(vec![
+            Expression::String("x =".to_string()),
+            Expression::Number(1),
+        ])]);
+        
+        let error = parse_program("salaar();").unwrap_err();
+        assert!(error.to_string().contains("salaar"));
+    }
+
+    #[test]
+    fn test_parse_empty_print() {
+        // Rejected later by the validator unless empty prints are allowed
+        assert_eq!(parse_program("bahubali();").unwrap(), vec![Statement::Print(vec![])]);
+    }
+
+    #[test]
+    fn test_statement_lines() {
+        let source = "rrr x = 1;\npushpa y = 2;\n\nmagadheera(x < y) {\n    bahubali(x); }";
+        let statements = parse_program(source).unwrap();
+        
+        let lines: Vec<usize> = statements.iter().map(|stmt| stmt.line).collect();
+        assert_eq!(lines, vec![1, 2, 4]);
+        
+        if let Statement::If(_, then_block, _) = &statements[2].node {
+            assert_eq!(then_block[0].line, 5);
+        } else {
+            panic!("Expected if statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_clear_statement() {
+        let statements = parse_program("wipe();").unwrap();
+        assert_eq!(statements, vec![Statement::Clear]);
+    }
+
+    #[test]
+    fn test_parse_raw_print_block() {
+        let source = "bahubali_block {\n  if (a) {\n  } else { b(\"c\"); } // kept\n\n    }\nbahubali_block {\n}\nbahubali(1);";
+        let statements = parse_program(source).unwrap();
+        
+        assert_eq!(statements, vec![
+            Statement::RawPrint("  if (a) {\n  } else { b(\"c\"); } // kept\n".to_string()),
+            Statement::RawPrint(String::new()),
+            Statement::Print(vec![Expression::Number(1)]),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_raw_js_block() {
+        let source = "rajini {\n  const o = { a: \"}\" };\n}\nrajini { }\nbahubali(1);";
+        let statements = parse_program(source).unwrap();
+        
+        assert_eq!(statements, vec![
+            Statement::RawJs("\n  const o = { a: \"}\" };\n".to_string()),
+            Statement::RawJs(" ".to_string()),
+            Statement::Print(vec![Expression::Number(1)]),
+        ]);
+        assert!(parse_program("rajini { if (a) { }").is_err());
+    }
+
+    #[test]
+    fn test_raw_print_block_needs_closing_line() {
+        assert!(parse_program("bahubali_block {\ntext }").is_err());
+        assert!(parse_program("bahubali_block { text\n}").is_err());
+    }
+
     #[test]
     fn test_parse_const_declaration() {
         let source = "rrr x = 42;";
@@ -437,7 +1327,7 @@ mod tests {
         let statements = result.unwrap();
         assert_eq!(statements.len(), 1);
         
-        if let Statement::Const(name, expr) = &statements[0] {
+        if let Statement::Const(name, expr) = &statements[0].node {
             assert_eq!(name, "x");
             if let Expression::Number(n) = expr {
                 assert_eq!(*n, 42);
@@ -449,6 +1339,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_multi_const_declaration() {
+        let statements = parse_program("rrr a = 1, b = 2, c = a + b;").unwrap();
+        
+        assert_eq!(statements[0], Statement::MultiConst(vec![
+            ("a".to_string(), Expression::Number(1)),
+            ("b".to_string(), Expression::Number(2)),
+            ("c".to_string(), Expression::BinaryOp(
+                Box::new(Expression::Identifier("a".to_string())),
+                "+".to_string(),
+                Box::new(Expression::Identifier("b".to_string()))
+            )),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_object_literal() {
+        let statements = parse_program("rrr cfg = { name: \"x\", count: 3 }; rrr empty = {};").unwrap();
+        
+        assert_eq!(statements[0], Statement::Const("cfg".to_string(), Expression::Object(vec![
+            ("name".to_string(), Expression::String("x".to_string())),
+            ("count".to_string(), Expression::Number(3)),
+        ])));
+        assert_eq!(statements[1], Statement::Const("empty".to_string(), Expression::Object(vec![])));
+    }
+
     #[test]
     fn test_parse_let_declaration() {
         let source = "pushpa y = 10;";
@@ -458,7 +1374,7 @@ mod tests {
         let statements = result.unwrap();
         assert_eq!(statements.len(), 1);
         
-        if let Statement::Let(name, expr) = &statements[0] {
+        if let Statement::Let(name, expr) = &statements[0].node {
             assert_eq!(name, "y");
             if let Expression::Number(n) = expr {
                 assert_eq!(*n, 10);
@@ -470,6 +1386,134 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_assignment() {
+        let source = "pushpa y = 10; y = y + 1;";
+        let statements = parse_program(source).unwrap();
+        assert_eq!(statements.len(), 2);
+        
+        if let Statement::Assign(name, expr) = &statements[1].node {
+            assert_eq!(name, "y");
+            assert!(matches!(expr, Expression::BinaryOp(_, _, _)));
+        } else {
+            panic!("Expected assign statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_increment_and_decrement() {
+        let statements = parse_program("pushpa i = 0; i++; i--;").unwrap();
+        assert_eq!(statements[1], Statement::Increment("i".to_string(), false));
+        assert_eq!(statements[2], Statement::Increment("i".to_string(), true));
+    }
+
+    #[test]
+    fn test_parse_compound_assignment() {
+        let statements = parse_program("pushpa i = 10; i -= 2;").unwrap();
+        
+        assert_eq!(statements[1], Statement::Assign("i".to_string(), Expression::BinaryOp(
+            Box::new(Expression::Identifier("i".to_string())),
+            "-".to_string(),
+            Box::new(Expression::Number(2))
+        )));
+    }
+
+    #[test]
+    fn test_parse_for_update_forms() {
+        let update_of = |source: &str| match &parse_program(source).unwrap()[0].node {
+            Statement::For(_, _, Some(update), _) => (**update).clone(),
+            _ => panic!("Expected for statement"),
+        };
+        
+        let decrement = update_of("eega(pushpa i = 3; i > 0; i--) { bahubali(i); }");
+        assert_eq!(decrement, Statement::Increment("i".to_string(), true));
+        
+        let compound = update_of("eega(pushpa i = 3; i > 0; i -= 1) { bahubali(i); }");
+        assert!(matches!(compound, Statement::Assign(_, Expression::BinaryOp(_, _, _))));
+        
+        let plain = update_of("eega(pushpa i = 0; i == 0; i + 1) { bahubali(i); }");
+        assert!(matches!(plain, Statement::Expr(Expression::BinaryOp(_, _, _))));
+    }
+
+    #[test]
+    fn test_parse_member_access() {
+        let source = "bahubali(config.db.host);";
+        let statements = parse_program(source).unwrap();
+        
+        let expected = Expression::Member(
+            Box::new(Expression::Member(
+                Box::new(Expression::Identifier("config".to_string())),
+                "db".to_string()
+            )),
+            "host".to_string()
+        );
+        assert_eq!(statements[0], Statement::Print(vec![expected]));
+    }
+
+    #[test]
+    fn test_parse_optional_member_access() {
+        let source = "bahubali(config?.name);";
+        let statements = parse_program(source).unwrap();
+        
+        let expected = Expression::OptionalMember(
+            Box::new(Expression::Identifier("config".to_string())),
+            "name".to_string()
+        );
+        assert_eq!(statements[0], Statement::Print(vec![expected]));
+    }
+
+    #[test]
+    fn test_parse_method_call() {
+        let source = "bahubali(name.trim().slice(1, 3));";
+        let statements = parse_program(source).unwrap();
+        
+        let expected = Expression::MethodCall(
+            Box::new(Expression::MethodCall(
+                Box::new(Expression::Identifier("name".to_string())),
+                "trim".to_string(),
+                vec![]
+            )),
+            "slice".to_string(),
+            vec![Expression::Number(1), Expression::Number(3)]
+        );
+        assert_eq!(statements[0], Statement::Print(vec![expected]));
+    }
+
+    #[test]
+    fn test_parse_index_and_slice() {
+        let statements = parse_program("bahubali(name[0], name[1..3]);").unwrap();
+        
+        let name = || Box::new(Expression::Identifier("name".to_string()));
+        assert_eq!(statements[0], Statement::Print(vec![
+            Expression::Index(name(), Box::new(Expression::Number(0))),
+            Expression::Slice(name(), Box::new(Expression::Number(1)), Box::new(Expression::Number(3))),
+        ]));
+        
+        let statements = parse_program("bahubali(name[-1], name[1..-1], name[0 - 1]);").unwrap();
+        let computed = Expression::BinaryOp(Box::new(Expression::Number(0)), "-".to_string(), Box::new(Expression::Number(1)));
+        assert_eq!(statements[0], Statement::Print(vec![
+            Expression::IndexFromEnd(name(), 1),
+            Expression::Slice(name(), Box::new(Expression::Number(1)), Box::new(Expression::Number(-1))),
+            Expression::Index(name(), Box::new(computed)),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_float_literals() {
+        let source = "bahubali(1.5, 1.5e3, 2e3, 1.5E-2);";
+        let statements = parse_program(source).unwrap();
+        
+        let expected = vec!["1.5", "1.5e3", "2e3", "1.5E-2"].into_iter()
+            .map(|f| Expression::Float(f.to_string()))
+            .collect();
+        assert_eq!(statements[0], Statement::Print(expected));
+    }
+
+    #[test]
+    fn test_parse_malformed_exponent_error() {
+        assert!(parse_program("rrr x = 1e;").is_err());
+    }
+
     #[test]
     fn test_parse_binary_expression() {
         let source = "rrr result = 5 + 3;";
@@ -479,7 +1523,7 @@ mod tests {
         let statements = result.unwrap();
         assert_eq!(statements.len(), 1);
         
-        if let Statement::Const(_, expr) = &statements[0] {
+        if let Statement::Const(_, expr) = &statements[0].node {
             if let Expression::BinaryOp(left, op, right) = expr {
                 assert_eq!(op, "+");
                 if let Expression::Number(n) = **left {
@@ -500,6 +1544,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_grouping_preserved() {
+        let source = "rrr result = (1 + 2) * 3;";
+        let statements = parse_program(source).unwrap();
+        
+        let expected = Expression::BinaryOp(
+            Box::new(Expression::Grouping(Box::new(Expression::BinaryOp(
+                Box::new(Expression::Number(1)),
+                "+".to_string(),
+                Box::new(Expression::Number(2))
+            )))),
+            "*".to_string(),
+            Box::new(Expression::Number(3))
+        );
+        assert_eq!(statements[0], Statement::Const("result".to_string(), expected));
+    }
+
     #[test]
     fn test_parse_if_statement() {
         let source = r#"
@@ -513,7 +1574,7 @@ mod tests {
         let statements = result.unwrap();
         assert_eq!(statements.len(), 1);
         
-        if let Statement::If(cond, then_block, else_block) = &statements[0] {
+        if let Statement::If(cond, then_block, else_block) = &statements[0].node {
             assert_eq!(then_block.len(), 1);
             assert!(else_block.is_none());
             
@@ -537,6 +1598,331 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_assignment_in_condition_error() {
+        let error = parse_program("pushpa x = 1; magadheera(x = 5) { bahubali(x); }").unwrap_err();
+        assert!(error.to_string().contains("'=='"));
+        
+        let error = parse_program("pushpa i = 0; pokiri(i = 3) { i++; }").unwrap_err();
+        assert!(error.to_string().contains("pokiri condition"));
+        
+        assert!(parse_program("pushpa x = 1; magadheera(x == 5) { bahubali(x); }").is_ok());
+    }
+
+    #[test]
+    fn test_for_header_missing_parts() {
+        let error = |source: &str| parse_program(source).unwrap_err().to_string();
+        
+        assert!(error("eega(pushpa i = 0;) { bahubali(i); }").contains("needs a second ';'"));
+        assert!(error("eega(pushpa i = 0; i < 5) { bahubali(i); }").contains("needs a second ';'"));
+        assert!(error("eega(pushpa i = 0; i < 5 i++) { bahubali(i); }").contains("needs a ';' between"));
+        
+        let source = "eega(pushpa i = 0; // start\n i < 5; i++) {\n // body\n bahubali(i);\n}";
+        let statements = parse_program_with_comments(source).unwrap();
+        assert!(matches!(&statements[0].node, Statement::For(_, _, _, body) if body.len() == 2));
+    }
+
+    #[test]
+    fn test_for_header_optional_clauses() {
+        let clauses = |source: &str| match &parse_program(source).unwrap()[0].node {
+            Statement::For(init, cond, update, _) => (init.is_some(), cond.is_some(), update.is_some()),
+            _ => panic!("Expected for statement"),
+        };
+        
+        let cases = [
+            ("eega(pushpa i = 0; i < 5; i++)", (true, true, true)),
+            ("eega(; i < 5; i++)", (false, true, true)),
+            ("eega(pushpa i = 0; ; i++)", (true, false, true)),
+            ("eega(pushpa i = 0; i < 5;)", (true, true, false)),
+            ("eega(; ; i++)", (false, false, true)),
+            ("eega(; i < 5;)", (false, true, false)),
+            ("eega(pushpa i = 0;;)", (true, false, false)),
+            ("eega(;;)", (false, false, false)),
+        ];
+        for (header, expected) in cases {
+            assert_eq!(clauses(&format!("{} {{ bahubali(1); }}", header)), expected, "{}", header);
+        }
+    }
+
+    #[test]
+    fn test_adversarial_inputs_return_errors() {
+        let inputs = [
+            "rrr x = 99999999999999999999;",
+            "bahubali(2147483648);",
+            "magadheera(1 > 0) { bahubali(1);",
+            "}}}{{{",
+            "+ - * / ~/",
+            "rrr x = ;",
+            "bahubali(\"unterminated);",
+            "\"",
+            "",
+            "బాహుబలి(1);",
+            "rrr x = { a: 1, a };",
+            &"(".repeat(10_000),
+        ];
+        
+        for input in inputs {
+            let result = std::panic::catch_unwind(|| parse_program(input));
+            assert!(matches!(result, Ok(Err(_))), "expected a parse error for {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_max_number_literal() {
+        let statements = parse_program("rrr x = 2147483647;").unwrap();
+        assert_eq!(statements[0], Statement::Const("x".to_string(), Expression::Number(i32::MAX)));
+    }
+
+    #[test]
+    fn test_parse_labeled_loop_and_break() {
+        let source = "outer: pokiri(1 > 0) { aagipo outer; aagipo; }";
+        let statements = parse_program(source).unwrap();
+        
+        if let Statement::Labeled(label, body) = &statements[0].node {
+            assert_eq!(label, "outer");
+            if let Statement::While(_, block) = &**body {
+                assert_eq!(block[0], Statement::Break(Some("outer".to_string())));
+                assert_eq!(block[1], Statement::Break(None));
+            } else {
+                panic!("Expected labeled while loop");
+            }
+        } else {
+            panic!("Expected labeled statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_print_keyword_arguments() {
+        let string = |s: &str| Expression::String(s.to_string());
+        
+        let statements = parse_program("bahubali(\"a\", \"b\", sep=\"-\", end=\"!\");").unwrap();
+        assert_eq!(statements[0], Statement::PrintJoined(vec![string("a"), string("b")], string("-"), string("!")));
+        
+        // A missing keyword keeps its default, and a comparison is still a plain value
+        let statements = parse_program("rrr sep = 1; bahubali(sep == 1, end=\"\");").unwrap();
+        assert!(matches!(&statements[1].node, Statement::PrintJoined(args, sep, end)
+            if args.len() == 1 && *sep == string(" ") && *end == string("")));
+        
+        for (source, message) in [
+            ("bahubali(sep=\"-\", \"a\");", "must come before"),
+            ("bahubali(\"a\", fill=\"-\");", "Unknown bahubali argument 'fill'"),
+            ("bahubali(\"a\", end=\"\", end=\"!\");", "more than once"),
+        ] {
+            let error = parse_program(source).unwrap_err();
+            assert!(error.to_string().contains(message), "{}: {}", source, error);
+        }
+    }
+
+    #[test]
+    fn test_parse_conditional_expression() {
+        let ident = |name: &str| Box::new(Expression::Identifier(name.to_string()));
+        let string = |text: &str| Box::new(Expression::String(text.to_string()));
+        let statements = parse_program(r#"bahubali(x > 0 ? "pos" : "neg", a ? b : c ? d : e);"#).unwrap();
+        
+        let positive = Expression::Conditional(
+            Box::new(Expression::BinaryOp(ident("x"), ">".to_string(), Box::new(Expression::Number(0)))),
+            string("pos"),
+            string("neg"),
+        );
+        let chained = Expression::Conditional(
+            ident("a"),
+            ident("b"),
+            Box::new(Expression::Conditional(ident("c"), ident("d"), ident("e"))),
+        );
+        assert_eq!(statements[0].node, Statement::Print(vec![positive, chained]));
+        
+        // ?. is still optional member access
+        assert!(parse_program("bahubali(cfg?.name);").is_ok());
+        assert!(parse_program("bahubali(x ? 1);").is_err());
+    }
+
+    #[test]
+    fn test_parse_lambda_and_calls() {
+        let ident = |name: &str| Expression::Identifier(name.to_string());
+        
+        let statements = parse_program("rrr add = fn(a, b) { vaapas a + b; };\nbahubali(add(2, 3));\nreset();").unwrap();
+        assert_eq!(statements[0], Statement::Const("add".to_string(), Expression::Lambda(
+            vec!["a".to_string(), "b".to_string()],
+            vec![Spanned::new(Statement::Return(Some(Expression::BinaryOp(
+                Box::new(ident("a")),
+                "+".to_string(),
+                Box::new(ident("b")),
+            ))), 1)],
+        )));
+        assert_eq!(statements[1], Statement::Print(vec![Expression::Call(
+            Box::new(ident("add")),
+            vec![Expression::Number(2), Expression::Number(3)],
+        )]));
+        assert_eq!(statements[2], Statement::Expr(Expression::Call(Box::new(ident("reset")), vec![])));
+        
+        // "fn" only starts a function when a parameter list follows
+        let statements = parse_program("rrr fnord = 1; bahubali(fnord);").unwrap();
+        assert_eq!(statements[1], Statement::Print(vec![ident("fnord")]));
+    }
+
+    #[test]
+    fn test_parse_assert_statement() {
+        let statements = parse_program("nammaka(total   ==\n 5);").unwrap();
+        assert_eq!(statements[0], Statement::Assert(
+            Expression::BinaryOp(
+                Box::new(Expression::Identifier("total".to_string())),
+                "==".to_string(),
+                Box::new(Expression::Number(5)),
+            ),
+            "total == 5".to_string(),
+        ));
+        
+        let error = parse_program("nammaka(total = 5);").unwrap_err();
+        assert!(error.to_string().contains("use '=='"));
+    }
+
+    #[test]
+    fn test_parse_two_character_operators() {
+        for op in [">=", "<=", "==", "!=", ">", "<"] {
+            let statements = parse_program(&format!("bahubali(x {} 5);", op)).unwrap();
+            assert_eq!(statements[0], Statement::Print(vec![Expression::BinaryOp(
+                Box::new(Expression::Identifier("x".to_string())),
+                op.to_string(),
+                Box::new(Expression::Number(5))
+            )]), "operator {}", op);
+        }
+    }
+
+    #[test]
+    fn test_parse_string_escapes() {
+        let statements = parse_program(r#"bahubali("caf\u00e9 \u{1F3AC}\n\"hi\" \\");"#).unwrap();
+        assert_eq!(statements[0], Statement::Print(vec![Expression::String("café 🎬\n\"hi\" \\".to_string())]));
+    }
+
+    #[test]
+    fn test_reject_malformed_escapes() {
+        for source in [
+            r#"bahubali("\q");"#,
+            r#"bahubali("\u00e");"#,
+            r#"bahubali("\u{}");"#,
+            r#"bahubali("\u{41");"#,
+            r#"bahubali("\u{+41}");"#,
+            r#"bahubali("\uD800");"#,
+            r#"bahubali("\u{110000}");"#,
+        ] {
+            assert!(parse_program(source).is_err(), "{} should be rejected", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_range_loop() {
+        let statements = parse_program("eega i in 0..n + 1 { bahubali(i); }").unwrap();
+        
+        assert_eq!(statements[0], Statement::ForRange(
+            "i".to_string(),
+            Expression::Number(0),
+            Expression::BinaryOp(
+                Box::new(Expression::Identifier("n".to_string())),
+                "+".to_string(),
+                Box::new(Expression::Number(1))
+            ),
+            vec![Statement::Print(vec![Expression::Identifier("i".to_string())]).into()]
+        ));
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let source = r#"
+            // greet the user
+            bahubali("hi"); // trailing
+            pokiri(1 > 2) {
+                // inside a block
+                bahubali("loop");
+            }
+        "#;
+        
+        let with_comments = parse_program_with_comments(source).unwrap();
+        assert_eq!(with_comments[0], Statement::Comment("greet the user".to_string()));
+        assert_eq!(with_comments[2], Statement::Comment("trailing".to_string()));
+        if let Statement::While(_, block) = &with_comments[3].node {
+            assert_eq!(block[0], Statement::Comment("inside a block".to_string()));
+        } else {
+            panic!("Expected while statement");
+        }
+        
+        // The default parser drops comments everywhere
+        let statements = parse_program(source).unwrap();
+        assert_eq!(statements.len(), 2);
+        if let Statement::While(_, block) = &statements[1].node {
+            assert_eq!(block.len(), 1);
+        } else {
+            panic!("Expected while statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_comment_after_if_block() {
+        let source = "magadheera(1 < 2) { bahubali(1); } // after\nmagadheera(1 < 2) {\n    bahubali(2);\n} // between\nkarthikeya {\n    wipe();\n}";
+        let statements = parse_program_with_comments(source).unwrap();
+        
+        assert_eq!(statements.len(), 3);
+        assert!(matches!(&statements[0].node, Statement::If(_, then_block, None) if then_block.len() == 1));
+        assert_eq!(statements[1], Statement::Comment("after".to_string()));
+        assert_eq!(statements[1].line, 1);
+        if let Statement::If(_, then_block, Some(else_block)) = &statements[2].node {
+            assert_eq!(then_block.len(), 1);
+            assert_eq!(else_block[0], Statement::Comment("between".to_string()));
+        } else {
+            panic!("Expected if statement with an else block");
+        }
+    }
+
+    #[test]
+    fn test_parse_comments_only_program_error() {
+        let source = "// nothing here";
+        assert!(parse_program_with_comments(source).is_err());
+    }
+
+    #[test]
+    fn test_parse_deeply_nested_expression_error() {
+        let depth = DEFAULT_MAX_NESTING_DEPTH * 100;
+        let source = format!("bahubali({}1{});", "(".repeat(depth), ")".repeat(depth));
+        let result = parse_program(&source);
+        
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("too deeply nested"));
+    }
+
+    #[test]
+    fn test_parse_nesting_within_limit() {
+        let source = format!("bahubali({}1{});", "(".repeat(10), ")".repeat(10));
+        assert!(parse_program_with_nesting_limit(&source, false, 20).is_ok());
+        assert!(parse_program_with_nesting_limit(&source, false, 5).is_err());
+    }
+
+    #[test]
+    fn test_parse_operator_chain_counts_toward_nesting() {
+        let chain = |terms: usize| vec!["1"; terms].join(" + ");
+        let source = format!("bahubali({});", chain(10));
+        assert!(parse_program_with_nesting_limit(&source, false, 20).is_ok());
+        assert!(parse_program_with_nesting_limit(&source, false, 5).is_err());
+        
+        // Chains end at `;` and `,`, and two-character operators count once
+        let source = format!("bahubali({}, {});\nbahubali({});", chain(8), chain(8), ["1"; 8].join(" >= "));
+        assert!(parse_program_with_nesting_limit(&source, false, 10).is_ok());
+        
+        let error = parse_program(&format!("bahubali({});", chain(200_000))).unwrap_err();
+        assert!(error.to_string().contains("too deeply nested"));
+    }
+
+    #[test]
+    fn test_parse_long_member_and_call_chains_error() {
+        for chain in [".b", ".upper()", "()", "[0]"] {
+            let source = format!("bahubali(a{});", chain.repeat(200_000));
+            let error = parse_program(&source).unwrap_err();
+            assert!(error.to_string().contains("too deeply nested"), "{}", chain);
+        }
+        
+        // Floats and ranges don't count as member accesses
+        let source = "eega i in 0..3 {\n    bahubali(i + 1.5, name.upper().trim());\n}";
+        assert!(parse_program_with_nesting_limit(source, false, 6).is_ok());
+    }
+
     #[test]
     fn test_parse_empty_program_error() {
         let source = "";
@@ -0,0 +1,149 @@
+use crate::generator::generate_program;
+use crate::parser::{parse_program_silent, DEFAULT_MAX_NESTING_DEPTH};
+use crate::validator::validate_program;
+use std::fs;
+
+/// Meta-commands understood by the interactive mode
+pub const HELP: &str = "\
+.load FILE   Run the statements in a .tfi file as part of the session
+.save FILE   Write every statement entered so far to a file
+.help        Show this help
+.exit        Leave the session";
+
+/// Result of one line of interactive input
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    /// JavaScript for the newly accepted statements, to run after those before it
+    Run(String),
+    /// Text to show the user
+    Message(String),
+    /// The user asked to leave the session
+    Exit,
+}
+
+/// Interactive session: the source accepted so far, validated together as one program
+#[derive(Debug, Default)]
+pub struct Session {
+    /// Source of every accepted input, in order
+    history: Vec<String>,
+    /// Number of top-level statements in the history
+    statement_count: usize,
+}
+
+impl Session {
+    /// Create an empty session
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Get the source of every accepted input, in order
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+    
+    /// Handle one line of input, either a meta-command starting with `.` or TFI source
+    pub fn eval(&mut self, input: &str) -> Result<Reply, String> {
+        let input = input.trim();
+        let (command, argument) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        match command {
+            ".load" => self.load(argument.trim()),
+            ".save" => self.save(argument.trim()),
+            ".help" => Ok(Reply::Message(HELP.to_string())),
+            ".exit" => Ok(Reply::Exit),
+            _ if command.starts_with('.') => Err(format!("Unknown command: {} (try .help)", command)),
+            _ => self.run(input),
+        }
+    }
+    
+    /// Run the statements of a file as if they had been typed in
+    fn load(&mut self, path: &str) -> Result<Reply, String> {
+        if path.is_empty() {
+            return Err("Usage: .load FILE".to_string());
+        }
+        let source = fs::read_to_string(path).map_err(|e| format!("Cannot load {}: {}", path, e))?;
+        self.run(source.trim())
+    }
+    
+    /// Write the session history to a file, one input per line
+    fn save(&self, path: &str) -> Result<Reply, String> {
+        if path.is_empty() {
+            return Err("Usage: .save FILE".to_string());
+        }
+        let mut source = self.history.join("\n");
+        source.push('\n');
+        fs::write(path, source).map_err(|e| format!("Cannot save {}: {}", path, e))?;
+        Ok(Reply::Message(format!("Saved {} inputs to {}", self.history.len(), path)))
+    }
+    
+    /// Validate new source together with the history, returning JavaScript for the new statements
+    ///
+    /// Input that fails to parse or validate is not added to the history.
+    fn run(&mut self, source: &str) -> Result<Reply, String> {
+        if source.is_empty() {
+            return Ok(Reply::Run(String::new()));
+        }
+        
+        let program = self.history.iter().map(String::as_str).chain([source]).collect::<Vec<_>>().join("\n");
+        let ast = parse_program_silent(&program, false, DEFAULT_MAX_NESTING_DEPTH).map_err(|e| e.to_string())?;
+        validate_program(&ast).map_err(|e| e.to_string())?;
+        
+        let js_code = generate_program(&ast[self.statement_count..]);
+        self.history.push(source.to_string());
+        self.statement_count = ast.len();
+        Ok(Reply::Run(js_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statements_build_on_history() {
+        let mut session = Session::new();
+        
+        assert_eq!(session.eval("rrr x = 1;"), Ok(Reply::Run("const x = 1;".to_string())));
+        assert_eq!(session.eval("bahubali(x);"), Ok(Reply::Run("console.log(x);".to_string())));
+        
+        // Rejected input is forgotten
+        assert!(session.eval("bahubali(y);").is_err());
+        assert!(session.eval("rrr x = 2;").is_err());
+        assert_eq!(session.history(), &["rrr x = 1;", "bahubali(x);"]);
+    }
+
+    #[test]
+    fn test_load_then_use_declared_variable() {
+        let path = std::env::temp_dir().join("tfi_lang_repl_load.tfi");
+        fs::write(&path, "rrr hero = \"Pushpa\";\npushpa count = 2;\n").unwrap();
+        
+        let mut session = Session::new();
+        let loaded = session.eval(&format!(".load {}", path.display())).unwrap();
+        assert_eq!(loaded, Reply::Run("const hero = \"Pushpa\";\nlet count = 2;".to_string()));
+        assert_eq!(session.eval("bahubali(hero, count);"), Ok(Reply::Run("console.log(hero, count);".to_string())));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let mut session = Session::new();
+        
+        let error = session.eval(".load /nonexistent/missing.tfi").unwrap_err();
+        assert!(error.starts_with("Cannot load /nonexistent/missing.tfi"));
+        assert!(session.eval(".load").is_err());
+        assert!(session.history().is_empty());
+    }
+
+    #[test]
+    fn test_save_history() {
+        let path = std::env::temp_dir().join("tfi_lang_repl_save.tfi");
+        let mut session = Session::new();
+        session.eval("rrr x = 1;").unwrap();
+        session.eval("bahubali(x);").unwrap();
+        
+        let saved = session.eval(&format!(".save {}", path.display())).unwrap();
+        assert!(matches!(saved, Reply::Message(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "rrr x = 1;\nbahubali(x);\n");
+        
+        assert_eq!(session.eval(".exit"), Ok(Reply::Exit));
+        assert!(session.eval(".frobnicate").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::compiler::{compile_with_options, CompilationOptions};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Compile TFI source and run it with node, returning what the program printed to stdout
+///
+/// Fails if the source doesn't compile, node can't be started, or the program exits with an error.
+pub fn compile_and_run(source: &str, options: &CompilationOptions) -> Result<String, Box<dyn std::error::Error>> {
+    let result = compile_with_options(source, options)?;
+    let mut node = Command::new("node")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Dropping stdin after writing closes it, so node starts running the program
+    node.stdin.take().ok_or("Failed to open node's stdin")?.write_all(result.file_contents().as_bytes())?;
+    let output = node.wait_with_output()?;
+    RuntimeError::check(&output)?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Compiled program that exited with an error when node ran it, such as from an uncaught exception
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    /// Exit code node returned, or None if it was stopped by a signal
+    pub status: Option<i32>,
+    /// What the program wrote to stderr
+    pub stderr: String,
+}
+
+impl RuntimeError {
+    /// Turn a finished node process that failed into an error
+    pub fn check(output: &Output) -> Result<(), RuntimeError> {
+        if output.status.success() {
+            return Ok(());
+        }
+        Err(RuntimeError {
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+impl std::fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            Some(code) => write!(f, "Program exited with error (exit code {})", code),
+            None => write!(f, "Program exited with error (stopped by a signal)"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
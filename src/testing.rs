@@ -0,0 +1,52 @@
+use crate::ast::{Statement, Expression};
+
+/// Build a numeric literal
+pub fn num(n: i32) -> Expression {
+    Expression::Number(n)
+}
+
+/// Build a string literal
+pub fn str(s: &str) -> Expression {
+    Expression::String(s.to_string())
+}
+
+/// Build a variable reference
+pub fn ident(name: &str) -> Expression {
+    Expression::Identifier(name.to_string())
+}
+
+/// Build a binary operation
+pub fn binop(left: Expression, op: &str, right: Expression) -> Expression {
+    Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right))
+}
+
+/// Build a print statement
+pub fn print(args: Vec<Expression>) -> Statement {
+    Statement::Print(args)
+}
+
+/// Build a const declaration
+pub fn rrr(name: &str, value: Expression) -> Statement {
+    Statement::Const(name.to_string(), value)
+}
+
+/// Build a let declaration
+pub fn pushpa(name: &str, value: Expression) -> Statement {
+    Statement::Let(name.to_string(), value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_program;
+
+    #[test]
+    fn test_builders_match_parsed_ast() {
+        let statements = parse_program("rrr x = 1 + 2; bahubali(\"x\", x);").unwrap();
+        
+        assert_eq!(statements, vec![
+            rrr("x", binop(num(1), "+", num(2))),
+            print(vec![str("x"), ident("x")]),
+        ]);
+    }
+}
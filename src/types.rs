@@ -0,0 +1,245 @@
+use crate::ast::{Statement, Spanned, Expression};
+use crate::validator::DEFAULT_MAX_EXPRESSION_DEPTH;
+use std::collections::HashMap;
+
+/// Static type of a value, as far as it can be inferred from the source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    Number,
+    String,
+    Boolean,
+    /// Object literal, or a variable holding one
+    Object,
+    /// Type could not be determined (e.g. property access or conflicting assignments)
+    Unknown,
+}
+
+impl ValueType {
+    /// Get the type name used in diagnostics
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::Number => "number",
+            ValueType::String => "string",
+            ValueType::Boolean => "boolean",
+            ValueType::Object => "object",
+            ValueType::Unknown => "unknown",
+        }
+    }
+}
+
+/// Variable types inferred from the declarations and assignments in a program
+#[derive(Debug)]
+pub struct TypeEnv {
+    vars: HashMap<String, ValueType>,
+    /// Deepest expression whose type is inferred; anything deeper is `Unknown`
+    max_depth: usize,
+}
+
+impl Default for TypeEnv {
+    fn default() -> Self {
+        Self { vars: HashMap::new(), max_depth: DEFAULT_MAX_EXPRESSION_DEPTH }
+    }
+}
+
+impl TypeEnv {
+    /// Create an empty type environment
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Build a type environment from every declaration and assignment in a program
+    ///
+    /// Scopes are not tracked, so a name bound to values of different types becomes `Unknown`.
+    pub fn from_program(statements: &[Spanned<Statement>]) -> Self {
+        Self::from_program_with_depth_limit(statements, DEFAULT_MAX_EXPRESSION_DEPTH)
+    }
+    
+    /// Build a type environment like `from_program`, inferring types only `max_depth` levels deep
+    pub fn from_program_with_depth_limit(statements: &[Spanned<Statement>], max_depth: usize) -> Self {
+        let mut env = Self { max_depth, ..Self::default() };
+        env.collect(statements);
+        env
+    }
+    
+    /// Record that a variable holds a value of the given type
+    pub fn bind(&mut self, name: &str, value_type: ValueType) {
+        let merged = match self.vars.get(name) {
+            Some(existing) if *existing != value_type => ValueType::Unknown,
+            _ => value_type,
+        };
+        self.vars.insert(name.to_string(), merged);
+    }
+    
+    /// Get the type of a variable
+    pub fn get(&self, name: &str) -> ValueType {
+        self.vars.get(name).copied().unwrap_or(ValueType::Unknown)
+    }
+    
+    /// Infer the type of an expression
+    ///
+    /// Expressions nested deeper than the environment's depth limit are `Unknown`, so inferring
+    /// the type of a runaway operator chain can't overflow the stack.
+    pub fn infer(&self, expr: &Expression) -> ValueType {
+        self.infer_within(expr, self.max_depth)
+    }
+    
+    /// Infer the type of an expression, giving up once `depth_left` levels have been used
+    fn infer_within(&self, expr: &Expression, depth_left: usize) -> ValueType {
+        let Some(depth_left) = depth_left.checked_sub(1) else {
+            return ValueType::Unknown;
+        };
+        let infer = |expr: &Expression| self.infer_within(expr, depth_left);
+        match expr {
+            Expression::Number(_) | Expression::Float(_) => ValueType::Number,
+            Expression::String(_) => ValueType::String,
+            Expression::Identifier(name) => self.get(name),
+            Expression::Grouping(inner) | Expression::Folded(inner, _) => infer(inner),
+            Expression::Object(_) => ValueType::Object,
+            Expression::Member(_, _) | Expression::OptionalMember(_, _) => ValueType::Unknown,
+            // Functions have no type of their own yet, and what a call returns isn't tracked
+            Expression::Lambda(_, _) | Expression::Call(_, _) => ValueType::Unknown,
+            // Indexing or slicing a string gives a string
+            Expression::Index(object, _) | Expression::IndexFromEnd(object, _) | Expression::Slice(object, _, _) => match infer(object) {
+                ValueType::String => ValueType::String,
+                _ => ValueType::Unknown,
+            },
+            // Known only when both branches agree
+            Expression::Conditional(_, then_branch, else_branch) => match (infer(then_branch), infer(else_branch)) {
+                (then_type, else_type) if then_type == else_type => then_type,
+                _ => ValueType::Unknown,
+            },
+            Expression::MethodCall(_, method, _) => match method.as_str() {
+                "upper" | "lower" | "trim" | "slice" | "replace" | "repeat" => ValueType::String,
+                "contains" | "starts" | "ends" => ValueType::Boolean,
+                _ => ValueType::Unknown,
+            },
+            Expression::BinaryOp(left, op, right) => match op.as_str() {
+                "+" => match (infer(left), infer(right)) {
+                    (ValueType::String, _) | (_, ValueType::String) => ValueType::String,
+                    (ValueType::Number, ValueType::Number) => ValueType::Number,
+                    _ => ValueType::Unknown,
+                },
+                "-" | "*" | "/" | "~/" => ValueType::Number,
+                ">" | "<" | ">=" | "<=" | "==" | "!=" | "&&" => ValueType::Boolean,
+                _ => ValueType::Unknown,
+            },
+        }
+    }
+    
+    /// Walk statements, binding the type of every declared or assigned variable
+    fn collect(&mut self, statements: &[Spanned<Statement>]) {
+        for stmt in statements {
+            self.collect_statement(stmt);
+        }
+    }
+    
+    /// Bind the variables declared or assigned by a single statement
+    fn collect_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Const(name, expr) | Statement::Let(name, expr) | Statement::Assign(name, expr) => {
+                let value_type = self.infer(expr);
+                self.bind(name, value_type);
+            }
+            Statement::MultiConst(declarators) => {
+                for (name, expr) in declarators {
+                    let value_type = self.infer(expr);
+                    self.bind(name, value_type);
+                }
+            }
+            Statement::Increment(name, _) => self.bind(name, ValueType::Number),
+            Statement::If(_, then_block, else_block) => {
+                self.collect(then_block);
+                if let Some(else_block) = else_block {
+                    self.collect(else_block);
+                }
+            }
+            Statement::While(_, block) => self.collect(block),
+            Statement::For(init, _, update, block) => {
+                for clause in init.iter().chain(update) {
+                    self.collect_statement(clause);
+                }
+                self.collect(block);
+            }
+            Statement::ForRange(name, _, _, block) => {
+                self.bind(name, ValueType::Number);
+                self.collect(block);
+            }
+            Statement::Labeled(_, body) => self.collect_statement(body),
+            Statement::Print(_) | Statement::PrintInline(_) | Statement::PrintJoined(_, _, _) | Statement::RawPrint(_) | Statement::Clear | Statement::Break(_)
+            | Statement::Comment(_) | Statement::Expr(_) | Statement::Import(_) | Statement::RawJs(_) | Statement::Assert(_, _)
+            | Statement::Return(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_literals_and_operators() {
+        let env = TypeEnv::new();
+        
+        let concat = Expression::BinaryOp(
+            Box::new(Expression::String("n=".to_string())),
+            "+".to_string(),
+            Box::new(Expression::Number(5))
+        );
+        assert_eq!(env.infer(&concat), ValueType::String);
+        
+        let comparison = Expression::BinaryOp(
+            Box::new(Expression::Number(1)),
+            "<".to_string(),
+            Box::new(Expression::Number(2))
+        );
+        assert_eq!(env.infer(&comparison), ValueType::Boolean);
+    }
+
+    #[test]
+    fn test_infer_mixed_int_and_float() {
+        let float = || Box::new(Expression::Float("1.5".to_string()));
+        let mut env = TypeEnv::new();
+        
+        // Ints and floats are both JS numbers, so mixing them is never a mismatch
+        let sum = Expression::BinaryOp(Box::new(Expression::Number(3)), "+".to_string(), float());
+        assert_eq!(env.infer(&sum), ValueType::Number);
+        let comparison = Expression::BinaryOp(Box::new(Expression::Number(3)), "<".to_string(), float());
+        assert_eq!(env.infer(&comparison), ValueType::Boolean);
+        
+        env.bind("total", ValueType::Number);
+        env.bind("total", env.infer(&sum));
+        assert_eq!(env.get("total"), ValueType::Number);
+    }
+
+    #[test]
+    fn test_infer_string_index_and_slice() {
+        let mut env = TypeEnv::new();
+        env.bind("name", ValueType::String);
+        
+        let name = || Box::new(Expression::Identifier("name".to_string()));
+        assert_eq!(env.infer(&Expression::Index(name(), Box::new(Expression::Number(0)))), ValueType::String);
+        assert_eq!(
+            env.infer(&Expression::Slice(name(), Box::new(Expression::Number(1)), Box::new(Expression::Number(3)))),
+            ValueType::String
+        );
+        
+        let unknown = Expression::Index(Box::new(Expression::Identifier("other".to_string())), Box::new(Expression::Number(0)));
+        assert_eq!(env.infer(&unknown), ValueType::Unknown);
+    }
+
+    #[test]
+    fn test_env_from_program() {
+        let statements = vec![
+            Statement::Const("n".to_string(), Expression::Number(1)).into(),
+            Statement::Let("s".to_string(), Expression::String("a".to_string())).into(),
+            Statement::Let("mixed".to_string(), Expression::Number(1)).into(),
+            Statement::Assign("mixed".to_string(), Expression::String("b".to_string())).into(),
+        ];
+        
+        let env = TypeEnv::from_program(&statements);
+        assert_eq!(env.get("n"), ValueType::Number);
+        assert_eq!(env.get("s"), ValueType::String);
+        assert_eq!(env.get("mixed"), ValueType::Unknown);
+        assert_eq!(env.get("missing"), ValueType::Unknown);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::ast::{Statement, Expression};
+use crate::ast::{describe_line, Statement, Spanned, Expression, js_string_method, BINARY_OPERATORS};
+use crate::error_codes;
+use crate::types::{TypeEnv, ValueType};
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// Validation error types
 #[derive(Debug, Clone, PartialEq)]
@@ -11,50 +15,264 @@ pub enum ValidationError {
     EmptyBlock(usize, String),
     /// Invalid expression type
     InvalidExpression(usize, String),
-    /// Duplicate variable declaration
-    DuplicateVariable(String, usize),
+    /// Duplicate variable declaration, with the source lines of both declarations, the imported
+    /// files those lines are in, if any, and the top-level statement of the second
+    DuplicateVariable {
+        name: String,
+        first_line: usize,
+        first_file: Option<Arc<str>>,
+        second_line: usize,
+        second_file: Option<Arc<str>>,
+        statement: usize,
+    },
     /// Undefined variable reference
     UndefinedVariable(String, usize),
+    /// Assignment to a const (rrr) variable
+    ConstAssignment(String, usize),
+    /// Expression nested deeper than the configured limit
+    NestingTooDeep(usize, usize),
+    /// aagipo used outside of any loop
+    BreakOutsideLoop(usize),
+    /// aagipo names a label that no enclosing loop has
+    UndefinedLabel(String, usize),
+    /// Loop label reused by a nested loop
+    DuplicateLabel(String, usize),
+    /// Division whose divisor is the literal 0
+    DivisionByZero(usize),
+    /// import left in the program after imports were inlined
+    UnresolvedImport(usize, String),
+    /// == or != comparing operands known to have different types
+    TypeMismatch(usize, String, ValueType, ValueType),
+    /// vaapas used outside of any fn
+    ReturnOutsideFunction(usize),
+}
+
+impl ValidationError {
+    /// Get the stable error code, explained by `tfi-lang --explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::EmptyPrintStatement(_) => error_codes::EMPTY_PRINT,
+            ValidationError::EmptyIdentifier(_, _) => error_codes::EMPTY_IDENTIFIER,
+            ValidationError::EmptyBlock(_, _) => error_codes::EMPTY_BLOCK,
+            ValidationError::InvalidExpression(_, _) => error_codes::INVALID_EXPRESSION,
+            ValidationError::DuplicateVariable { .. } => error_codes::DUPLICATE_VARIABLE,
+            ValidationError::UndefinedVariable(_, _) => error_codes::UNDEFINED_VARIABLE,
+            ValidationError::ConstAssignment(_, _) => error_codes::CONST_ASSIGNMENT,
+            ValidationError::NestingTooDeep(_, _) => error_codes::NESTING_TOO_DEEP,
+            ValidationError::BreakOutsideLoop(_) => error_codes::BREAK_OUTSIDE_LOOP,
+            ValidationError::UndefinedLabel(_, _) => error_codes::UNDEFINED_LABEL,
+            ValidationError::DuplicateLabel(_, _) => error_codes::DUPLICATE_LABEL,
+            ValidationError::DivisionByZero(_) => error_codes::DIVISION_BY_ZERO,
+            ValidationError::UnresolvedImport(_, _) => error_codes::UNRESOLVED_IMPORT,
+            ValidationError::TypeMismatch(_, _, _, _) => error_codes::TYPE_MISMATCH,
+            ValidationError::ReturnOutsideFunction(_) => error_codes::RETURN_OUTSIDE_FUNCTION,
+        }
+    }
+    
+    /// Get the 1-based number of the top-level statement the error was found in
+    pub fn statement(&self) -> usize {
+        match self {
+            ValidationError::EmptyPrintStatement(line)
+            | ValidationError::EmptyIdentifier(line, _)
+            | ValidationError::EmptyBlock(line, _)
+            | ValidationError::InvalidExpression(line, _)
+            | ValidationError::DuplicateVariable { statement: line, .. }
+            | ValidationError::UndefinedVariable(_, line)
+            | ValidationError::ConstAssignment(_, line)
+            | ValidationError::NestingTooDeep(line, _)
+            | ValidationError::BreakOutsideLoop(line)
+            | ValidationError::UndefinedLabel(_, line)
+            | ValidationError::DuplicateLabel(_, line)
+            | ValidationError::DivisionByZero(line)
+            | ValidationError::UnresolvedImport(line, _)
+            | ValidationError::TypeMismatch(line, _, _, _)
+            | ValidationError::ReturnOutsideFunction(line) => *line,
+        }
+    }
+    
+    /// Get the 1-based line the error was found on in the compiled file, for errors that record one
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ValidationError::DuplicateVariable { second_line, second_file: None, .. } if *second_line > 0 => Some(*second_line),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ValidationError::EmptyPrintStatement(line) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
                 writeln!(f, "   bahubali() requires at least one argument")?;
                 writeln!(f, "   💡 Suggestion: bahubali(\"Hello, world!\");")
             }
             ValidationError::EmptyIdentifier(line, stmt_type) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
                 writeln!(f, "   {} declaration requires a valid identifier", stmt_type)?;
                 writeln!(f, "   💡 Suggestion: {} variable_name = value;", stmt_type)
             }
             ValidationError::EmptyBlock(line, stmt_type) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
                 writeln!(f, "   {} block cannot be empty", stmt_type)?;
                 writeln!(f, "   💡 Suggestion: {} (condition) {{ bahubali(\"action\"); }}", stmt_type)
             }
             ValidationError::InvalidExpression(line, msg) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
                 writeln!(f, "   {}", msg)
             }
-            ValidationError::DuplicateVariable(name, line) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
-                writeln!(f, "   Variable '{}' is already declared", name)?;
+            ValidationError::DuplicateVariable { name, first_line, first_file, second_line, second_file, .. } => {
+                let first = describe_line(*first_line, first_file.as_deref());
+                let second = describe_line(*second_line, second_file.as_deref());
+                writeln!(f, "⚠️  Validation Error [{}] at {}", self.code(), second)?;
+                writeln!(f, "   Variable '{}' is already declared at {}, redeclared at {}", name, first, second)?;
                 writeln!(f, "   💡 Suggestion: Use a different variable name or redeclare with 'pushpa'")
             }
             ValidationError::UndefinedVariable(name, line) => {
-                writeln!(f, "⚠️  Validation Error at statement {}", line)?;
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
                 writeln!(f, "   Variable '{}' is not defined", name)?;
                 writeln!(f, "   💡 Suggestion: Declare the variable first with 'rrr {} = value;' or 'pushpa {} = value;'", name, name)
             }
+            ValidationError::ConstAssignment(name, line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   Cannot assign to '{}' because it was declared with 'rrr'", name)?;
+                writeln!(f, "   💡 Suggestion: Declare it with 'pushpa {} = value;' if it needs to change", name)
+            }
+            ValidationError::NestingTooDeep(line, limit) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   Expression too deeply nested (limit is {} levels)", limit)?;
+                writeln!(f, "   💡 Suggestion: Split the expression into smaller parts using intermediate variables")
+            }
+            ValidationError::BreakOutsideLoop(line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   aagipo can only be used inside a pokiri or eega loop")
+            }
+            ValidationError::UndefinedLabel(label, line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   No enclosing loop is labeled '{}'", label)?;
+                writeln!(f, "   💡 Suggestion: Label the loop to exit: {}: pokiri(condition) {{ ... }}", label)
+            }
+            ValidationError::DuplicateLabel(label, line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   Label '{}' is already used by an enclosing loop", label)?;
+                writeln!(f, "   💡 Suggestion: Give the inner loop a different label")
+            }
+            ValidationError::DivisionByZero(line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   Division by zero")?;
+                writeln!(f, "   💡 Suggestion: Check that the divisor is not zero before dividing")
+            }
+            ValidationError::UnresolvedImport(line, path) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   import \"{}\" could not be resolved", path)?;
+                writeln!(f, "   💡 Suggestion: Put imports at the top level of a file compiled from disk")
+            }
+            ValidationError::TypeMismatch(line, op, left, right) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   '{}' compares a {} with a {}", op, left.name(), right.name())?;
+                writeln!(f, "   💡 Suggestion: Compare values of the same type")
+            }
+            ValidationError::ReturnOutsideFunction(line) => {
+                writeln!(f, "⚠️  Validation Error [{}] at statement {}", self.code(), line)?;
+                writeln!(f, "   vaapas can only be used inside a fn body")
+            }
         }
     }
 }
 
 impl std::error::Error for ValidationError {}
 
+/// Validation warnings for code that is legal but likely a mistake
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// magadheera and karthikeya blocks are identical
+    IdenticalBranches(usize),
+    /// Method is not one of the recognized string methods
+    UnknownMethod(usize, String),
+    /// Condition of the named control structure is known to be a non-boolean value
+    NonBooleanCondition(usize, String, ValueType),
+    /// pushpa variable that is never reassigned and could be rrr
+    PreferConst(usize, String),
+    /// eega update is a plain expression, so the loop variable never changes
+    IneffectiveForUpdate(usize),
+    /// rajini block, whose JavaScript is copied to the output unchecked
+    UncheckedRawJs(usize),
+    /// eega condition never reads the variable the loop's init declares
+    LoopVariableNotInCondition(usize, String),
+    /// Statement in the same block after vaapas or aagipo, which can never run
+    UnreachableCode(usize),
+    /// Print statement with more arguments than the configured limit, with the argument count
+    TooManyPrintArgs(usize, usize),
+    /// Loop body with more statements than the configured limit, with the loop kind and body size
+    LongLoopBody(usize, String, usize),
+    /// pushpa variable assigned a value of a different type than it held before
+    TypeChanged {
+        name: String,
+        from: ValueType,
+        to: ValueType,
+        line: usize,
+    },
+}
+
+impl Warning {
+    /// Get the 1-based number of the top-level statement the warning is about
+    pub fn statement(&self) -> usize {
+        match self {
+            Warning::IdenticalBranches(line)
+            | Warning::UnknownMethod(line, _)
+            | Warning::NonBooleanCondition(line, _, _)
+            | Warning::PreferConst(line, _)
+            | Warning::IneffectiveForUpdate(line)
+            | Warning::LoopVariableNotInCondition(line, _)
+            | Warning::UncheckedRawJs(line)
+            | Warning::UnreachableCode(line)
+            | Warning::TooManyPrintArgs(line, _)
+            | Warning::LongLoopBody(line, _, _)
+            | Warning::TypeChanged { line, .. } => *line,
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::IdenticalBranches(line) => {
+                write!(f, "Statement {}: magadheera and karthikeya blocks are identical, the condition has no effect", line)
+            }
+            Warning::UnknownMethod(line, method) => {
+                write!(f, "Statement {}: '{}' is not a known string method, it will be called as-is", line, method)
+            }
+            Warning::NonBooleanCondition(line, keyword, value_type) => {
+                write!(f, "Statement {}: {} condition is a {}, not a boolean; use a comparison such as '== 0'", line, keyword, value_type.name())
+            }
+            Warning::PreferConst(line, name) => {
+                write!(f, "Statement {}: '{}' is never reassigned, prefer 'rrr {}' over 'pushpa {}'", line, name, name, name)
+            }
+            Warning::IneffectiveForUpdate(line) => {
+                write!(f, "Statement {}: eega update has no effect; use an update such as 'i++' or 'i += 1'", line)
+            }
+            Warning::UncheckedRawJs(line) => {
+                write!(f, "Statement {}: rajini block is copied to the output without being checked", line)
+            }
+            Warning::LoopVariableNotInCondition(line, name) => {
+                write!(f, "Statement {}: eega declares '{}' but its condition never uses it; check the condition tests the loop variable", line, name)
+            }
+            Warning::UnreachableCode(line) => {
+                write!(f, "Statement {}: code after vaapas or aagipo in the same block never runs", line)
+            }
+            Warning::TooManyPrintArgs(line, count) => {
+                write!(f, "Statement {}: Print statement has {} arguments, consider breaking it up", line, count)
+            }
+            Warning::LongLoopBody(line, kind, size) => {
+                write!(f, "Statement {}: {} loop has {} statements, consider refactoring", line, kind, size)
+            }
+            Warning::TypeChanged { name, from, to, line } => {
+                write!(f, "Statement {}: '{}' held a {} and is now assigned a {}; use a separate variable", line, name, from.name(), to.name())
+            }
+        }
+    }
+}
+
 /// Variable declaration type
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeclarationType {
@@ -62,15 +280,50 @@ pub enum DeclarationType {
     Let,
 }
 
+/// Default limit on expression depth, counting every operator in a chain as a level
+pub const DEFAULT_MAX_EXPRESSION_DEPTH: usize = 1000;
+
 /// Validation context for tracking variables and other state
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ValidationContext {
     /// Set of declared variables
     declared_vars: std::collections::HashSet<String>,
-    /// Map of variable names to their declaration line
-    var_declarations: std::collections::HashMap<String, usize>,
+    /// Map of variable names to the source line of their declaration and the imported file it is in
+    var_declarations: std::collections::HashMap<String, (usize, Option<Arc<str>>)>,
     /// Map of variable names to their declaration type
     var_types: std::collections::HashMap<String, DeclarationType>,
+    /// Value types inferred for the whole program, used to check conditions
+    value_types: Rc<TypeEnv>,
+    /// Type of the value each pushpa variable currently holds, where it is known
+    held_types: std::collections::HashMap<String, ValueType>,
+    /// Warnings collected so far
+    warnings: Vec<Warning>,
+    /// Deepest expression nesting allowed before validation fails
+    max_expression_depth: usize,
+    /// Current expression nesting depth
+    expression_depth: usize,
+    /// Number of loops enclosing the current statement
+    loop_depth: usize,
+    /// Labels of the enclosing loops, outermost first
+    loop_labels: Vec<String>,
+    /// Number of fn bodies enclosing the current statement
+    function_depth: usize,
+    /// 1-based number of the top-level statement being validated
+    statement: usize,
+    /// Source line of the statement being validated, from its `Spanned::line`
+    source_line: usize,
+    /// Imported file the top-level statement being validated came from, from its `Spanned::file`
+    source_file: Option<Arc<str>>,
+    /// Accept `bahubali()` with no arguments, printing a blank line
+    allow_empty_print: bool,
+    /// JavaScript globals such as `Math` that may be read without a declaration
+    allowed_globals: Rc<std::collections::HashSet<String>>,
+}
+
+impl Default for ValidationContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ValidationContext {
@@ -80,28 +333,106 @@ impl ValidationContext {
             declared_vars: std::collections::HashSet::new(),
             var_declarations: std::collections::HashMap::new(),
             var_types: std::collections::HashMap::new(),
+            value_types: Rc::default(),
+            held_types: std::collections::HashMap::new(),
+            warnings: Vec::new(),
+            max_expression_depth: DEFAULT_MAX_EXPRESSION_DEPTH,
+            expression_depth: 0,
+            loop_depth: 0,
+            loop_labels: Vec::new(),
+            function_depth: 0,
+            statement: 0,
+            source_line: 0,
+            source_file: None,
+            allow_empty_print: false,
+            allowed_globals: Rc::default(),
         }
     }
     
-    /// Declare a variable
+    /// Create a new validation context with a custom expression depth limit
+    pub fn with_max_expression_depth(max_expression_depth: usize) -> Self {
+        Self {
+            max_expression_depth,
+            ..Self::new()
+        }
+    }
+    
+    /// Accept `bahubali()` with no arguments instead of reporting an empty print
+    pub fn allowing_empty_print(mut self) -> Self {
+        self.allow_empty_print = true;
+        self
+    }
+    
+    /// Permit reading the named JavaScript globals, which are otherwise undefined variables
+    pub fn allowing_globals<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut allowed = (*self.allowed_globals).clone();
+        allowed.extend(names.into_iter().map(Into::into));
+        self.allowed_globals = Rc::new(allowed);
+        self
+    }
+    
+    /// Create a nested scope that sees every variable declared so far
+    fn enter_scope(&self) -> Self {
+        Self {
+            declared_vars: self.declared_vars.clone(),
+            var_declarations: self.var_declarations.clone(),
+            var_types: self.var_types.clone(),
+            value_types: Rc::clone(&self.value_types),
+            held_types: self.held_types.clone(),
+            warnings: Vec::new(),
+            max_expression_depth: self.max_expression_depth,
+            expression_depth: 0,
+            loop_depth: self.loop_depth,
+            loop_labels: self.loop_labels.clone(),
+            function_depth: self.function_depth,
+            statement: self.statement,
+            source_line: self.source_line,
+            source_file: self.source_file.clone(),
+            allow_empty_print: self.allow_empty_print,
+            allowed_globals: Rc::clone(&self.allowed_globals),
+        }
+    }
+    
+    /// Leave a nested scope, keeping the warnings it collected
+    fn exit_scope(&mut self, scope: ValidationContext) {
+        self.warnings.extend(scope.warnings);
+    }
+    
+    /// Get the warnings collected so far
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+    
+    /// Declare a variable on a 1-based source line of the current statement's file
     pub fn declare_variable(&mut self, name: &str, line: usize, decl_type: DeclarationType) -> Result<(), ValidationError> {
         if self.declared_vars.contains(name) {
-            let original_line = self.var_declarations.get(name).unwrap_or(&0);
+            let (original_line, original_file) = self.var_declarations.get(name).cloned().unwrap_or((0, None));
             let original_type = self.var_types.get(name).unwrap_or(&DeclarationType::Let);
             
             // Allow redeclaration if the original is const and new is let (shadowing)
             if *original_type == DeclarationType::Const && decl_type == DeclarationType::Let {
                 // This is valid shadowing
-                self.var_declarations.insert(name.to_string(), line);
+                self.var_declarations.insert(name.to_string(), (line, self.source_file.clone()));
                 self.var_types.insert(name.to_string(), decl_type);
                 return Ok(());
             }
             
-            return Err(ValidationError::DuplicateVariable(name.to_string(), *original_line));
+            return Err(ValidationError::DuplicateVariable {
+                name: name.to_string(),
+                first_line: original_line,
+                first_file: original_file,
+                second_line: line,
+                second_file: self.source_file.clone(),
+                statement: self.statement,
+            });
         }
         
         self.declared_vars.insert(name.to_string());
-        self.var_declarations.insert(name.to_string(), line);
+        self.var_declarations.insert(name.to_string(), (line, self.source_file.clone()));
         self.var_types.insert(name.to_string(), decl_type);
         Ok(())
     }
@@ -111,6 +442,11 @@ impl ValidationContext {
         self.declared_vars.contains(name)
     }
     
+    /// Get the declaration type of a variable, if it is declared
+    pub fn get_declaration_type(&self, name: &str) -> Option<&DeclarationType> {
+        self.var_types.get(name)
+    }
+    
     /// Get all declared variables
     pub fn get_declared_variables(&self) -> &std::collections::HashSet<String> {
         &self.declared_vars
@@ -118,25 +454,212 @@ impl ValidationContext {
 }
 
 /// Validate a complete TFI program
-pub fn validate_program(statements: &[Statement]) -> Result<(), Box<dyn std::error::Error>> {
-    let mut context = ValidationContext::new();
+pub fn validate_program(statements: &[Spanned<Statement>]) -> Result<(), Box<dyn std::error::Error>> {
+    validate_program_with_warnings(statements)?;
+    Ok(())
+}
+
+/// Validate a complete TFI program, returning any warnings on success
+pub fn validate_program_with_warnings(statements: &[Spanned<Statement>]) -> Result<Vec<Warning>, Box<dyn std::error::Error>> {
+    validate_program_with_depth_limit(statements, DEFAULT_MAX_EXPRESSION_DEPTH)
+}
+
+/// Validate a complete TFI program, rejecting expressions nested deeper than `max_depth`
+pub fn validate_program_with_depth_limit(
+    statements: &[Spanned<Statement>],
+    max_depth: usize
+) -> Result<Vec<Warning>, Box<dyn std::error::Error>> {
+    validate_program_in_context(statements, ValidationContext::with_max_expression_depth(max_depth))
+}
+
+/// Validate a complete TFI program starting from a configured context, returning any warnings
+pub fn validate_program_in_context(
+    statements: &[Spanned<Statement>],
+    mut context: ValidationContext
+) -> Result<Vec<Warning>, Box<dyn std::error::Error>> {
+    context.value_types = Rc::new(TypeEnv::from_program_with_depth_limit(statements, context.max_expression_depth));
     
     for (i, stmt) in statements.iter().enumerate() {
+        context.statement = i + 1;
+        context.source_line = stmt.line;
+        context.source_file = stmt.file.clone();
         validate_statement(stmt, i + 1, &mut context)?;
     }
     
-    Ok(())
+    context.warnings.extend(find_unreassigned_lets(statements));
+    Ok(context.warnings)
+}
+
+/// Find pushpa declarations whose variable is never assigned, incremented or decremented
+///
+/// Names are matched across the whole program, so a name reassigned anywhere counts as reassigned.
+fn find_unreassigned_lets(statements: &[Spanned<Statement>]) -> Vec<Warning> {
+    let mut declared = Vec::new();
+    let mut assigned = std::collections::HashSet::new();
+    for (i, stmt) in statements.iter().enumerate() {
+        collect_let_usage(stmt, i + 1, &mut declared, &mut assigned);
+    }
+    
+    declared.into_iter()
+        .filter(|(_, name)| !assigned.contains(name))
+        .map(|(line, name)| Warning::PreferConst(line, name))
+        .collect()
+}
+
+/// Record the pushpa declarations and assignment targets in a statement and its blocks
+fn collect_let_usage(
+    stmt: &Statement,
+    line: usize,
+    declared: &mut Vec<(usize, String)>,
+    assigned: &mut std::collections::HashSet<String>
+) {
+    // Function bodies can reassign variables too
+    for expr in statement_expressions(stmt) {
+        for_each_lambda_body(expr, &mut |body| {
+            for stmt in body {
+                collect_let_usage(stmt, line, declared, assigned);
+            }
+        });
+    }
+    
+    match stmt {
+        Statement::Let(name, _) => declared.push((line, name.clone())),
+        Statement::Assign(name, _) | Statement::Increment(name, _) => {
+            assigned.insert(name.clone());
+        }
+        Statement::If(_, then_block, else_block) => {
+            for stmt in then_block.iter().chain(else_block.iter().flatten()) {
+                collect_let_usage(stmt, line, declared, assigned);
+            }
+        }
+        Statement::While(_, block) => {
+            for stmt in block {
+                collect_let_usage(stmt, line, declared, assigned);
+            }
+        }
+        Statement::For(init, _, update, block) => {
+            for clause in init.iter().chain(update) {
+                collect_let_usage(clause, line, declared, assigned);
+            }
+            for stmt in block {
+                collect_let_usage(stmt, line, declared, assigned);
+            }
+        }
+        Statement::ForRange(_, _, _, block) => {
+            for stmt in block {
+                collect_let_usage(stmt, line, declared, assigned);
+            }
+        }
+        Statement::Labeled(_, body) => collect_let_usage(body, line, declared, assigned),
+        _ => {}
+    }
+}
+
+/// Get the expressions a statement holds directly, leaving out its blocks and nested statements
+fn statement_expressions(stmt: &Statement) -> Vec<&Expression> {
+    match stmt {
+        Statement::Print(exprs) | Statement::PrintInline(exprs) => exprs.iter().collect(),
+        Statement::PrintJoined(exprs, sep, end) => exprs.iter().chain([sep, end]).collect(),
+        Statement::MultiConst(declarators) => declarators.iter().map(|(_, expr)| expr).collect(),
+        Statement::Const(_, expr) | Statement::Let(_, expr) | Statement::Assign(_, expr) | Statement::Expr(expr)
+            | Statement::Assert(expr, _) | Statement::Return(Some(expr)) | Statement::If(expr, _, _)
+            | Statement::While(expr, _) | Statement::For(_, Some(expr), _, _) => vec![expr],
+        Statement::ForRange(_, start, end, _) => vec![start, end],
+        Statement::For(_, None, _, _) | Statement::Labeled(_, _) | Statement::Return(None) | Statement::RawPrint(_) | Statement::RawJs(_) | Statement::Clear
+            | Statement::Increment(_, _) | Statement::Break(_) | Statement::Comment(_) | Statement::Import(_) => vec![],
+    }
+}
+
+/// Call `f` with the body of every fn written inside an expression, outermost first
+fn for_each_lambda_body(expr: &Expression, f: &mut dyn FnMut(&[Spanned<Statement>])) {
+    match expr {
+        Expression::Lambda(_, body) => f(body),
+        Expression::BinaryOp(left, _, right) | Expression::Index(left, right) => {
+            for_each_lambda_body(left, f);
+            for_each_lambda_body(right, f);
+        }
+        Expression::Folded(value, _) => for_each_lambda_body(value, f),
+        Expression::Member(object, _) | Expression::OptionalMember(object, _) | Expression::Grouping(object)
+        | Expression::IndexFromEnd(object, _) => {
+            for_each_lambda_body(object, f);
+        }
+        Expression::MethodCall(object, _, args) | Expression::Call(object, args) => {
+            for_each_lambda_body(object, f);
+            for arg in args {
+                for_each_lambda_body(arg, f);
+            }
+        }
+        Expression::Object(entries) => {
+            for (_, value) in entries {
+                for_each_lambda_body(value, f);
+            }
+        }
+        Expression::Slice(object, start, end) | Expression::Conditional(object, start, end) => {
+            for part in [object, start, end] {
+                for_each_lambda_body(part, f);
+            }
+        }
+        Expression::Number(_) | Expression::Float(_) | Expression::Identifier(_) | Expression::String(_) => {}
+    }
+}
+
+/// Check whether an expression reads the named variable
+fn mentions_variable(expr: &Expression, name: &str) -> bool {
+    match expr {
+        Expression::Identifier(id) => id == name,
+        Expression::BinaryOp(left, _, right) | Expression::Index(left, right) => {
+            mentions_variable(left, name) || mentions_variable(right, name)
+        }
+        Expression::Member(object, _) | Expression::OptionalMember(object, _) | Expression::Grouping(object)
+        | Expression::IndexFromEnd(object, _) => {
+            mentions_variable(object, name)
+        }
+        Expression::MethodCall(object, _, args) | Expression::Call(object, args) => {
+            mentions_variable(object, name) || args.iter().any(|arg| mentions_variable(arg, name))
+        }
+        // A function body runs when it is called, not where it is written
+        Expression::Lambda(_, _) => false,
+        Expression::Object(entries) => entries.iter().any(|(_, value)| mentions_variable(value, name)),
+        Expression::Folded(value, _) => mentions_variable(value, name),
+        Expression::Slice(object, start, end) | Expression::Conditional(object, start, end) => {
+            [object, start, end].iter().any(|part| mentions_variable(part, name))
+        }
+        Expression::Number(_) | Expression::Float(_) | Expression::String(_) => false,
+    }
+}
+
+/// Check whether a block has no statements other than comments
+fn is_empty_block(block: &[Spanned<Statement>]) -> bool {
+    block.iter().all(|stmt| matches!(stmt.node, Statement::Comment(_)))
 }
 
 /// Validate a single statement
+/// Validate the statements of a block, warning once if any follow a vaapas or aagipo
+fn validate_block(block: &[Spanned<Statement>], line: usize, context: &mut ValidationContext) -> Result<(), ValidationError> {
+    let code = block.iter().filter(|stmt| !matches!(stmt.node, Statement::Comment(_))).collect::<Vec<_>>();
+    if code.iter().rev().skip(1).any(|stmt| matches!(stmt.node, Statement::Return(_) | Statement::Break(_))) {
+        context.warnings.push(Warning::UnreachableCode(line));
+    }
+    
+    // Declarations after the block are on the enclosing statement's line
+    let source_line = context.source_line;
+    for stmt in block {
+        context.source_line = stmt.line;
+        validate_statement(stmt, line, context)?;
+    }
+    context.source_line = source_line;
+    Ok(())
+}
+
 fn validate_statement(
     stmt: &Statement, 
     line: usize, 
     context: &mut ValidationContext
 ) -> Result<(), ValidationError> {
     match stmt {
-        Statement::Print(expressions) => {
-            if expressions.is_empty() {
+        Statement::Print(expressions) | Statement::PrintInline(expressions) => {
+            let allowed = context.allow_empty_print && matches!(stmt, Statement::Print(_));
+            if expressions.is_empty() && !allowed {
                 return Err(ValidationError::EmptyPrintStatement(line));
             }
             
@@ -144,130 +667,391 @@ fn validate_statement(
                 validate_expression(expr, line, context)?;
             }
         }
+        // With no values this prints just the ending, so it is never empty
+        Statement::PrintJoined(expressions, sep, end) => {
+            for expr in expressions.iter().chain([sep, end]) {
+                validate_expression(expr, line, context)?;
+            }
+        }
         Statement::Const(name, expr) => {
             if name.is_empty() {
                 return Err(ValidationError::EmptyIdentifier(line, "rrr".to_string()));
             }
             
-            context.declare_variable(name, line, DeclarationType::Const)?;
+            context.declare_variable(name, context.source_line, DeclarationType::Const)?;
             validate_expression(expr, line, context)?;
         }
+        Statement::MultiConst(declarators) => {
+            for (name, expr) in declarators {
+                if name.is_empty() {
+                    return Err(ValidationError::EmptyIdentifier(line, "rrr".to_string()));
+                }
+                
+                context.declare_variable(name, context.source_line, DeclarationType::Const)?;
+                validate_expression(expr, line, context)?;
+            }
+        }
         Statement::Let(name, expr) => {
             if name.is_empty() {
                 return Err(ValidationError::EmptyIdentifier(line, "pushpa".to_string()));
             }
             
-            context.declare_variable(name, line, DeclarationType::Let)?;
+            context.declare_variable(name, context.source_line, DeclarationType::Let)?;
+            validate_expression(expr, line, context)?;
+            
+            let value_type = context.value_types.infer(expr);
+            context.held_types.insert(name.clone(), value_type);
+        }
+        Statement::Assign(name, expr) => {
+            match context.get_declaration_type(name) {
+                None => return Err(ValidationError::UndefinedVariable(name.clone(), line)),
+                Some(DeclarationType::Const) => return Err(ValidationError::ConstAssignment(name.clone(), line)),
+                Some(DeclarationType::Let) => {}
+            }
+            
             validate_expression(expr, line, context)?;
+            
+            // Only a change between two known types is reported
+            let to = context.value_types.infer(expr);
+            if to != ValueType::Unknown {
+                if let Some(&from) = context.held_types.get(name)
+                    && from != ValueType::Unknown
+                    && from != to
+                {
+                    context.warnings.push(Warning::TypeChanged { name: name.clone(), from, to, line });
+                }
+                context.held_types.insert(name.clone(), to);
+            }
+        }
+        Statement::Increment(name, _) => {
+            match context.get_declaration_type(name) {
+                None => return Err(ValidationError::UndefinedVariable(name.clone(), line)),
+                Some(DeclarationType::Const) => return Err(ValidationError::ConstAssignment(name.clone(), line)),
+                Some(DeclarationType::Let) => {}
+            }
         }
         Statement::If(cond, then_block, else_block) => {
-            validate_expression(cond, line, context)?;
+            validate_condition(cond, "magadheera", line, context)?;
             
-            if then_block.is_empty() {
+            if is_empty_block(then_block) {
                 return Err(ValidationError::EmptyBlock(line, "magadheera".to_string()));
             }
             
             // Create a new scope for the if block
-            let mut if_context = ValidationContext::new();
-            if_context.declared_vars.extend(context.declared_vars.clone());
-            if_context.var_declarations.extend(context.var_declarations.clone());
-            if_context.var_types.extend(context.var_types.clone());
+            let mut if_context = context.enter_scope();
             
-            for stmt in then_block {
-                validate_statement(stmt, line, &mut if_context)?;
-            }
+            validate_block(then_block, line, &mut if_context)?;
+            context.exit_scope(if_context);
             
             if let Some(else_block) = else_block {
-                if else_block.is_empty() {
+                if is_empty_block(else_block) {
                     return Err(ValidationError::EmptyBlock(line, "karthikeya".to_string()));
                 }
                 
                 // Create a new scope for the else block
-                let mut else_context = ValidationContext::new();
-                else_context.declared_vars.extend(context.declared_vars.clone());
-                else_context.var_declarations.extend(context.var_declarations.clone());
-                else_context.var_types.extend(context.var_types.clone());
+                let mut else_context = context.enter_scope();
                 
-                for stmt in else_block {
-                    validate_statement(stmt, line, &mut else_context)?;
+                validate_block(else_block, line, &mut else_context)?;
+                context.exit_scope(else_context);
+                
+                if else_block == then_block {
+                    context.warnings.push(Warning::IdenticalBranches(line));
                 }
             }
         }
         Statement::While(cond, block) => {
-            validate_expression(cond, line, context)?;
+            validate_condition(cond, "pokiri", line, context)?;
             
-            if block.is_empty() {
+            if is_empty_block(block) {
                 return Err(ValidationError::EmptyBlock(line, "pokiri".to_string()));
             }
             
             // Create a new scope for the while block
-            let mut while_context = ValidationContext::new();
-            while_context.declared_vars.extend(context.declared_vars.clone());
-            while_context.var_declarations.extend(context.var_declarations.clone());
-            while_context.var_types.extend(context.var_types.clone());
+            let mut while_context = context.enter_scope();
+            while_context.loop_depth += 1;
             
-            for stmt in block {
-                validate_statement(stmt, line, &mut while_context)?;
-            }
+            validate_block(block, line, &mut while_context)?;
+            context.exit_scope(while_context);
         }
         Statement::For(init, cond, update, block) => {
-            validate_statement(init, line, context)?;
-            validate_expression(cond, line, context)?;
-            validate_expression(update, line, context)?;
+            // The loop variable is scoped to the loop, as with `for (let ...)` in JS
+            let mut for_context = context.enter_scope();
+            for_context.loop_depth += 1;
+            if let Some(init) = init {
+                validate_statement(init, line, &mut for_context)?;
+            }
+            if let Some(cond) = cond {
+                validate_condition(cond, "eega", line, &mut for_context)?;
+            }
+            if let Some(update) = update {
+                validate_statement(update, line, &mut for_context)?;
+            }
+            
+            // Heuristic: a condition that ignores the loop variable usually tests the wrong name
+            if let Some(init) = init
+                && let Statement::Const(name, _) | Statement::Let(name, _) | Statement::Assign(name, _) = &**init
+                && let Some(cond) = cond
+                && !mentions_variable(cond, name)
+            {
+                for_context.warnings.push(Warning::LoopVariableNotInCondition(line, name.clone()));
+            }
             
-            if block.is_empty() {
+            // Expressions have no side effects, so only assignments and increments advance the loop
+            if matches!(update.as_deref(), Some(Statement::Expr(_))) {
+                for_context.warnings.push(Warning::IneffectiveForUpdate(line));
+            }
+            
+            if is_empty_block(block) {
                 return Err(ValidationError::EmptyBlock(line, "eega".to_string()));
             }
             
-            // Create a new scope for the for block
-            let mut for_context = ValidationContext::new();
-            for_context.declared_vars.extend(context.declared_vars.clone());
-            for_context.var_declarations.extend(context.var_declarations.clone());
-            for_context.var_types.extend(context.var_types.clone());
+            validate_block(block, line, &mut for_context)?;
+            context.exit_scope(for_context);
+        }
+        Statement::ForRange(name, start, end, block) => {
+            // The bounds are evaluated outside the loop, before the variable exists
+            validate_expression(start, line, context)?;
+            validate_expression(end, line, context)?;
             
-            for stmt in block {
-                validate_statement(stmt, line, &mut for_context)?;
+            if is_empty_block(block) {
+                return Err(ValidationError::EmptyBlock(line, "eega".to_string()));
+            }
+            
+            let mut range_context = context.enter_scope();
+            range_context.loop_depth += 1;
+            range_context.declare_variable(name, range_context.source_line, DeclarationType::Let)?;
+            
+            validate_block(block, line, &mut range_context)?;
+            context.exit_scope(range_context);
+        }
+        Statement::Labeled(label, body) => {
+            if context.loop_labels.contains(label) {
+                return Err(ValidationError::DuplicateLabel(label.clone(), line));
+            }
+            
+            context.loop_labels.push(label.clone());
+            let result = validate_statement(body, line, context);
+            context.loop_labels.pop();
+            result?;
+        }
+        Statement::Break(label) => {
+            if context.loop_depth == 0 {
+                return Err(ValidationError::BreakOutsideLoop(line));
+            }
+            if let Some(label) = label
+                && !context.loop_labels.contains(label)
+            {
+                return Err(ValidationError::UndefinedLabel(label.clone(), line));
             }
         }
+        Statement::Return(value) => {
+            if context.function_depth == 0 {
+                return Err(ValidationError::ReturnOutsideFunction(line));
+            }
+            if let Some(value) = value {
+                validate_expression(value, line, context)?;
+            }
+        }
+        Statement::Expr(expr) => validate_expression(expr, line, context)?,
+        Statement::Assert(cond, _) => validate_condition(cond, "nammaka", line, context)?,
+        Statement::Clear | Statement::RawPrint(_) | Statement::Comment(_) => {}
+        Statement::RawJs(_) => context.warnings.push(Warning::UncheckedRawJs(line)),
+        Statement::Import(path) => return Err(ValidationError::UnresolvedImport(line, path.clone())),
     }
     
     Ok(())
 }
 
-/// Validate an expression
+/// Validate an expression, guarding against unbounded recursion
 fn validate_expression(
     expr: &Expression, 
     line: usize, 
-    context: &ValidationContext
+    context: &mut ValidationContext
+) -> Result<(), ValidationError> {
+    if context.expression_depth >= context.max_expression_depth {
+        return Err(ValidationError::NestingTooDeep(line, context.max_expression_depth));
+    }
+    
+    context.expression_depth += 1;
+    let result = validate_expression_node(expr, line, context);
+    context.expression_depth -= 1;
+    result
+}
+
+/// Validate a loop or branch condition, warning when it is known not to be a boolean
+fn validate_condition(
+    cond: &Expression,
+    keyword: &str,
+    line: usize,
+    context: &mut ValidationContext
+) -> Result<(), ValidationError> {
+    validate_expression(cond, line, context)?;
+    
+    // Literal conditions such as pokiri(1) are deliberate, so only computed values are checked
+    let is_literal = matches!(cond, Expression::Number(_) | Expression::Float(_) | Expression::String(_));
+    let value_type = context.value_types.infer(cond);
+    if !is_literal && matches!(value_type, ValueType::Number | ValueType::String) {
+        context.warnings.push(Warning::NonBooleanCondition(line, keyword.to_string(), value_type));
+    }
+    Ok(())
+}
+
+/// Validate a single expression node and its children
+fn validate_expression_node(
+    expr: &Expression, 
+    line: usize, 
+    context: &mut ValidationContext
 ) -> Result<(), ValidationError> {
     match expr {
         Expression::Number(_) => Ok(()),
+        Expression::Float(text) => {
+            if text.parse::<f64>().is_err() {
+                return Err(ValidationError::InvalidExpression(line, format!("Malformed float literal: '{}'", text)));
+            }
+            Ok(())
+        }
         Expression::String(_) => Ok(()),
         Expression::Identifier(name) => {
-            if !context.is_variable_declared(name) {
+            if name.is_empty() {
+                return Err(ValidationError::InvalidExpression(line, "Empty identifier in expression".to_string()));
+            }
+            if !context.is_variable_declared(name) && !context.allowed_globals.contains(name) {
                 return Err(ValidationError::UndefinedVariable(name.clone(), line));
             }
             Ok(())
         }
         Expression::BinaryOp(left, op, right) => {
+            // Check the operator first so a malformed tree is reported as such
+            if !BINARY_OPERATORS.contains(&op.as_str()) {
+                return Err(ValidationError::InvalidExpression(line, format!("Unknown operator: '{}'", op)));
+            }
+            
+            if (op == "/" || op == "~/") && is_zero(right) {
+                return Err(ValidationError::DivisionByZero(line));
+            }
+            
             validate_expression(left, line, context)?;
             validate_expression(right, line, context)?;
             
-            // Validate operator
-            match op.as_str() {
-                "+" | "-" | "*" | "/" | ">" | "<" | ">=" | "<=" | "==" | "!=" => Ok(()),
-                _ => Err(ValidationError::InvalidExpression(line, format!("Unknown operator: {}", op)))
+            // JS loose equality converts mismatched operands, which hides the mistake at runtime
+            if op == "==" || op == "!=" {
+                let left_type = context.value_types.infer(left);
+                let right_type = context.value_types.infer(right);
+                if left_type != ValueType::Unknown && right_type != ValueType::Unknown && left_type != right_type {
+                    return Err(ValidationError::TypeMismatch(line, op.clone(), left_type, right_type));
+                }
+            }
+            Ok(())
+        }
+        // Fields belong to runtime JS objects, so only the base can be checked
+        Expression::Member(object, field) | Expression::OptionalMember(object, field) => {
+            if field.is_empty() {
+                return Err(ValidationError::InvalidExpression(line, "Empty property name".to_string()));
+            }
+            validate_expression(object, line, context)
+        }
+        Expression::Grouping(inner) => validate_expression(inner, line, context),
+        Expression::MethodCall(object, method, args) => {
+            if method.is_empty() {
+                return Err(ValidationError::InvalidExpression(line, "Empty method name".to_string()));
+            }
+            
+            validate_expression(object, line, context)?;
+            for arg in args {
+                validate_expression(arg, line, context)?;
+            }
+            
+            if js_string_method(method).is_none() {
+                context.warnings.push(Warning::UnknownMethod(line, method.clone()));
+            }
+            Ok(())
+        }
+        Expression::Index(object, index) => {
+            validate_expression(object, line, context)?;
+            validate_expression(index, line, context)
+        }
+        Expression::IndexFromEnd(object, _) => validate_expression(object, line, context),
+        Expression::Slice(object, start, end) => {
+            validate_expression(object, line, context)?;
+            validate_expression(start, line, context)?;
+            validate_expression(end, line, context)
+        }
+        Expression::Conditional(cond, then_branch, else_branch) => {
+            validate_expression(cond, line, context)?;
+            validate_expression(then_branch, line, context)?;
+            validate_expression(else_branch, line, context)
+        }
+        // Only the value becomes code; the original is shown in a comment
+        Expression::Folded(value, _) => validate_expression(value, line, context),
+        Expression::Lambda(params, body) => {
+            // The body sees the variables around it, but loops outside it can't be exited from it
+            let mut fn_context = context.enter_scope();
+            fn_context.function_depth += 1;
+            fn_context.loop_depth = 0;
+            fn_context.loop_labels.clear();
+            
+            // Parameters may shadow any outer variable, but not each other
+            for (i, param) in params.iter().enumerate() {
+                if params[..i].contains(param) {
+                    return Err(ValidationError::DuplicateVariable {
+                        name: param.clone(),
+                        first_line: context.source_line,
+                        first_file: context.source_file.clone(),
+                        second_line: context.source_line,
+                        second_file: context.source_file.clone(),
+                        statement: line,
+                    });
+                }
+                fn_context.declared_vars.insert(param.clone());
+                fn_context.var_declarations.insert(param.clone(), (context.source_line, context.source_file.clone()));
+                fn_context.var_types.insert(param.clone(), DeclarationType::Let);
+            }
+            
+            validate_block(body, line, &mut fn_context)?;
+            context.exit_scope(fn_context);
+            Ok(())
+        }
+        Expression::Call(callee, args) => {
+            validate_expression(callee, line, context)?;
+            for arg in args {
+                validate_expression(arg, line, context)?;
             }
+            Ok(())
+        }
+        Expression::Object(entries) => {
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if key.is_empty() {
+                    return Err(ValidationError::InvalidExpression(line, "Empty key in object literal".to_string()));
+                }
+                if entries[..i].iter().any(|(other, _)| other == key) {
+                    return Err(ValidationError::InvalidExpression(line, format!("Duplicate key '{}' in object literal", key)));
+                }
+                validate_expression(value, line, context)?;
+            }
+            Ok(())
         }
     }
 }
 
+/// Check whether an expression is the number literal 0, possibly in parentheses
+fn is_zero(expr: &Expression) -> bool {
+    match expr {
+        Expression::Number(n) => *n == 0,
+        Expression::Grouping(inner) => is_zero(inner),
+        _ => false,
+    }
+}
+
 /// Validate a program with detailed error reporting
-pub fn validate_program_detailed(statements: &[Statement]) -> Result<(), Vec<ValidationError>> {
+pub fn validate_program_detailed(statements: &[Spanned<Statement>]) -> Result<(), Vec<ValidationError>> {
     let mut context = ValidationContext::new();
+    context.value_types = Rc::new(TypeEnv::from_program_with_depth_limit(statements, context.max_expression_depth));
     let mut errors = Vec::new();
     
     for (i, stmt) in statements.iter().enumerate() {
+        context.statement = i + 1;
+        context.source_line = stmt.line;
+        context.source_file = stmt.file.clone();
         if let Err(e) = validate_statement(stmt, i + 1, &mut context) {
             errors.push(e);
         }
@@ -299,6 +1083,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_allowed_globals() {
+        let read_math = Statement::Print(vec![Expression::MethodCall(
+            Box::new(Expression::Identifier("Math".to_string())),
+            "random".to_string(),
+            vec![],
+        )]);
+        
+        let result = validate_statement(&read_math, 1, &mut ValidationContext::new());
+        assert!(matches!(result, Err(ValidationError::UndefinedVariable(name, 1)) if name == "Math"));
+        
+        let mut context = ValidationContext::new().allowing_globals(["Math"]);
+        assert!(validate_statement(&read_math, 1, &mut context).is_ok());
+        
+        // Only the listed globals are allowed
+        let read_json = Statement::Print(vec![Expression::Identifier("JSON".to_string())]);
+        assert!(validate_statement(&read_json, 2, &mut context).is_err());
+    }
+
     #[test]
     fn test_validate_empty_identifier_error() {
         let stmt = Statement::Const("".to_string(), Expression::Number(42));
@@ -336,13 +1139,28 @@ mod tests {
     #[test]
     fn test_validate_duplicate_variable_error() {
         let statements = vec![
-            Statement::Let("x".to_string(), Expression::Number(1)),
-            Statement::Const("x".to_string(), Expression::Number(2)),
+            Statement::Let("x".to_string(), Expression::Number(1)).into(),
+            Statement::Const("x".to_string(), Expression::Number(2)).into(),
         ];
         let result = validate_program(&statements);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_duplicate_variable_reports_source_lines() {
+        let declare_x = |line| Spanned::new(Statement::Let("x".to_string(), Expression::Number(1)), line);
+        let statements = vec![
+            Spanned::new(Statement::Print(vec![Expression::Number(0)]), 1),
+            Spanned::new(Statement::If(Expression::Number(1), vec![declare_x(3), declare_x(5)], None), 2),
+        ];
+        
+        // Both declarations are in the block of the second statement, on lines of their own
+        let error = validate_program_detailed(&statements).unwrap_err().remove(0);
+        assert_eq!(error, ValidationError::DuplicateVariable { name: "x".to_string(), first_line: 3, first_file: None, second_line: 5, second_file: None, statement: 2 });
+        assert_eq!(error.statement(), 2);
+        assert!(error.to_string().contains("already declared at line 3, redeclared at line 5"));
+    }
+
     #[test]
     fn test_validate_undefined_variable_error() {
         let stmt = Statement::Print(vec![Expression::Identifier("undefined_var".to_string())]);
@@ -361,8 +1179,8 @@ mod tests {
     #[test]
     fn test_validate_valid_program() {
         let statements = vec![
-            Statement::Const("x".to_string(), Expression::Number(10)),
-            Statement::Let("y".to_string(), Expression::Number(5)),
+            Statement::Const("x".to_string(), Expression::Number(10)).into(),
+            Statement::Let("y".to_string(), Expression::Number(5)).into(),
             Statement::Print(vec![
                 Expression::String("sum".to_string()),
                 Expression::BinaryOp(
@@ -370,7 +1188,7 @@ mod tests {
                     "+".to_string(),
                     Box::new(Expression::Identifier("y".to_string()))
                 )
-            ])
+            ]).into()
         ];
         
         let result = validate_program(&statements);
@@ -390,9 +1208,10 @@ mod tests {
         let result = context.declare_variable("x", 2, DeclarationType::Const);
         assert!(result.is_err());
         
-        if let Err(ValidationError::DuplicateVariable(name, line)) = result {
-            assert_eq!(name, "x");
-            assert_eq!(line, 1);
+        if let Err(error @ ValidationError::DuplicateVariable { .. }) = result {
+            assert_eq!(error, ValidationError::DuplicateVariable { name: "x".to_string(), first_line: 1, first_file: None, second_line: 2, second_file: None, statement: 0 });
+            assert_eq!(error.line(), Some(2));
+            assert!(error.to_string().contains("already declared at line 1, redeclared at line 2"));
         } else {
             panic!("Expected DuplicateVariable error");
         }
@@ -409,7 +1228,7 @@ mod tests {
             "+".to_string(),
             Box::new(Expression::Number(5))
         );
-        assert!(validate_expression(&valid_expr, 1, &context).is_ok());
+        assert!(validate_expression(&valid_expr, 1, &mut context).is_ok());
         
         // Valid operator (now that * is supported)
         let valid_expr = Expression::BinaryOp(
@@ -417,7 +1236,7 @@ mod tests {
             "*".to_string(),
             Box::new(Expression::Number(2))
         );
-        assert!(validate_expression(&valid_expr, 1, &context).is_ok());
+        assert!(validate_expression(&valid_expr, 1, &mut context).is_ok());
         
         // Invalid operator
         let invalid_expr = Expression::BinaryOp(
@@ -425,7 +1244,7 @@ mod tests {
             "&".to_string(),
             Box::new(Expression::Number(2))
         );
-        let result = validate_expression(&invalid_expr, 1, &context);
+        let result = validate_expression(&invalid_expr, 1, &mut context);
         assert!(result.is_err());
         
         if let Err(ValidationError::InvalidExpression(_, msg)) = result {
@@ -435,13 +1254,507 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_multi_const_duplicate_error() {
+        let stmt = Statement::MultiConst(vec![
+            ("a".to_string(), Expression::Number(1)),
+            ("a".to_string(), Expression::Number(2)),
+        ]);
+        let mut context = ValidationContext::new();
+        let result = validate_statement(&stmt, 1, &mut context);
+        
+        assert!(matches!(result, Err(ValidationError::DuplicateVariable { .. })));
+    }
+
+    #[test]
+    fn test_validate_object_literal() {
+        let mut context = ValidationContext::new();
+        
+        let undefined_value = Expression::Object(vec![("count".to_string(), Expression::Identifier("n".to_string()))]);
+        let result = validate_expression(&undefined_value, 1, &mut context);
+        assert!(matches!(result, Err(ValidationError::UndefinedVariable(_, 1))));
+        
+        let duplicate_key = Expression::Object(vec![
+            ("a".to_string(), Expression::Number(1)),
+            ("a".to_string(), Expression::Number(2)),
+        ]);
+        let result = validate_expression(&duplicate_key, 2, &mut context);
+        assert!(matches!(result, Err(ValidationError::InvalidExpression(2, _))));
+    }
+
+    #[test]
+    fn test_validate_assignment_to_let() {
+        let statements = vec![
+            Statement::Let("x".to_string(), Expression::Number(1)).into(),
+            Statement::Assign("x".to_string(), Expression::Number(5)).into(),
+        ];
+        assert!(validate_program(&statements).is_ok());
+    }
+
+    #[test]
+    fn test_validate_assignment_to_undeclared_error() {
+        let stmt = Statement::Assign("x".to_string(), Expression::Number(5));
+        let mut context = ValidationContext::new();
+        let result = validate_statement(&stmt, 1, &mut context);
+        
+        if let Err(ValidationError::UndefinedVariable(name, line)) = result {
+            assert_eq!(name, "x");
+            assert_eq!(line, 1);
+        } else {
+            panic!("Expected UndefinedVariable error");
+        }
+    }
+
+    #[test]
+    fn test_validate_assignment_to_const_error() {
+        let mut context = ValidationContext::new();
+        context.declare_variable("x", 1, DeclarationType::Const).unwrap();
+        
+        let stmt = Statement::Assign("x".to_string(), Expression::Number(5));
+        let result = validate_statement(&stmt, 2, &mut context);
+        
+        if let Err(ValidationError::ConstAssignment(name, line)) = result {
+            assert_eq!(name, "x");
+            assert_eq!(line, 2);
+        } else {
+            panic!("Expected ConstAssignment error");
+        }
+    }
+
+    #[test]
+    fn test_for_loop_variable_scoped_to_loop() {
+        let counting_loop = |start| Statement::For(
+            Some(Box::new(Statement::Let("i".to_string(), Expression::Number(start)))),
+            Some(Expression::BinaryOp(
+                Box::new(Expression::Identifier("i".to_string())),
+                "<".to_string(),
+                Box::new(Expression::Number(10))
+            )),
+            Some(Box::new(Statement::Increment("i".to_string(), false))),
+            vec![Statement::Print(vec![Expression::Identifier("i".to_string())]).into()]
+        );
+        
+        let statements = vec![
+            counting_loop(0).into(),
+            counting_loop(5).into(),
+            Statement::Const("i".to_string(), Expression::Number(1)).into(),
+        ];
+        assert!(validate_program(&statements).is_ok());
+        
+        let statements = vec![
+            counting_loop(0).into(),
+            Statement::Print(vec![Expression::Identifier("i".to_string())]).into(),
+        ];
+        assert!(validate_program(&statements).unwrap_err().to_string().contains("is not defined"));
+    }
+
+    #[test]
+    fn test_division_by_literal_zero() {
+        let divide = |op: &str, divisor| vec![Statement::Print(vec![Expression::BinaryOp(
+            Box::new(Expression::Number(10)),
+            op.to_string(),
+            Box::new(divisor)
+        )]).into()];
+        
+        let zero = Expression::Grouping(Box::new(Expression::Number(0)));
+        assert_eq!(validate_program_detailed(&divide("/", zero)), Err(vec![ValidationError::DivisionByZero(1)]));
+        assert_eq!(validate_program_detailed(&divide("~/", Expression::Number(0))), Err(vec![ValidationError::DivisionByZero(1)]));
+        assert!(validate_program(&divide("*", Expression::Number(0))).is_ok());
+    }
+
+    #[test]
+    fn test_equality_requires_matching_types() {
+        let compare = |left, right| vec![
+            Statement::Const("x".to_string(), Expression::Number(1)).into(),
+            Statement::Print(vec![Expression::BinaryOp(Box::new(left), "==".to_string(), Box::new(right))]).into(),
+        ];
+        let is_positive = || Expression::BinaryOp(
+            Box::new(Expression::Identifier("x".to_string())),
+            ">".to_string(),
+            Box::new(Expression::Number(0))
+        );
+        
+        assert!(validate_program(&compare(is_positive(), is_positive())).is_ok());
+        assert!(validate_program(&compare(Expression::Identifier("x".to_string()), Expression::Number(5))).is_ok());
+        assert_eq!(
+            validate_program_detailed(&compare(Expression::Number(5), is_positive())),
+            Err(vec![ValidationError::TypeMismatch(2, "==".to_string(), ValueType::Number, ValueType::Boolean)])
+        );
+        assert!(validate_program(&compare(Expression::Identifier("x".to_string()), Expression::String("1".to_string()))).is_err());
+        assert!(validate_program(&compare(Expression::Identifier("x".to_string()), Expression::Float("2.5".to_string()))).is_ok());
+    }
+
+    #[test]
+    fn test_if_branch_declarations_are_separate_scopes() {
+        let print_x = || Statement::Print(vec![Expression::Identifier("x".to_string())]);
+        let declare_x = || Statement::Const("x".to_string(), Expression::Number(1));
+        
+        // Declared in the then-block, used in the else-block
+        let statements = vec![Statement::If(
+            Expression::Number(1),
+            vec![declare_x().into(), print_x().into()],
+            Some(vec![print_x().into()])
+        ).into()];
+        assert_eq!(validate_program_detailed(&statements), Err(vec![ValidationError::UndefinedVariable("x".to_string(), 1)]));
+        
+        // Declared in the then-block, used after the if
+        let statements = vec![
+            Statement::If(Expression::Number(1), vec![declare_x().into()], None).into(),
+            print_x().into(),
+        ];
+        assert_eq!(validate_program_detailed(&statements), Err(vec![ValidationError::UndefinedVariable("x".to_string(), 2)]));
+        
+        // Both branches may declare the same name, and so may the code after them
+        let statements = vec![
+            Statement::If(Expression::Number(1), vec![declare_x().into()], Some(vec![declare_x().into()])).into(),
+            declare_x().into(),
+            print_x().into(),
+        ];
+        assert!(validate_program(&statements).is_ok());
+    }
+
+    #[test]
+    fn test_validate_break_targets() {
+        let labeled_loop = |label: &str, body: Vec<Spanned<Statement>>| Statement::Labeled(
+            label.to_string(),
+            Box::new(Statement::While(Expression::Number(1), body))
+        );
+        let break_to = |label: &str| Statement::Break(Some(label.to_string()));
+        
+        let nested = labeled_loop("outer", vec![
+            Statement::While(Expression::Number(1), vec![break_to("outer").into()]).into()
+        ]);
+        assert!(validate_statement(&nested, 1, &mut ValidationContext::new()).is_ok());
+        
+        let result = validate_statement(&Statement::Break(None), 2, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::BreakOutsideLoop(2)));
+        
+        let wrong_label = labeled_loop("outer", vec![break_to("inner").into()]);
+        let result = validate_statement(&wrong_label, 3, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::UndefinedLabel("inner".to_string(), 3)));
+        
+        let reused = labeled_loop("outer", vec![labeled_loop("outer", vec![break_to("outer").into()]).into()]);
+        let result = validate_statement(&reused, 4, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::DuplicateLabel("outer".to_string(), 4)));
+    }
+
+    #[test]
+    fn test_validate_lambda_scope() {
+        let ident = |name: &str| Expression::Identifier(name.to_string());
+        let lambda = |params: &[&str], body: Vec<Statement>| Expression::Lambda(
+            params.iter().map(|param| param.to_string()).collect(),
+            body.into_iter().map(Spanned::from).collect(),
+        );
+        
+        // Parameters may shadow outer variables and are only visible in the body
+        let statements = vec![
+            Statement::Const("x".to_string(), Expression::Number(1)).into(),
+            Statement::Const("f".to_string(), lambda(&["x"], vec![Statement::Return(Some(ident("x")))])).into(),
+            Statement::Print(vec![Expression::Call(Box::new(ident("f")), vec![ident("x")])]).into(),
+        ];
+        assert!(validate_program(&statements).is_ok());
+        
+        let leaked = vec![
+            Statement::Const("f".to_string(), lambda(&["y"], vec![Statement::Return(None)])).into(),
+            Statement::Print(vec![ident("y")]).into(),
+        ];
+        assert!(validate_program(&leaked).is_err());
+        
+        let duplicate = Statement::Expr(lambda(&["a", "a"], vec![]));
+        let result = validate_statement(&duplicate, 1, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::DuplicateVariable { name: "a".to_string(), first_line: 0, first_file: None, second_line: 0, second_file: None, statement: 1 }));
+        
+        let result = validate_statement(&Statement::Return(None), 2, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::ReturnOutsideFunction(2)));
+        
+        // A loop around a function can't be exited from inside it
+        let escape = Statement::While(Expression::Number(1), vec![
+            Statement::Expr(lambda(&[], vec![Statement::Break(None)])).into(),
+        ]);
+        let result = validate_statement(&escape, 3, &mut ValidationContext::new());
+        assert_eq!(result, Err(ValidationError::BreakOutsideLoop(3)));
+    }
+
+    #[test]
+    fn test_unreachable_code_warning() {
+        let function = |body: Vec<Statement>| -> Vec<Spanned<Statement>> {
+            let lambda = Expression::Lambda(vec![], body.into_iter().map(Spanned::from).collect());
+            vec![Statement::Const("f".to_string(), lambda).into()]
+        };
+        let print = || Statement::Print(vec![Expression::Number(1)]);
+        
+        let warnings = validate_program_with_warnings(&function(vec![Statement::Return(None), print()])).unwrap();
+        assert!(warnings.contains(&Warning::UnreachableCode(1)));
+        
+        let warnings = validate_program_with_warnings(&function(vec![print(), Statement::Return(None)])).unwrap();
+        assert!(!warnings.contains(&Warning::UnreachableCode(1)));
+        
+        // A trailing comment isn't code, so it doesn't count as unreachable
+        let looped = vec![Statement::While(Expression::Number(1), vec![
+            Statement::Break(None).into(),
+            Statement::Comment("done".to_string()).into(),
+        ]).into()];
+        let warnings = validate_program_with_warnings(&looped).unwrap();
+        assert!(!warnings.contains(&Warning::UnreachableCode(1)));
+    }
+
+    #[test]
+    fn test_validate_increment() {
+        let mut context = ValidationContext::new();
+        
+        let result = validate_statement(&Statement::Increment("i".to_string(), false), 1, &mut context);
+        assert!(matches!(result, Err(ValidationError::UndefinedVariable(_, 1))));
+        
+        context.declare_variable("i", 1, DeclarationType::Let).unwrap();
+        context.declare_variable("n", 2, DeclarationType::Const).unwrap();
+        assert!(validate_statement(&Statement::Increment("i".to_string(), true), 3, &mut context).is_ok());
+        
+        let result = validate_statement(&Statement::Increment("n".to_string(), false), 4, &mut context);
+        assert!(matches!(result, Err(ValidationError::ConstAssignment(_, 4))));
+    }
+
+    #[test]
+    fn test_validate_comment_only_block_error() {
+        let stmt = Statement::While(
+            Expression::Number(1),
+            vec![Statement::Comment("todo".to_string()).into()]
+        );
+        let mut context = ValidationContext::new();
+        let result = validate_statement(&stmt, 1, &mut context);
+        assert_eq!(result, Err(ValidationError::EmptyBlock(1, "pokiri".to_string())));
+    }
+
+    #[test]
+    fn test_identical_branches_warning() {
+        let branch = vec![Statement::Print(vec![Expression::String("same".to_string())]).into()];
+        let statements = vec![
+            Statement::If(Expression::Number(1), branch.clone(), Some(branch)).into(),
+        ];
+        
+        let warnings = validate_program_with_warnings(&statements).unwrap();
+        assert_eq!(warnings, vec![Warning::IdenticalBranches(1)]);
+    }
+
+    #[test]
+    fn test_different_branches_no_warning() {
+        let statements = vec![
+            Statement::If(
+                Expression::Number(1),
+                vec![Statement::Print(vec![Expression::String("yes".to_string())]).into()],
+                Some(vec![Statement::Print(vec![Expression::String("no".to_string())]).into()])
+            ).into(),
+        ];
+        
+        let warnings = validate_program_with_warnings(&statements).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_comparison_variable_is_boolean_condition() {
+        let print = || vec![Statement::Print(vec![Expression::Identifier("x".to_string())]).into()];
+        let statements = vec![
+            Statement::Const("x".to_string(), Expression::Number(10)).into(),
+            Statement::Const("big".to_string(), Expression::BinaryOp(
+                Box::new(Expression::Identifier("x".to_string())),
+                ">".to_string(),
+                Box::new(Expression::Number(5))
+            )).into(),
+            Statement::If(Expression::Identifier("big".to_string()), print(), None).into(),
+            Statement::If(Expression::Identifier("x".to_string()), print(), None).into(),
+        ];
+        
+        let warnings = validate_program_with_warnings(&statements).unwrap();
+        assert_eq!(warnings, vec![Warning::NonBooleanCondition(4, "magadheera".to_string(), ValueType::Number)]);
+    }
+
+    #[test]
+    fn test_prefer_const_warning() {
+        let statements = vec![
+            Statement::Let("fixed".to_string(), Expression::Number(1)).into(),
+            Statement::Let("count".to_string(), Expression::Number(0)).into(),
+            Statement::While(
+                Expression::Identifier("count".to_string()),
+                vec![Statement::Increment("count".to_string(), false).into()]
+            ).into(),
+        ];
+        
+        let warnings = validate_program_with_warnings(&statements).unwrap();
+        assert!(warnings.contains(&Warning::PreferConst(1, "fixed".to_string())));
+        assert!(!warnings.iter().any(|w| matches!(w, Warning::PreferConst(_, name) if name == "count")));
+    }
+
+    #[test]
+    fn test_type_changed_warning() {
+        let statements = vec![
+            Statement::Let("count".to_string(), Expression::Number(1)).into(),
+            Statement::Assign("count".to_string(), Expression::Number(2)).into(),
+            Statement::Assign("count".to_string(), Expression::String("many".to_string())).into(),
+        ];
+        
+        let warnings = validate_program_with_warnings(&statements).unwrap();
+        assert_eq!(warnings, vec![Warning::TypeChanged {
+            name: "count".to_string(),
+            from: ValueType::Number,
+            to: ValueType::String,
+            line: 3,
+        }]);
+    }
+
+    #[test]
+    fn test_ineffective_for_update_warning() {
+        let counting_loop = |update: Statement| -> Vec<Spanned<Statement>> {
+            vec![Statement::For(
+                Some(Box::new(Statement::Let("i".to_string(), Expression::Number(0)))),
+                Some(Expression::BinaryOp(
+                    Box::new(Expression::Identifier("i".to_string())),
+                    "<".to_string(),
+                    Box::new(Expression::Number(3))
+                )),
+                Some(Box::new(update)),
+                vec![Statement::Print(vec![Expression::Identifier("i".to_string())]).into()]
+            ).into()]
+        };
+        
+        let plus_one = Statement::Expr(Expression::BinaryOp(
+            Box::new(Expression::Identifier("i".to_string())),
+            "+".to_string(),
+            Box::new(Expression::Number(1))
+        ));
+        let warnings = validate_program_with_warnings(&counting_loop(plus_one)).unwrap();
+        assert!(warnings.contains(&Warning::IneffectiveForUpdate(1)));
+        
+        let increment = Statement::Increment("i".to_string(), false);
+        let warnings = validate_program_with_warnings(&counting_loop(increment)).unwrap();
+        assert!(!warnings.contains(&Warning::IneffectiveForUpdate(1)));
+    }
+
+    #[test]
+    fn test_loop_variable_not_in_condition_warning() {
+        let counting_loop = |tested: &str| -> Vec<Spanned<Statement>> {
+            vec![
+                Statement::Const("j".to_string(), Expression::Number(5)).into(),
+                Statement::For(
+                    Some(Box::new(Statement::Let("i".to_string(), Expression::Number(0)))),
+                    Some(Expression::BinaryOp(
+                        Box::new(Expression::Identifier(tested.to_string())),
+                        "<".to_string(),
+                        Box::new(Expression::Number(5))
+                    )),
+                    Some(Box::new(Statement::Increment("i".to_string(), false))),
+                    vec![Statement::Print(vec![Expression::Identifier("i".to_string())]).into()]
+                ).into(),
+            ]
+        };
+        
+        let warnings = validate_program_with_warnings(&counting_loop("j")).unwrap();
+        assert!(warnings.contains(&Warning::LoopVariableNotInCondition(2, "i".to_string())));
+        
+        let warnings = validate_program_with_warnings(&counting_loop("i")).unwrap();
+        assert!(!warnings.iter().any(|w| matches!(w, Warning::LoopVariableNotInCondition(_, _))));
+    }
+
+    #[test]
+    fn test_method_call_validation() {
+        let call = |method: &str| Statement::Print(vec![Expression::MethodCall(
+            Box::new(Expression::Identifier("name".to_string())),
+            method.to_string(),
+            vec![]
+        )]);
+        
+        let declared = Statement::Const("name".to_string(), Expression::String("tfi".to_string()));
+        let warnings = validate_program_with_warnings(&[declared.clone().into(), call("upper").into()]).unwrap();
+        assert!(warnings.is_empty());
+        
+        let warnings = validate_program_with_warnings(&[declared.into(), call("shout").into()]).unwrap();
+        assert_eq!(warnings, vec![Warning::UnknownMethod(2, "shout".to_string())]);
+        
+        // The base must still be declared
+        assert!(validate_program(&[call("upper").into()]).is_err());
+    }
+
+    #[test]
+    fn test_negative_index_validation() {
+        let name = || Box::new(Expression::Identifier("name".to_string()));
+        let program = [
+            Statement::Const("name".to_string(), Expression::String("tfi".to_string())).into(),
+            Statement::Print(vec![
+                Expression::IndexFromEnd(name(), 1),
+                Expression::Slice(name(), Box::new(Expression::Number(0)), Box::new(Expression::Number(-1))),
+            ]).into(),
+        ];
+        
+        assert!(validate_program_with_warnings(&program).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_malformed_binary_op_error() {
+        let mut context = ValidationContext::new();
+        let expr = Expression::BinaryOp(
+            Box::new(Expression::Number(1)),
+            String::new(),
+            Box::new(Expression::Number(2))
+        );
+        let stmt = Statement::Print(vec![expr]);
+        
+        let result = validate_statement(&stmt, 1, &mut context);
+        assert!(matches!(result, Err(ValidationError::InvalidExpression(1, _))));
+    }
+
+    #[test]
+    fn test_degenerate_operand_error() {
+        let mut context = ValidationContext::new();
+        let expr = Expression::BinaryOp(
+            Box::new(Expression::Number(1)),
+            "+".to_string(),
+            Box::new(Expression::Identifier(String::new()))
+        );
+        let stmt = Statement::Print(vec![expr]);
+        
+        let result = validate_statement(&stmt, 1, &mut context);
+        assert!(matches!(result, Err(ValidationError::InvalidExpression(1, _))));
+        
+        let bad_float = Statement::Print(vec![Expression::Float("1.5.5".to_string())]);
+        assert!(validate_statement(&bad_float, 1, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_deeply_nested_expression_error() {
+        let mut expr = Expression::Number(1);
+        for _ in 0..50 {
+            expr = Expression::Grouping(Box::new(expr));
+        }
+        let statements = vec![Statement::Print(vec![expr]).into()];
+        
+        assert!(validate_program_with_depth_limit(&statements, 100).is_ok());
+        
+        let error = validate_program_with_depth_limit(&statements, 10).unwrap_err();
+        assert!(error.to_string().contains("too deeply nested"));
+    }
+
+    #[test]
+    fn test_long_operator_chain_error() {
+        // Built directly, since the parser's own nesting limit would reject the source
+        let mut expr = Expression::Number(1);
+        for _ in 0..50_000 {
+            expr = Expression::BinaryOp(Box::new(expr), "+".to_string(), Box::new(Expression::Number(1)));
+        }
+        let statements = vec![Statement::Const("total".to_string(), expr).into()];
+        
+        // Inferring the declared type runs first and must stop at the same limit
+        let error = validate_program_with_depth_limit(&statements, 100).unwrap_err();
+        assert!(error.to_string().contains("too deeply nested"));
+        
+        // Dropping the boxed chain recurses once per level, which would overflow the stack itself
+        std::mem::forget(statements);
+    }
+
     #[test]
     fn test_validate_detailed() {
         let statements = vec![
-            Statement::Print(vec![]), // Error 1
-            Statement::Const("x".to_string(), Expression::Number(1)),
-            Statement::Const("x".to_string(), Expression::Number(2)), // Error 2
-            Statement::Print(vec![Expression::Identifier("undefined".to_string())]), // Error 3
+            Statement::Print(vec![]).into(), // Error 1
+            Statement::Const("x".to_string(), Expression::Number(1)).into(),
+            Statement::Const("x".to_string(), Expression::Number(2)).into(), // Error 2
+            Statement::Print(vec![Expression::Identifier("undefined".to_string())]).into(), // Error 3
         ];
         
         let result = validate_program_detailed(&statements);
@@ -454,7 +1767,7 @@ mod tests {
             let error_types: Vec<&str> = errors.iter()
                 .map(|e| match e {
                     ValidationError::EmptyPrintStatement(_) => "EmptyPrintStatement",
-                    ValidationError::DuplicateVariable(_, _) => "DuplicateVariable",
+                    ValidationError::DuplicateVariable { .. } => "DuplicateVariable",
                     ValidationError::UndefinedVariable(_, _) => "UndefinedVariable",
                     _ => "Other",
                 })
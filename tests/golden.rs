@@ -0,0 +1,100 @@
+//! Golden-file tests: each `tests/fixtures/*.tfi` must compile to its committed `.js.golden`
+//!
+//! Run with `TFI_UPDATE_GOLDEN=1` to rewrite the goldens from the current compiler output.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+
+/// Environment variable that regenerates the goldens instead of comparing against them
+const UPDATE_ENV: &str = "TFI_UPDATE_GOLDEN";
+
+/// Every `.tfi` fixture, sorted so failures are reported in a stable order
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "tfi"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Line diff of `expected` against `actual`, marking removed lines with `-` and added ones with `+`
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[test]
+fn test_golden_files() {
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "no fixtures found in tests/fixtures");
+    
+    let mut failures = Vec::new();
+    for fixture in &fixtures {
+        let source = fs::read_to_string(fixture).unwrap();
+        let options = CompilationOptions::new().with_source_path(fixture);
+        let actual = match compile_with_options(&source, &options) {
+            Ok(result) => result.file_contents(),
+            Err(e) => {
+                failures.push(format!("{} failed to compile: {}", fixture.display(), e));
+                continue;
+            }
+        };
+        
+        let golden = fixture.with_extension("js.golden");
+        if update {
+            fs::write(&golden, &actual).unwrap();
+            continue;
+        }
+        
+        match fs::read_to_string(&golden) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{} does not match {} (- golden, + actual):\n{}",
+                fixture.display(),
+                golden.display(),
+                diff(&expected, &actual)
+            )),
+            Err(_) => failures.push(format!(
+                "{} has no golden file; run with {}=1 to create it",
+                fixture.display(),
+                UPDATE_ENV
+            )),
+        }
+    }
+    
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
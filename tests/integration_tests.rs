@@ -1,5 +1,85 @@
 use tfi_lang::*;
 
+/// Run generated JavaScript with node and return its stdout, or None if node is unavailable
+fn run_js(name: &str, js_code: &str) -> Option<String> {
+    let path = std::env::temp_dir().join(format!("tfi_lang_{}.js", name));
+    std::fs::write(&path, js_code).unwrap();
+    let output = std::process::Command::new("node").arg(&path).output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Operator precedence and associativity, checked on the parsed tree and on the value node prints
+mod precedence {
+    use super::*;
+    
+    fn num(n: i32) -> Expression {
+        Expression::Number(n)
+    }
+    
+    fn op(left: Expression, op: &str, right: Expression) -> Expression {
+        Expression::BinaryOp(Box::new(left), op.to_string(), Box::new(right))
+    }
+    
+    /// Assert the tree `source` parses to, and the output node prints for it if node is available
+    fn check(name: &str, source: &str, expected: Expression, printed: &str) {
+        let statements = parse_program(&format!("bahubali({});", source)).unwrap();
+        assert_eq!(statements[0].node, Statement::Print(vec![expected]), "{}", source);
+        
+        let js_code = compile_tfi_to_js(&format!("bahubali({});", source)).unwrap();
+        if let Some(stdout) = run_js(&format!("precedence_{}", name), &js_code) {
+            assert_eq!(stdout, format!("{}\n", printed), "{}", source);
+        }
+    }
+    
+    #[test]
+    fn test_subtraction_is_left_associative() {
+        check("subtraction", "10 - 3 - 2", op(op(num(10), "-", num(3)), "-", num(2)), "5");
+    }
+    
+    #[test]
+    fn test_division_is_left_associative() {
+        check("division", "64 / 8 / 2", op(op(num(64), "/", num(8)), "/", num(2)), "4");
+        check("int_division", "20 ~/ 3 ~/ 2", op(op(num(20), "~/", num(3)), "~/", num(2)), "3");
+    }
+    
+    #[test]
+    fn test_multiplication_binds_tighter_than_addition() {
+        check(
+            "mixed",
+            "2 + 3 * 4 - 1",
+            op(op(num(2), "+", op(num(3), "*", num(4))), "-", num(1)),
+            "13"
+        );
+        check(
+            "mixed_division",
+            "20 - 12 / 4 * 2",
+            op(num(20), "-", op(op(num(12), "/", num(4)), "*", num(2))),
+            "14"
+        );
+    }
+    
+    #[test]
+    fn test_comparison_binds_looser_than_arithmetic() {
+        check("comparison", "1 + 2 < 2 * 2", op(op(num(1), "+", num(2)), "<", op(num(2), "*", num(2))), "true");
+        check(
+            "equality",
+            "1 < 2 == 3 < 4",
+            op(op(num(1), "<", num(2)), "==", op(num(3), "<", num(4))),
+            "true"
+        );
+    }
+    
+    #[test]
+    fn test_grouping_overrides_precedence() {
+        check(
+            "grouping",
+            "(2 + 3) * 4",
+            op(Expression::Grouping(Box::new(op(num(2), "+", num(3)))), "*", num(4)),
+            "20"
+        );
+    }
+}
+
 #[test]
 fn test_basic_compilation_workflow() {
     let source = r#"
@@ -74,6 +154,107 @@ fn test_for_loop_compilation() {
     assert!(js_code.contains("(i < 5)"));
 }
 
+#[test]
+fn test_for_loop_reusing_declared_variable() {
+    let source = r#"
+        pushpa i = 10;
+        eega(i = 0; i < 3; i++) {
+            bahubali(i);
+        }
+        bahubali(i);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("for (i = 0; (i < 3); i++)"));
+    if let Some(stdout) = run_js("for_reuse", &js_code) {
+        assert_eq!(stdout, "0\n1\n2\n3\n");
+    }
+    
+    // The init assigns, so the variable must already exist and be mutable
+    assert!(compile_tfi_to_js("eega(i = 0; i < 3; i++) { bahubali(i); }").is_err());
+    assert!(compile_tfi_to_js("rrr i = 0; eega(i = 0; i < 3; i++) { bahubali(i); }").is_err());
+}
+
+#[test]
+fn test_for_loop_with_empty_clauses() {
+    let source = r#"
+        pushpa i = 0;
+        eega(;;) {
+            magadheera(i == 2) {
+                aagipo;
+            }
+            i++;
+        }
+        eega(; i < 4;) {
+            bahubali(i);
+            i++;
+        }
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("for (;;)"));
+    assert!(js_code.contains("for (; (i < 4);)"));
+    if let Some(stdout) = run_js("for_empty_clauses", &js_code) {
+        assert_eq!(stdout, "2\n3\n");
+    }
+    
+    let error = compile_tfi_to_js("eega(;;) { }").unwrap_err();
+    assert!(error.to_string().contains("eega"));
+}
+
+#[test]
+fn test_conditional_expression_in_print() {
+    let source = r#"
+        rrr x = 5;
+        bahubali(x > 0 ? "pos" : "neg", x < 3 ? "small" : "big");
+        bahubali((x == 5 ? 1 : 2) + 1, sep="-");
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains(r#"((x > 0) ? "pos" : "neg")"#));
+    if let Some(stdout) = run_js("conditional_print", &js_code) {
+        assert_eq!(stdout, "pos big\n2\n");
+    }
+}
+
+#[test]
+fn test_compile_and_run_reports_runtime_errors() {
+    use tfi_lang::compiler::CompilationOptions;
+    use tfi_lang::runner::{compile_and_run, RuntimeError};
+    
+    // Node is optional for the test suite
+    if std::process::Command::new("node").arg("--version").output().is_err() {
+        return;
+    }
+    
+    let options = CompilationOptions::new();
+    assert_eq!(compile_and_run("bahubali(1 + 2);", &options).unwrap(), "3\n");
+    
+    let error = compile_and_run("bahubali(\"before\"); nammaka(1 == 2);", &options).unwrap_err();
+    let runtime_error = error.downcast_ref::<RuntimeError>().expect("a runtime error, not a compile error");
+    assert_eq!(runtime_error.status, Some(1));
+    assert!(runtime_error.stderr.contains("assertion failed: 1 == 2"));
+    assert!(error.to_string().contains("Program exited with error"));
+}
+
+#[test]
+fn test_loop_guard_stops_runaway_loops() {
+    use tfi_lang::compiler::CompilationOptions;
+    use tfi_lang::runner::{compile_and_run, RuntimeError};
+    
+    if std::process::Command::new("node").arg("--version").output().is_err() {
+        return;
+    }
+    
+    let options = CompilationOptions::new().with_loop_guard(100);
+    let counted = "eega i in 0..3 {\n    pushpa j = 0;\n    pokiri(j < 2) {\n        bahubali(i, j);\n        j = j + 1;\n    }\n}";
+    assert_eq!(compile_and_run(counted, &options).unwrap(), "0 0\n0 1\n1 0\n1 1\n2 0\n2 1\n");
+    
+    let error = compile_and_run("pushpa i = 0;\npokiri(i < 10) {\n    bahubali(i);\n}", &options).unwrap_err();
+    let runtime_error = error.downcast_ref::<RuntimeError>().expect("a runtime error, not a compile error");
+    assert!(runtime_error.stderr.contains("loop ran more than 100 iterations"));
+}
+
 #[test]
 fn test_nested_control_structures() {
     let source = r#"
@@ -112,6 +293,149 @@ fn test_complex_expressions() {
     assert!(js_code.contains("const c = 3"));
 }
 
+#[test]
+fn test_division_semantics() {
+    let source = r#"
+        bahubali(5 ~/ 2);
+        bahubali(5 / 2);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.starts_with("function __tfi_floor_div(a, b) { return Math.floor(a / b); }\n"));
+    assert!(js_code.contains("__tfi_floor_div(5, 2)"));
+    assert!(js_code.contains("(5 / 2)"));
+    
+    if let Some(stdout) = run_js("division_semantics", &js_code) {
+        assert_eq!(stdout, "2\n2.5\n");
+    }
+}
+
+#[test]
+fn test_clear_statement_compilation() {
+    let source = r#"
+        bahubali("before");
+        wipe();
+        bahubali("after");
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert_eq!(js_code, "console.log(\"before\");\nconsole.clear();\nconsole.log(\"after\");");
+}
+
+#[test]
+fn test_print_inline_compilation() {
+    let source = r#"
+        salaar("Loading");
+        salaar(".", ".", ".");
+        bahubali(" done");
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("process.stdout.write(String(\"Loading\"));"));
+    
+    if let Some(stdout) = run_js("print_inline", &js_code) {
+        assert_eq!(stdout, "Loading... done\n");
+    }
+}
+
+#[test]
+fn test_single_quote_style_compilation() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    use tfi_lang::generator::QuoteStyle;
+    
+    let source = r#"bahubali("it's", "fine");"#;
+    let options = CompilationOptions::new().with_quote_style(QuoteStyle::Single);
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert_eq!(js_code, r"console.log('it\'s', 'fine');");
+    
+    if let Some(stdout) = run_js("single_quotes", &js_code) {
+        assert_eq!(stdout, "it's fine\n");
+    }
+}
+
+#[test]
+fn test_return_string_output_mode() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    use tfi_lang::generator::OutputMode;
+    
+    let source = r#"
+        rrr name = "world";
+        bahubali("hello", name);
+        salaar("no newline");
+        bahubali(1, 2, sep=", ", end="!");
+        eega i in 0..2 {
+            bahubali(i);
+        }
+    "#;
+    let options = CompilationOptions::new().with_output_mode(OutputMode::ReturnString);
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.starts_with("function run() {"));
+    assert!(!js_code.contains("console.log") && !js_code.contains("process.stdout"));
+    
+    // Calling the function produces the text without anything reaching stdout
+    let host = format!("{}\nconst output = run();\nconsole.log(JSON.stringify(output));", js_code);
+    if let Some(stdout) = run_js("return_string", &host) {
+        assert_eq!(stdout, "\"hello world\\nno newline1, 2!0\\n1\\n\"\n");
+    }
+}
+
+#[test]
+fn test_minified_parentheses() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr x = 4;
+        bahubali((1 + 2) * 3);
+        bahubali(10 - (4 - 3), (10 - 4) - 3, x * (x + 1) / 2);
+        bahubali(("ab" + "c").upper(), x > 1 == (x < 10));
+    "#;
+    let options = CompilationOptions::new().with_minification();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.contains("console.log((1+2)*3);"));
+    assert!(js_code.contains("console.log(10-(4-3), 10-4-3, x*(x+1)/2);"));
+    assert!(js_code.contains(r#"("ab"+"c").toUpperCase()"#));
+    
+    // Dropping the parentheses must not change what the program prints
+    if let Some(stdout) = run_js("minified_parentheses", &js_code) {
+        assert_eq!(Some(stdout), run_js("unminified_parentheses", &compile_tfi_to_js(source).unwrap()));
+    }
+}
+
+#[test]
+fn test_no_semicolons_compilation() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr x = 5;
+        pushpa total = 0;
+        total += x;
+        bahubali(total);
+    "#;
+    let options = CompilationOptions::new().with_strict_mode().with_omitted_semicolons();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert_eq!(js_code, "\"use strict\"\nconst x = 5\nlet total = 0\ntotal = (total + x)\nconsole.log(total)");
+    
+    if let Some(stdout) = run_js("no_semicolons", &js_code) {
+        assert_eq!(stdout, "5\n");
+    }
+}
+
+#[test]
+fn test_float_literals() {
+    let source = r#"
+        bahubali(1.5e3);
+        bahubali(2e3);
+        bahubali(1.5E-2);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert_eq!(js_code, "console.log(1.5e3);\nconsole.log(2e3);\nconsole.log(1.5E-2);");
+    
+    if let Some(stdout) = run_js("float_literals", &js_code) {
+        assert_eq!(stdout, "1500\n2000\n0.015\n");
+    }
+}
+
 #[test]
 fn test_string_literals() {
     let source = r#"
@@ -136,6 +460,182 @@ fn test_error_handling_empty_print() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_empty_print_allowed_with_option() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let default_error = compile_tfi_to_js("bahubali();").unwrap_err();
+    assert!(default_error.to_string().contains("E0004"));
+    
+    let options = CompilationOptions::new().with_empty_print();
+    let result = compile_with_options("bahubali(1);\nbahubali();\nbahubali(2);", &options).unwrap();
+    assert!(result.js_code.contains("console.log();"));
+    
+    if let Some(stdout) = run_js("empty_print", &result.js_code) {
+        assert_eq!(stdout, "1\n\n2\n");
+    }
+    
+    // salaar() has nothing to print even as a blank line
+    assert!(compile_with_options("salaar();", &options).is_err());
+}
+
+#[test]
+fn test_lambda_called_through_variable() {
+    let source = r#"
+        rrr add = fn(a, b) {
+            vaapas a + b;
+        };
+        rrr fact = fn(n) {
+            magadheera(n < 2) {
+                vaapas 1;
+            }
+            vaapas n * fact(n - 1);
+        };
+        pushpa calls = 0;
+        rrr count = fn() { calls += 1; };
+        count();
+        count();
+        bahubali(add(2, 3), fact(5), fn(x) { vaapas x * 2; }(21), calls);
+    "#;
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("const add = (a, b) => {"));
+    assert!(js_code.contains("return (a + b);"));
+    
+    if let Some(stdout) = run_js("lambda", &js_code) {
+        assert_eq!(stdout, "5 120 42 2\n");
+    }
+    
+    let error = compile_tfi_to_js("vaapas 1;").unwrap_err();
+    assert!(error.to_string().contains("E0015"));
+}
+
+#[test]
+fn test_lint_reports_every_warning() {
+    use tfi_lang::compiler::CompilationOptions;
+    use tfi_lang::diagnostics::{lint, Severity};
+    
+    let source = "pushpa greeting = \"hi\";\nmagadheera(1 > 0) {\n    bahubali(greeting);\n} karthikeya {\n    bahubali(greeting);\n}\n";
+    let report = lint(source, &CompilationOptions::new());
+    
+    assert!(!report.has_errors());
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings.iter().all(|warning| warning.severity == Severity::Warning));
+    assert!(report.warnings[0].message.contains("prefer 'rrr greeting'"));
+    assert_eq!(report.warnings[0].range.start.line, 0);
+    assert!(report.warnings[1].message.contains("blocks are identical"));
+    assert_eq!(report.warnings[1].range.start.line, 1);
+    
+    let text = report.summary("prog.tfi");
+    assert!(text.starts_with("prog.tfi: 0 errors, 2 warnings"));
+    assert!(text.contains("prog.tfi:1:1: 'greeting' is never reassigned"));
+    assert!(text.contains("prog.tfi:2:1: magadheera and karthikeya blocks are identical"));
+    
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["errors"].as_array().unwrap().len(), 0);
+    assert_eq!(json["warnings"][1]["severity"], "warning");
+    
+    // An error is reported with its code, and no JavaScript is needed to find it
+    let report = lint("bahubali(y);", &CompilationOptions::new());
+    assert_eq!(report.errors[0].code, Some("E0001"));
+}
+
+#[test]
+fn test_print_sep_and_end() {
+    let source = "bahubali(\"a\", \"b\", 3, sep=\"-\", end=\"!\\n\");\nbahubali(1, 2, sep=\", \");\nbahubali(\"x\", end=\"\");\nbahubali(\"y\");";
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("process.stdout.write([\"a\", \"b\", 3].join(\"-\") + \"!\\n\");"));
+    
+    if let Some(stdout) = run_js("print_sep_end", &js_code) {
+        assert_eq!(stdout, "a-b-3!\n1, 2\nxy\n");
+    }
+}
+
+#[test]
+fn test_assert_statement() {
+    let source = |value: i32| format!("rrr total = {};\nnammaka(total == 5);\nbahubali(\"after\");", value);
+    
+    let passing = compile_tfi_to_js(&source(5)).unwrap();
+    assert!(passing.contains("if (!(total == 5)) throw new Error(\"assertion failed: total == 5\");"));
+    if let Some(stdout) = run_js("assert_passing", &passing) {
+        assert_eq!(stdout, "after\n");
+    }
+    
+    // A failing assertion throws, so nothing after it runs
+    let failing = compile_tfi_to_js(&source(4)).unwrap();
+    let path = std::env::temp_dir().join("tfi_lang_assert_failing.js");
+    std::fs::write(&path, &failing).unwrap();
+    if let Ok(output) = std::process::Command::new("node").arg(&path).output() {
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("assertion failed: total == 5"));
+    }
+}
+
+#[test]
+fn test_allowed_globals() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = "rrr roll = Math.floor(Math.random() * 6);\nbahubali(JSON.stringify(roll >= 0));";
+    let default_error = compile_tfi_to_js(source).unwrap_err();
+    assert!(default_error.to_string().contains("Math"));
+    
+    // Allowing one global does not allow the others
+    let only_math = CompilationOptions::new().with_allowed_global("Math");
+    assert!(compile_with_options(source, &only_math).unwrap_err().to_string().contains("JSON"));
+    
+    let options = only_math.with_allowed_global("JSON");
+    let result = compile_with_options(source, &options).unwrap();
+    assert!(result.js_code.contains("Math.floor((Math.random() * 6))"));
+    
+    if let Some(stdout) = run_js("allowed_globals", &result.js_code) {
+        assert_eq!(stdout, "true\n");
+    }
+    
+    // Reading a global is allowed, replacing it is not
+    assert!(compile_with_options("Math = 1;", &options).is_err());
+}
+
+#[test]
+fn test_folded_division_by_zero() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = "bahubali(10 / (5 - 5));";
+    
+    // Without folding the divisor is only known at runtime, where JavaScript yields Infinity
+    let js_code = compile_tfi_to_js(source).unwrap();
+    if let Some(stdout) = run_js("folded_division", &js_code) {
+        assert_eq!(stdout, "Infinity\n");
+    }
+    
+    let error = compile_with_options(source, &CompilationOptions::new().with_constant_folding()).unwrap_err();
+    assert!(error.to_string().contains("E0012"));
+    
+    // A literal zero is caught either way
+    assert!(compile_tfi_to_js("bahubali(10 / 0);").unwrap_err().to_string().contains("E0012"));
+}
+
+#[test]
+fn test_folded_constants_keep_names() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr width = 5, height = 10;
+        rrr area = width * height;
+        bahubali(area, 2 * 3);
+    "#;
+    let options = CompilationOptions::new().with_constant_folding().with_kept_names();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.contains("const area = 50 /* width * height */;"), "got: {}", js_code);
+    assert!(js_code.contains("console.log(area, 6);"));
+    
+    if let Some(stdout) = run_js("keep_names", &js_code) {
+        assert_eq!(stdout, "50 6\n");
+    }
+    
+    let plain = compile_with_options(source, &CompilationOptions::new().with_constant_folding()).unwrap().js_code;
+    assert!(plain.contains("const area = 50;"));
+}
+
 #[test]
 fn test_error_handling_empty_if_block() {
     let source = r#"
@@ -153,6 +653,469 @@ fn test_error_handling_invalid_syntax() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_assignment_compilation() {
+    let source = r#"
+        pushpa count = 0;
+        pokiri(count < 3) {
+            bahubali(count);
+            count = count + 1;
+        }
+    "#;
+    
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_ok());
+    
+    let js_code = result.unwrap();
+    assert!(js_code.contains("let count = 0;"));
+    assert!(js_code.contains("count = (count + 1);"));
+}
+
+#[test]
+fn test_increment_compilation() {
+    let js_code = compile_tfi_to_js("pushpa i = 0; i++;").unwrap();
+    assert_eq!(js_code, "let i = 0;\ni++;");
+    
+    let js_code = compile_tfi_to_js("pushpa i = 3; i--;").unwrap();
+    assert_eq!(js_code, "let i = 3;\ni--;");
+}
+
+#[test]
+fn test_countdown_for_loop() {
+    for update in ["i--", "i -= 1", "i = i - 1"] {
+        let source = format!("eega(pushpa i = 3; i > 0; {}) {{ bahubali(i); }}", update);
+        let js_code = compile_tfi_to_js(&source).unwrap();
+        assert!(js_code.starts_with("for (let i = 3; (i > 0); "));
+        
+        if let Some(stdout) = run_js("countdown", &js_code) {
+            assert_eq!(stdout, "3\n2\n1\n");
+        }
+    }
+}
+
+#[test]
+fn test_error_handling_const_loop_counter_update() {
+    let result = compile_tfi_to_js("eega(rrr i = 3; i > 0; i--) { bahubali(i); }");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_sibling_block_declarations() {
+    let source = r#"
+        pushpa n = 0;
+        pokiri(n < 2) {
+            pushpa i = n * 10;
+            bahubali(i);
+            n++;
+        }
+        pokiri(n < 4) {
+            pushpa i = n * 100;
+            bahubali(i);
+            n++;
+        }
+        eega(rrr j = 0; j < 0; j + 1) {
+            bahubali(j);
+        }
+        rrr j = 7;
+        bahubali(j);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert_eq!(js_code.matches("let i = ").count(), 2);
+    
+    if let Some(stdout) = run_js("sibling_blocks", &js_code) {
+        assert_eq!(stdout, "0\n10\n200\n300\n7\n");
+    }
+}
+
+#[test]
+fn test_error_handling_assignment_in_condition() {
+    let error = compile_tfi_to_js("rrr x = 1; magadheera(x = 5) { bahubali(x); }").unwrap_err();
+    assert!(error.to_string().contains("use '==' to compare values"));
+}
+
+#[test]
+fn test_multi_const_declaration() {
+    let js_code = compile_tfi_to_js("rrr a = 1, b = 2, c = a + b; bahubali(c);").unwrap();
+    assert!(js_code.contains("const a = 1, b = 2, c = (a + b);"));
+    
+    if let Some(stdout) = run_js("multi_const", &js_code) {
+        assert_eq!(stdout, "3\n");
+    }
+}
+
+#[test]
+fn test_error_handling_multi_const_duplicate() {
+    let result = compile_tfi_to_js("rrr a = 1, b = 2, a = 3;");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_keyword_aliases() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    use tfi_lang::lexer::AliasMap;
+    
+    let mut aliases = AliasMap::new();
+    aliases.insert("print", "bahubali").unwrap();
+    let options = CompilationOptions::new().with_aliases(aliases);
+    
+    let aliased = compile_with_options("print(\"hi\");", &options).unwrap();
+    let canonical = compile_with_options("bahubali(\"hi\");", &options).unwrap();
+    assert_eq!(aliased.js_code, canonical.js_code);
+    assert_eq!(aliased.js_code, "console.log(\"hi\");");
+}
+
+#[test]
+fn test_object_literal() {
+    let source = r#"
+        rrr cfg = { name: "x", count: 3 };
+        bahubali(cfg.name, cfg.count);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("const cfg = { name: \"x\", count: 3 };"));
+    
+    if let Some(stdout) = run_js("object_literal", &js_code) {
+        assert_eq!(stdout, "x 3\n");
+    }
+}
+
+#[test]
+fn test_check_mode_writes_nothing() {
+    let dir = std::env::temp_dir().join("tfi_lang_check_mode");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("valid.tfi"), "rrr x = 1; bahubali(x);").unwrap();
+    std::fs::write(dir.join("invalid.tfi"), "bahubali(y);").unwrap();
+    let _ = std::fs::remove_file(dir.join("valid.js"));
+    
+    let run = |file: &str| std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .args(["--check", file])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    
+    assert!(run("valid.tfi").status.success());
+    assert!(!dir.join("valid.js").exists());
+    
+    let output = run("invalid.tfi");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E0001"));
+}
+
+#[test]
+fn test_dry_run_writes_nothing() {
+    let dir = std::env::temp_dir().join("tfi_lang_dry_run");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("valid.tfi"), "rrr x = 1; bahubali(x);").unwrap();
+    std::fs::write(dir.join("invalid.tfi"), "bahubali(y);").unwrap();
+    let _ = std::fs::remove_file(dir.join("valid.js"));
+    
+    let run = |file: &str| std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .args(["--dry-run", file])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    
+    let output = run("valid.tfi");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("would write"));
+    assert!(!dir.join("valid.js").exists());
+    
+    assert!(!run("invalid.tfi").status.success());
+}
+
+#[test]
+fn test_comparison_result_as_condition() {
+    use tfi_lang::compiler::compile_with_details;
+    
+    let result = compile_with_details(r#"
+        rrr x = 10;
+        rrr big = x > 5;
+        magadheera(big) {
+            bahubali("big");
+        }
+    "#).unwrap();
+    assert!(result.js_code.contains("const big = (x > 5);"));
+    assert!(!result.has_warnings(), "unexpected warnings: {:?}", result.warnings);
+    
+    let result = compile_with_details("rrr x = 10; magadheera(x) { bahubali(x); }").unwrap();
+    assert!(result.warnings.iter().any(|w| w.contains("not a boolean")));
+}
+
+#[test]
+fn test_break_out_of_outer_loop() {
+    let source = r#"
+        pushpa i = 0;
+        outer: pokiri(i < 3) {
+            eega(pushpa j = 0; j < 3; j++) {
+                magadheera(i == 1) {
+                    aagipo outer;
+                }
+                bahubali(i, j);
+            }
+            i++;
+        }
+        bahubali("done", i);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("outer: while ((i < 3)) {"));
+    assert!(js_code.contains("break outer;"));
+    
+    if let Some(stdout) = run_js("labeled_break", &js_code) {
+        assert_eq!(stdout, "0 0\n0 1\n0 2\ndone 1\n");
+    }
+}
+
+#[test]
+fn test_unicode_escapes() {
+    let js_code = compile_tfi_to_js(r#"bahubali("caf\u00e9", "\u{1F3AC}");"#).unwrap();
+    assert!(js_code.contains(r#"console.log("café", "🎬")"#));
+    
+    if let Some(stdout) = run_js("unicode_escapes", &js_code) {
+        assert_eq!(stdout, "café 🎬\n");
+    }
+}
+
+#[test]
+fn test_range_loop() {
+    let source = r#"
+        eega i in 0..5 {
+            bahubali(i);
+        }
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("for (let i = 0; i < 5; i++) {"));
+    
+    if let Some(stdout) = run_js("range_loop", &js_code) {
+        assert_eq!(stdout, "0\n1\n2\n3\n4\n");
+    }
+}
+
+#[test]
+fn test_error_handling_range_variable_outside_loop() {
+    let result = compile_tfi_to_js("eega i in 0..3 { bahubali(i); } bahubali(i);");
+    assert!(result.unwrap_err().to_string().contains("E0001"));
+}
+
+#[test]
+fn test_error_handling_break_to_unknown_label() {
+    let result = compile_tfi_to_js("pokiri(1 > 0) { aagipo outer; }");
+    assert!(result.unwrap_err().to_string().contains("E0010"));
+}
+
+#[test]
+fn test_prefer_const_warning() {
+    use tfi_lang::compiler::compile_with_details;
+    
+    let result = compile_with_details("pushpa name = \"tfi\"; bahubali(name);").unwrap();
+    assert!(result.warnings.iter().any(|w| w.contains("prefer 'rrr name'")));
+    
+    let result = compile_with_details("pushpa score = 1; score += 2; bahubali(score);").unwrap();
+    assert!(!result.has_warnings(), "unexpected warnings: {:?}", result.warnings);
+}
+
+#[test]
+fn test_error_handling_increment_undeclared() {
+    let result = compile_tfi_to_js("i++;");
+    assert!(result.is_err());
+    
+    let result = compile_tfi_to_js("rrr i = 0; i++;");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_error_handling_assignment_to_undeclared() {
+    let source = "x = 5;";
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Variable 'x' is not defined"));
+}
+
+#[test]
+fn test_error_handling_assignment_to_const() {
+    let source = r#"
+        rrr x = 1;
+        x = 5;
+    "#;
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("declared with 'rrr'"));
+}
+
+#[test]
+fn test_member_access_compilation() {
+    let source = r#"
+        rrr config = "settings";
+        bahubali(config.name);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("console.log(config.name)"));
+}
+
+#[test]
+fn test_optional_member_access_compilation() {
+    let source = r#"
+        rrr config = { name: "tfi" };
+        bahubali(config?.name);
+        bahubali(config?.missing?.name);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("console.log(config?.name)"));
+    
+    if let Some(output) = run_js("optional_member", &js_code) {
+        assert_eq!(output, "tfi\nundefined\n");
+    }
+}
+
+#[test]
+fn test_string_method_compilation() {
+    let source = r#"
+        rrr name = "  tfi  ";
+        bahubali(name.upper());
+        bahubali(name.trim().slice(1, 3));
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("console.log(name.toUpperCase())"));
+    assert!(js_code.contains("console.log(name.trim().slice(1, 3))"));
+}
+
+#[test]
+fn test_string_index_and_slice() {
+    let source = r#"
+        rrr name = "tollywood";
+        bahubali(name[0]);
+        bahubali(name[1..3]);
+        bahubali("first: " + name[0]);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("console.log(name[0])"));
+    assert!(js_code.contains("console.log(name.slice(1, 3))"));
+    
+    if let Some(output) = run_js("string_index", &js_code) {
+        assert_eq!(output, "t\nol\nfirst: t\n");
+    }
+}
+
+#[test]
+fn test_mixed_int_and_float_arithmetic() {
+    use tfi_lang::types::{TypeEnv, ValueType};
+    
+    let source = "rrr x = 3 + 1.5;\npushpa y = 2;\ny = y * 0.5;\nbahubali(x, x > 4, y);";
+    let statements = parse_program(source).unwrap();
+    let env = TypeEnv::from_program(&statements);
+    assert_eq!(env.get("x"), ValueType::Number);
+    assert_eq!(env.get("y"), ValueType::Number);
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("const x = (3 + 1.5);"));
+    
+    if let Some(output) = run_js("mixed_numbers", &js_code) {
+        assert_eq!(output, "4.5 true 1\n");
+    }
+}
+
+#[test]
+fn test_negative_string_index_and_slice() {
+    let source = r#"
+        rrr name = "tollywood";
+        bahubali(name[-1], name[-4]);
+        bahubali(name[1..-1]);
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("name[name.length - 1]"));
+    assert!(js_code.contains("name.slice(1, -1)"));
+    
+    if let Some(output) = run_js("negative_index", &js_code) {
+        assert_eq!(output, "d w\nollywoo\n");
+    }
+}
+
+#[test]
+fn test_computed_negative_index_unchanged_by_optimize() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"rrr name = "abc"; bahubali(name[0 - 1]);"#;
+    
+    let plain = compile_with_options(source, &CompilationOptions::new()).unwrap().js_code;
+    let folded = compile_with_options(source, &CompilationOptions::new().with_constant_folding()).unwrap().js_code;
+    assert!(!plain.contains("length") && !folded.contains("length"), "{}\n{}", plain, folded);
+    
+    // Only a literal -1 counts from the end, so folding 0 - 1 must not turn it into one
+    if let (Some(plain), Some(folded)) = (run_js("computed_index", &plain), run_js("computed_index_folded", &folded)) {
+        assert_eq!(plain, "undefined\n");
+        assert_eq!(folded, plain);
+    }
+}
+
+#[test]
+fn test_negative_index_calls_object_once() {
+    let source = r#"
+        rrr g = fn() {
+            bahubali("called");
+            vaapas { s: "abc" };
+        };
+        bahubali(g().s[-1]);
+    "#;
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.contains("g().s.at(-1)"), "{}", js_code);
+    
+    if let Some(stdout) = run_js("negative_index_call", &js_code) {
+        assert_eq!(stdout, "called\nc\n");
+    }
+}
+
+#[test]
+fn test_error_handling_member_access_on_undeclared() {
+    let source = "bahubali(config.name);";
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_err());
+    
+    assert!(compile_tfi_to_js("bahubali(config?.name);").is_err());
+}
+
+#[test]
+fn test_error_handling_use_before_declaration() {
+    let source = r#"
+        bahubali(total);
+        rrr total = 1;
+    "#;
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Variable 'total' is not defined"));
+}
+
+#[test]
+fn test_error_handling_block_uses_later_declaration() {
+    // The loop body runs before `limit` exists, which is a temporal dead zone error in JS
+    let source = r#"
+        pushpa i = 0;
+        pokiri(i < 3) {
+            bahubali(limit);
+            i = i + 1;
+        }
+        rrr limit = 3;
+    "#;
+    let result = compile_tfi_to_js(source);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Variable 'limit' is not defined"));
+}
+
+#[test]
+fn test_error_handling_deeply_nested_expression() {
+    let depth = 100_000;
+    let source = format!("bahubali({}1{});", "(".repeat(depth), ")".repeat(depth));
+    let result = compile_tfi_to_js(&source);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("too deeply nested"));
+}
+
 #[test]
 fn test_ast_creation_and_manipulation() {
     let print_stmt = Statement::Print(vec![
@@ -163,7 +1126,7 @@ fn test_ast_creation_and_manipulation() {
     let const_stmt = Statement::Const("x".to_string(), Expression::Number(10));
     let let_stmt = Statement::Let("y".to_string(), Expression::String("world".to_string()));
     
-    let statements = vec![print_stmt, const_stmt, let_stmt];
+    let statements = vec![print_stmt.into(), const_stmt.into(), let_stmt.into()];
     
     // Test that we can generate code from manually created AST
     let js_code = tfi_lang::generator::generate_program(&statements);
@@ -172,6 +1135,30 @@ fn test_ast_creation_and_manipulation() {
     assert!(js_code.contains("let y = \"world\""));
 }
 
+#[test]
+fn test_compile_hand_built_ast() {
+    use tfi_lang::compiler::{CompilationError, CompilationOptions};
+    
+    let statements = vec![
+        Statement::Let("y".to_string(), Expression::Number(1)).into(),
+        Statement::Assign("y".to_string(), Expression::BinaryOp(
+            Box::new(Expression::Identifier("y".to_string())),
+            "+".to_string(),
+            Box::new(Expression::Number(1))
+        )).into(),
+        Statement::Print(vec![Expression::Identifier("y".to_string())]).into(),
+    ];
+    
+    let result = compile_ast(&statements, &CompilationOptions::new()).unwrap();
+    assert_eq!(result.statement_count, 3);
+    assert_eq!(result.js_code, "let y = 1;\ny = (y + 1);\nconsole.log(y);");
+    
+    // Validation still runs on hand-built trees
+    let invalid = vec![Statement::Print(vec![Expression::Identifier("missing".to_string())]).into()];
+    let result = compile_ast(&invalid, &CompilationOptions::new());
+    assert!(matches!(result, Err(CompilationError::ValidationError { .. })));
+}
+
 #[test]
 fn test_expression_generation() {
     let expr = Expression::BinaryOp(
@@ -294,7 +1281,7 @@ fn test_parser_functionality() {
     assert_eq!(statements.len(), 2);
     
     // Check first statement (const declaration)
-    if let Statement::Const(name, expr) = &statements[0] {
+    if let Statement::Const(name, expr) = &statements[0].node {
         assert_eq!(name, "x");
         if let Expression::Number(n) = expr {
             assert_eq!(*n, 42);
@@ -306,7 +1293,7 @@ fn test_parser_functionality() {
     }
     
     // Check second statement (print)
-    if let Statement::Print(expressions) = &statements[1] {
+    if let Statement::Print(expressions) = &statements[1].node {
         assert_eq!(expressions.len(), 1);
         if let Expression::Identifier(name) = &expressions[0] {
             assert_eq!(name, "x");
@@ -337,4 +1324,266 @@ fn test_end_to_end_compilation() {
     assert!(js_code.contains("(current < max_count)"));
     assert!(js_code.contains("(current == max_count)"));
     assert!(js_code.contains("(current + 1)"));
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_cli_dump_tokens() {
+    let dir = std::env::temp_dir().join("tfi_lang_dump_tokens_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("prog.tfi");
+    std::fs::write(&input, "rrr x = 1;").unwrap();
+    
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .arg("--dump-tokens")
+        .arg(&input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, vec![
+        "Const @ 0..3 \"rrr\"",
+        "Identifier(\"x\") @ 4..5 \"x\"",
+        "Assign @ 6..7 \"=\"",
+        "Number(1) @ 8..9 \"1\"",
+        "Semicolon @ 9..10 \";\"",
+    ]);
+    
+    // A string is one token, a comment is skipped, and text that isn't a token is shown
+    std::fs::write(&input, "bahubali(\"hi there\"); // say hi\n%").unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .arg("--dump-tokens")
+        .arg(&input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, vec![
+        "Print @ 0..8 \"bahubali\"",
+        "LParen @ 8..9 \"(\"",
+        "String(\"hi there\") @ 9..19 \"\\\"hi there\\\"\"",
+        "RParen @ 19..20 \")\"",
+        "Semicolon @ 20..21 \";\"",
+        "Error @ 32..33 \"%\"",
+    ]);
+}
+
+#[test]
+fn test_out_dir_mirrors_source_tree() {
+    let dir = std::env::temp_dir().join("tfi_lang_out_dir_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src/pages/admin")).unwrap();
+    std::fs::write(dir.join("src/main.tfi"), "bahubali(\"main\");").unwrap();
+    std::fs::write(dir.join("src/pages/admin/users.tfi"), "rrr n = 2; bahubali(n);").unwrap();
+    std::fs::write(dir.join("src/notes.txt"), "not a source file").unwrap();
+    
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .args(["--out-dir", "dist", "src"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 compiled, 0 failed"));
+    
+    let main_js = std::fs::read_to_string(dir.join("dist/main.js")).unwrap();
+    assert!(main_js.contains("console.log(\"main\")"));
+    let users_js = std::fs::read_to_string(dir.join("dist/pages/admin/users.js")).unwrap();
+    assert!(users_js.contains("const n = 2"));
+    assert!(!dir.join("dist/notes.js").exists());
+    
+    // A failing file is reported without stopping the others
+    std::fs::write(dir.join("src/pages/broken.tfi"), "bahubali(missing);").unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .args(["--out-dir", "dist", "src"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 compiled, 1 failed"));
+}
+
+#[test]
+fn test_out_dir_continues_past_failures() {
+    let dir = std::env::temp_dir().join("tfi_lang_out_dir_failures_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    // Sorted first, so good.tfi is only built if the build carries on past a failure
+    std::fs::write(dir.join("src/bad.tfi"), "bahubali(missing);").unwrap();
+    std::fs::write(dir.join("src/good.tfi"), "bahubali(\"good\");").unwrap();
+    
+    let build = |extra: &[&str]| std::process::Command::new(env!("CARGO_BIN_EXE_tfi-lang"))
+        .args(extra)
+        .args(["--out-dir", "dist", "src"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    
+    let output = build(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 compiled, 1 failed"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bad.tfi"));
+    let good_js = std::fs::read_to_string(dir.join("dist/good.js")).unwrap();
+    assert!(good_js.contains("console.log(\"good\")"));
+    
+    // There is no mode that stops early, so there is no flag to ask for carrying on
+    let output = build(&["--keep-going"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown option: --keep-going"));
+}
+
+#[test]
+fn test_minify_with_mangled_names() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr greeting = "hello";
+        pushpa total = 0;
+        eega i in 0..3 {
+            total += i;
+        }
+        rrr config = { total: total };
+        bahubali(greeting, config.total, greeting.upper());
+    "#;
+    
+    let options = CompilationOptions::new().with_minification().with_mangled_names();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(!js_code.contains("greeting"));
+    assert!(js_code.contains("const a = \"hello\""), "got: {}", js_code);
+    assert!(js_code.contains("const c = { total: b }"), "got: {}", js_code);
+    assert!(js_code.contains(".total"));
+    assert!(js_code.contains("toUpperCase"));
+    
+    // Mangling alone leaves names intact
+    let js_code_unminified = compile_with_options(source, &CompilationOptions::new().with_mangled_names()).unwrap().js_code;
+    assert!(js_code_unminified.contains("greeting"));
+    
+    if let Some(output) = run_js("mangled", &js_code) {
+        assert_eq!(output, "hello 3 HELLO\n");
+    }
+}
+
+#[test]
+fn test_mangle_keeps_names_used_by_raw_js() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = "rrr total = 3; rajini { console.log(total); }";
+    let options = CompilationOptions::new().with_minification().with_mangled_names();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.contains("const total = 3"), "got: {}", js_code);
+    
+    if let Some(output) = run_js("mangled_raw_js", &js_code) {
+        assert_eq!(output, "3\n");
+    }
+}
+
+#[test]
+fn test_auto_chain_comparisons() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr x = 15;
+        magadheera(0 < x < 10) {
+            bahubali("inside");
+        } karthikeya {
+            bahubali("outside");
+        }
+    "#;
+    let options = CompilationOptions::new().with_auto_chain();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.contains("if (((0 < x) && (x < 10)))"), "got: {}", js_code);
+    
+    if let Some(output) = run_js("auto_chain", &js_code) {
+        assert_eq!(output, "outside\n");
+    }
+    
+    let error = compile_with_options("rrr s = \"m\"; bahubali(\"a\" < s.trim() < \"z\");", &options).unwrap_err();
+    assert!(error.to_string().contains("E0007"), "got: {}", error);
+}
+
+#[test]
+fn test_stringify_objects_option() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let source = r#"
+        rrr hero = { name: "pushpa", year: 2021 };
+        bahubali("hero:", hero);
+        bahubali({ a: 1 });
+    "#;
+    let options = CompilationOptions::new().with_stringified_objects();
+    let js_code = compile_with_options(source, &options).unwrap().js_code;
+    assert!(js_code.contains("console.log(\"hero:\", JSON.stringify(hero))"), "got: {}", js_code);
+    assert!(js_code.contains("console.log(JSON.stringify({ a: 1 }))"));
+    
+    if let Some(output) = run_js("stringify_objects", &js_code) {
+        assert_eq!(output, "hero: {\"name\":\"pushpa\",\"year\":2021}\n{\"a\":1}\n");
+    }
+}
+
+#[test]
+fn test_raw_print_block() {
+    let source = r#"
+        bahubali_block {
+  *** "Pushpa" ***
+  { fire } `nahi` ${flower} C:\movies // not a comment
+        }
+        bahubali("after");
+    "#;
+    
+    let js_code = compile_tfi_to_js(source).unwrap();
+    assert!(js_code.starts_with("console.log(`"), "got: {}", js_code);
+    
+    if let Some(output) = run_js("raw_print", &js_code) {
+        assert_eq!(output, "  *** \"Pushpa\" ***\n  { fire } `nahi` ${flower} C:\\movies // not a comment\nafter\n");
+    }
+}
+
+#[test]
+fn test_raw_js_block() {
+    use tfi_lang::compiler::compile_with_details;
+    
+    let source = r#"
+        rrr hero = "Rajini";
+        rajini { const style = { hero: hero + "}" }; console.log(JSON.stringify(style)); }
+        bahubali("after");
+    "#;
+    
+    let result = compile_with_details(source).unwrap();
+    assert!(result.js_code.contains("\nconst style = { hero: hero + \"}\" }; console.log(JSON.stringify(style));\n"));
+    assert!(result.warnings.iter().any(|w| w.contains("rajini block")));
+    
+    if let Some(output) = run_js("raw_js", &result.js_code) {
+        assert_eq!(output, "{\"hero\":\"Rajini}\"}\nafter\n");
+    }
+}
+
+#[test]
+fn test_import_inlines_declarations() {
+    use tfi_lang::compiler::{compile_with_options, CompilationOptions};
+    
+    let dir = std::env::temp_dir().join("tfi_lang_import_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::write(dir.join("lib/utils.tfi"), "rrr greeting = \"Namaste\";\nrrr times = 2;").unwrap();
+    std::fs::write(dir.join("main.tfi"), "import \"lib/utils.tfi\";\neega i in 0..times {\n    bahubali(greeting);\n}").unwrap();
+    std::fs::write(dir.join("clash.tfi"), "import \"lib/utils.tfi\";\nrrr times = 3;").unwrap();
+    
+    let compile = |file: &str| {
+        let path = dir.join(file);
+        let source = std::fs::read_to_string(&path).unwrap();
+        compile_with_options(&source, &CompilationOptions::new().with_source_path(&path))
+    };
+    
+    let js_code = compile("main.tfi").unwrap().js_code;
+    assert!(js_code.starts_with("const greeting = \"Namaste\";\nconst times = 2;"), "got: {}", js_code);
+    if let Some(output) = run_js("import", &js_code) {
+        assert_eq!(output, "Namaste\nNamaste\n");
+    }
+    
+    // Declarations from every file share one scope
+    assert!(compile("clash.tfi").unwrap_err().to_string().contains("E0002"));
+    
+    // Without a file to resolve against, the import is an error
+    let error = compile_tfi_to_js("import \"lib/utils.tfi\";").unwrap_err();
+    assert!(error.to_string().contains("E0013"));
+}